@@ -0,0 +1,70 @@
+//! Bulk operation HTTP handlers: launch, kill, snapshot and restore groups of profiles.
+
+use super::{ApiResult, ApiState};
+use crate::commands::bulk::{self, BulkReport, BulkSelector};
+use crate::commands::bulk_import::{self, BulkImportReport, BulkImportRequest};
+use axum::{extract::State, http::StatusCode, Json};
+
+#[derive(serde::Deserialize)]
+pub struct BulkSnapshotReq {
+    #[serde(flatten)]
+    selector: BulkSelector,
+    name: String,
+}
+
+pub async fn bulk_launch(
+    State(state): State<ApiState>,
+    Json(selector): Json<BulkSelector>,
+) -> ApiResult<Json<BulkReport>> {
+    let report = bulk::core_bulk_launch(&state, &selector)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    Ok(Json(report))
+}
+
+pub async fn bulk_kill(
+    State(state): State<ApiState>,
+    Json(selector): Json<BulkSelector>,
+) -> ApiResult<Json<BulkReport>> {
+    let report = bulk::core_bulk_kill(&state, &selector)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    Ok(Json(report))
+}
+
+pub async fn bulk_snapshot(
+    State(state): State<ApiState>,
+    Json(req): Json<BulkSnapshotReq>,
+) -> ApiResult<Json<BulkReport>> {
+    let report = bulk::core_bulk_snapshot(&state, &req.selector, &req.name)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    Ok(Json(report))
+}
+
+pub async fn bulk_restore(
+    State(state): State<ApiState>,
+    Json(req): Json<BulkSnapshotReq>,
+) -> ApiResult<Json<BulkReport>> {
+    let report = bulk::core_bulk_restore(&state, &req.selector, &req.name)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    Ok(Json(report))
+}
+
+/// Import profiles from CSV / JSON lines. `200` for a dry run, `201` when the rows
+/// were added, `422` (with the same report) when invalid rows blocked the commit.
+pub async fn bulk_import(
+    State(state): State<ApiState>,
+    Json(req): Json<BulkImportRequest>,
+) -> ApiResult<(StatusCode, Json<BulkImportReport>)> {
+    let report = bulk_import::core_bulk_import(&state, &req).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let status = if report.dry_run {
+        StatusCode::OK
+    } else if report.committed {
+        StatusCode::CREATED
+    } else {
+        StatusCode::UNPROCESSABLE_ENTITY
+    };
+    Ok((status, Json(report)))
+}
@@ -0,0 +1,53 @@
+//! Profile export/import HTTP handlers.
+
+use super::{body_to_temp_file, profile_error, stream_temp_file, ApiResult, ApiState};
+use crate::commands::bundles::{self, ImportOptions, ImportReport};
+use crate::commands::snapshot_archive::TempArchive;
+use axum::{
+    body::Body,
+    extract::{Path as AxumPath, Query, State},
+    http::{header, StatusCode},
+    Json,
+};
+
+#[derive(serde::Deserialize)]
+pub struct ExportQuery {
+    /// Include the user data dir (minus caches and lock files).
+    #[serde(default)]
+    include_data: bool,
+}
+
+/// Download a profile bundle (zip).
+pub async fn export_profile(
+    State(state): State<ApiState>,
+    AxumPath(id): AxumPath<String>,
+    Query(query): Query<ExportQuery>,
+) -> ApiResult<([(header::HeaderName, String); 3], Body)> {
+    let tmp = TempArchive::new();
+    bundles::core_export_profile(&state, &id, query.include_data, tmp.path().to_path_buf())
+        .await
+        .map_err(profile_error)?;
+    let (len, body) = stream_temp_file(tmp).await?;
+    let headers = [
+        (header::CONTENT_TYPE, "application/zip".to_string()),
+        (header::CONTENT_LENGTH, len.to_string()),
+        (
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}.browsion.zip\"", id.replace('"', "")),
+        ),
+    ];
+    Ok((headers, body))
+}
+
+/// Import a bundle (the request body is the zip). Options come from the query string.
+pub async fn import_profile(
+    State(state): State<ApiState>,
+    Query(opts): Query<ImportOptions>,
+    body: Body,
+) -> ApiResult<(StatusCode, Json<ImportReport>)> {
+    let tmp = body_to_temp_file(&state, body).await?;
+    let report = bundles::core_import_profile(&state, tmp.path(), opts)
+        .await
+        .map_err(profile_error)?;
+    Ok((StatusCode::CREATED, Json(report)))
+}
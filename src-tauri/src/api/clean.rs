@@ -0,0 +1,18 @@
+//! Profile cache / history cleanup HTTP handler.
+
+use super::{profile_error, ApiResult, ApiState};
+use crate::commands::clean::{self, CleanReport, CleanRequest};
+use axum::{
+    extract::{Path as AxumPath, State},
+    Json,
+};
+
+/// Clean the selected categories of a profile (`{}` cleans the caches).
+pub async fn clean_profile(
+    State(state): State<ApiState>,
+    AxumPath(id): AxumPath<String>,
+    Json(req): Json<CleanRequest>,
+) -> ApiResult<Json<CleanReport>> {
+    let report = clean::core_clean_profile(&state, &id, &req).await.map_err(profile_error)?;
+    Ok(Json(report))
+}
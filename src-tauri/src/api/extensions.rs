@@ -0,0 +1,68 @@
+//! Extension library HTTP handlers: list, install, inspect and remove extensions.
+
+use super::{ApiResult, ApiState};
+use crate::commands::extensions::{self, ExtensionEntry};
+use axum::{
+    extract::{Path as AxumPath, State},
+    http::StatusCode,
+    Json,
+};
+use std::path::PathBuf;
+
+#[derive(serde::Deserialize)]
+pub struct InstallExtensionReq {
+    /// Local `.zip` or `.crx` file.
+    path: PathBuf,
+    /// Library id; defaults to a slug of the manifest name.
+    #[serde(default)]
+    id: Option<String>,
+}
+
+pub async fn list_extensions(State(state): State<ApiState>) -> ApiResult<Json<Vec<ExtensionEntry>>> {
+    let config = state.config.read().clone();
+    let entries = extensions::core_list_extensions(&config)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    Ok(Json(entries))
+}
+
+pub async fn get_extension(
+    State(state): State<ApiState>,
+    AxumPath(id): AxumPath<String>,
+) -> ApiResult<Json<ExtensionEntry>> {
+    let config = state.config.read().clone();
+    extensions::core_get_extension(&config, &id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+        .map(Json)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Extension '{}' not found", id)))
+}
+
+pub async fn install_extension(
+    State(state): State<ApiState>,
+    Json(req): Json<InstallExtensionReq>,
+) -> ApiResult<Json<ExtensionEntry>> {
+    let config = state.config.read().clone();
+    let entry = extensions::core_install_extension(&config, req.path, req.id)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    Ok(Json(entry))
+}
+
+pub async fn remove_extension(
+    State(state): State<ApiState>,
+    AxumPath(id): AxumPath<String>,
+) -> ApiResult<StatusCode> {
+    let config = state.config.read().clone();
+    if extensions::core_get_extension(&config, &id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+        .is_none()
+    {
+        return Err((StatusCode::NOT_FOUND, format!("Extension '{}' not found", id)));
+    }
+    extensions::core_remove_extension(&config, &id)
+        .await
+        .map_err(|e| (StatusCode::CONFLICT, e))?;
+    Ok(StatusCode::NO_CONTENT)
+}
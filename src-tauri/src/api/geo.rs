@@ -0,0 +1,31 @@
+//! Proxy geography consistency HTTP handler.
+
+use super::{ApiResult, ApiState};
+use crate::commands::geo::{self, GeoCheckReport};
+use axum::{
+    extract::{Path as AxumPath, Query, State},
+    http::StatusCode,
+    Json,
+};
+
+#[derive(serde::Deserialize)]
+pub struct GeoCheckQuery {
+    /// Write the timezone and language matching the exit IP back to the profile.
+    #[serde(default)]
+    auto_align: bool,
+}
+
+/// Resolve the profile's proxy exit IP and report timezone / language mismatches.
+pub async fn check_profile_geo(
+    State(state): State<ApiState>,
+    AxumPath(id): AxumPath<String>,
+    Query(query): Query<GeoCheckQuery>,
+) -> ApiResult<Json<GeoCheckReport>> {
+    if !state.config.read().profiles.iter().any(|p| p.id == id) {
+        return Err((StatusCode::NOT_FOUND, "Profile not found".to_string()));
+    }
+    let report = geo::core_check_profile_geo(&state, &id, query.auto_align)
+        .await
+        .map_err(|e| (StatusCode::BAD_GATEWAY, e))?;
+    Ok(Json(report))
+}
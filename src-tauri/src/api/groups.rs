@@ -0,0 +1,80 @@
+//! Profile group HTTP handlers.
+
+use super::{ApiResult, ApiState};
+use crate::commands::groups::{self, MoveProfileRequest};
+use crate::config::schema::{BrowserProfile, ProfileGroup};
+use axum::{
+    extract::{Path as AxumPath, State},
+    http::StatusCode,
+    Json,
+};
+
+pub async fn list_groups(State(state): State<ApiState>) -> ApiResult<Json<Vec<ProfileGroup>>> {
+    Ok(Json(state.config.read().groups.clone()))
+}
+
+pub async fn get_group(
+    State(state): State<ApiState>,
+    AxumPath(id): AxumPath<String>,
+) -> ApiResult<Json<ProfileGroup>> {
+    state
+        .config
+        .read()
+        .groups
+        .iter()
+        .find(|g| g.id == id)
+        .cloned()
+        .map(Json)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Group not found".to_string()))
+}
+
+/// Create a group; the id is generated when omitted.
+pub async fn create_group(
+    State(state): State<ApiState>,
+    Json(group): Json<ProfileGroup>,
+) -> ApiResult<(StatusCode, Json<ProfileGroup>)> {
+    if state.config.read().groups.iter().any(|g| g.id == group.id) {
+        return Err((StatusCode::CONFLICT, format!("Group {} already exists", group.id)));
+    }
+    let group = groups::core_save_group(&state, group).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    Ok((StatusCode::CREATED, Json(group)))
+}
+
+/// Replace a group (rename, recolor, move under another `parent`, ...).
+pub async fn update_group(
+    State(state): State<ApiState>,
+    AxumPath(id): AxumPath<String>,
+    Json(mut group): Json<ProfileGroup>,
+) -> ApiResult<Json<ProfileGroup>> {
+    if !state.config.read().groups.iter().any(|g| g.id == id) {
+        return Err((StatusCode::NOT_FOUND, "Group not found".to_string()));
+    }
+    group.id = id;
+    let group = groups::core_save_group(&state, group).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    Ok(Json(group))
+}
+
+/// Delete a group; its subgroups and profiles move to its parent.
+pub async fn delete_group(
+    State(state): State<ApiState>,
+    AxumPath(id): AxumPath<String>,
+) -> ApiResult<StatusCode> {
+    match groups::core_delete_group(&state, &id) {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err((StatusCode::NOT_FOUND, "Group not found".to_string())),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e)),
+    }
+}
+
+/// Move a profile into a group (`{"group": "<id>"}`) or out of all groups (`{"group": null}`).
+pub async fn move_profile(
+    State(state): State<ApiState>,
+    AxumPath(id): AxumPath<String>,
+    Json(req): Json<MoveProfileRequest>,
+) -> ApiResult<Json<BrowserProfile>> {
+    if !state.config.read().profiles.iter().any(|p| p.id == id) {
+        return Err((StatusCode::NOT_FOUND, "Profile not found".to_string()));
+    }
+    let profile = groups::core_move_profile(&state, &id, req.group).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    Ok(Json(profile))
+}
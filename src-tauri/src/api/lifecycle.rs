@@ -1,8 +1,11 @@
 //! Browser lifecycle HTTP handlers: launch, kill, running.
 
-use super::{ApiResult, ApiState};
+use super::{profile_error, ApiResult, ApiState};
 use crate::commands::get_effective_chrome_path_from_config;
+use crate::config::LaunchOverrides;
+use crate::error::BrowsionError;
 use axum::{
+    body::Bytes,
     extract::{Path as AxumPath, State},
     http::StatusCode,
     routing::{get, post},
@@ -20,6 +23,8 @@ pub fn router() -> Router<ApiState> {
 pub struct LaunchResponse {
     pub pid: u32,
     pub cdp_port: u16,
+    /// Launch flag and fingerprint warnings; empty for attached browsers.
+    pub warnings: Vec<String>,
 }
 
 #[derive(serde::Serialize)]
@@ -30,10 +35,19 @@ pub struct RunningBrowser {
     launched_at: u64,
 }
 
+/// Launch a profile. The optional JSON body (`LaunchOverrides`) changes window
+/// settings or startup URLs for this launch only; an empty body launches as stored.
 pub async fn launch_profile(
     State(state): State<ApiState>,
     AxumPath(profile_id): AxumPath<String>,
+    body: Bytes,
 ) -> ApiResult<Json<LaunchResponse>> {
+    let overrides: LaunchOverrides = if body.iter().all(u8::is_ascii_whitespace) {
+        LaunchOverrides::default()
+    } else {
+        serde_json::from_slice(&body)
+            .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid launch overrides: {}", e)))?
+    };
     let config = state.config.read().clone();
     let _profile = config
         .profiles
@@ -49,11 +63,15 @@ pub async fn launch_profile(
     let chrome_path = get_effective_chrome_path_from_config(&config)
         .await
         .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
-    let (pid, cdp_port) = state
+    let launched = state
         .process_manager
-        .launch_profile(&profile_id, &config, &chrome_path)
+        .launch_profile_with(&profile_id, &config, &chrome_path, &overrides)
         .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        .map_err(|e| match e {
+            BrowsionError::ProfileLocked(_) | BrowsionError::Conflict(_) => (StatusCode::CONFLICT, e.to_string()),
+            BrowsionError::Validation(_) => (StatusCode::BAD_REQUEST, e.to_string()),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        })?;
     {
         let mut config = state.config.write();
         config.recent_profiles.retain(|id| id != &profile_id);
@@ -66,14 +84,19 @@ pub async fn launch_profile(
         }
     }
     // Persist session for reconnect across Tauri restarts
-    let pid_id = profile_id.clone();
-    tokio::spawn(async move {
-        if let Err(e) = crate::process::sessions_persist::save_session(&pid_id, pid, cdp_port).await {
-            tracing::warn!("Failed to persist session for {}: {}", pid_id, e);
-        }
-    });
+    if let Some(info) = state.process_manager.get_process_info(&profile_id) {
+        tokio::spawn(async move {
+            if let Err(e) = crate::process::sessions_persist::save_session(&info).await {
+                tracing::warn!("Failed to persist session for {}: {}", info.profile_id, e);
+            }
+        });
+    }
     state.emit("browser-status-changed");
-    Ok(Json(LaunchResponse { pid, cdp_port }))
+    Ok(Json(LaunchResponse {
+        pid: launched.pid,
+        cdp_port: launched.cdp_port,
+        warnings: launched.warnings,
+    }))
 }
 
 pub async fn kill_profile(
@@ -96,6 +119,26 @@ pub async fn kill_profile(
     Ok(StatusCode::NO_CONTENT)
 }
 
+pub async fn attach_profile(
+    State(state): State<ApiState>,
+    AxumPath(profile_id): AxumPath<String>,
+) -> ApiResult<Json<LaunchResponse>> {
+    let (pid, cdp_port) = crate::commands::discovery::core_attach_profile(&state, &profile_id)
+        .await
+        .map_err(profile_error)?;
+    Ok(Json(LaunchResponse {
+        pid,
+        cdp_port,
+        warnings: Vec::new(),
+    }))
+}
+
+pub async fn discover_browsers(
+    State(state): State<ApiState>,
+) -> Json<Vec<crate::process::discovery::DiscoveredBrowser>> {
+    Json(crate::commands::discovery::core_discover_browsers(&state))
+}
+
 pub async fn get_running_browsers(State(state): State<ApiState>) -> Json<Vec<RunningBrowser>> {
     let ids = state.process_manager.get_running_profiles();
     let browsers: Vec<RunningBrowser> = ids
@@ -2,14 +2,27 @@
 //!
 //! Profile CRUD, browser launch/kill, settings, snapshots, WebSocket, health check.
 
+pub mod bulk;
+pub mod bundles;
+pub mod clean;
+pub mod extensions;
+pub mod geo;
+pub mod groups;
 pub mod lifecycle;
+pub mod storage;
+pub mod templates;
+pub mod trash;
 pub mod ws;
 
-use crate::config::{validation, BrowserProfile};
+use crate::commands::groups::check_profile_group;
+use crate::commands::search::{self, ProfileQuery};
+use crate::commands::SavedProfile;
+use crate::config::{patch, validation, BrowserProfile};
 use crate::state::AppState;
 use axum::{
-    extract::{Path as AxumPath, State},
-    http::StatusCode,
+    body::{Body, Bytes},
+    extract::{DefaultBodyLimit, Path as AxumPath, Query, State},
+    http::{header, HeaderMap, HeaderName, HeaderValue, StatusCode},
     routing::{delete, get, post},
     Json, Router,
 };
@@ -18,15 +31,22 @@ use std::sync::Arc;
 pub type ApiState = Arc<AppState>;
 pub type ApiResult<T> = Result<T, (StatusCode, String)>;
 
+const X_TOTAL_COUNT: HeaderName = HeaderName::from_static("x-total-count");
+const X_NEXT_CURSOR: HeaderName = HeaderName::from_static("x-next-cursor");
+
 // ---------------------------------------------------------------------------
 // Profile CRUD routes
 // ---------------------------------------------------------------------------
 
+/// List profiles matching the query. The body is one page of profiles (each with
+/// `is_running`); `X-Total-Count` holds the number of matches and `X-Next-Cursor`
+/// the cursor for the next page, if any.
 async fn list_profiles(
     State(state): State<ApiState>,
-) -> Result<Json<Vec<serde_json::Value>>, (StatusCode, String)> {
-    let config = state.config.read();
-    let profiles: Vec<serde_json::Value> = config
+    Query(query): Query<ProfileQuery>,
+) -> ApiResult<(HeaderMap, Json<Vec<serde_json::Value>>)> {
+    let page = search::core_list_profiles(&state, &query).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let profiles: Vec<serde_json::Value> = page
         .profiles
         .iter()
         .map(|p| {
@@ -34,38 +54,68 @@ async fn list_profiles(
             if let Some(obj) = v.as_object_mut() {
                 obj.insert(
                     "is_running".to_string(),
-                    serde_json::Value::Bool(state.process_manager.is_running(&p.id)),
+                    serde_json::Value::Bool(page.running.contains(&p.id)),
                 );
             }
             v
         })
         .collect();
-    Ok(Json(profiles))
+
+    let mut headers = HeaderMap::new();
+    headers.insert(X_TOTAL_COUNT, HeaderValue::from(page.total));
+    if let Some(cursor) = page.next_cursor.as_deref().and_then(|c| HeaderValue::from_str(c).ok()) {
+        headers.insert(X_NEXT_CURSOR, cursor);
+    }
+    Ok((headers, Json(profiles)))
 }
 
-#[derive(serde::Deserialize)]
-struct CreateProfileReq {
-    profile: BrowserProfile,
+/// Status code for a profile validation / conflict error.
+pub(crate) fn profile_error(e: crate::error::BrowsionError) -> (StatusCode, String) {
+    let status = match e {
+        crate::error::BrowsionError::ProfileNotFound(_) | crate::error::BrowsionError::BrowserNotRunning(_) => {
+            StatusCode::NOT_FOUND
+        }
+        crate::error::BrowsionError::Conflict(_) | crate::error::BrowsionError::ProfileLocked(_) => {
+            StatusCode::CONFLICT
+        }
+        crate::error::BrowsionError::Validation(_) => StatusCode::BAD_REQUEST,
+        crate::error::BrowsionError::CdpConnection(_)
+        | crate::error::BrowsionError::CdpCommand(_)
+        | crate::error::BrowsionError::Timeout(_) => StatusCode::BAD_GATEWAY,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, e.to_string())
 }
 
+/// Create a profile. The body is the profile itself (the older `{"profile": {...}}`
+/// wrapper is also accepted); `id` and `user_data_dir` may be omitted.
 async fn add_profile(
     State(state): State<ApiState>,
-    Json(req): Json<CreateProfileReq>,
-) -> ApiResult<Json<BrowserProfile>> {
-    validation::validate_profile(&req.profile).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    Json(body): Json<serde_json::Value>,
+) -> ApiResult<(StatusCode, Json<SavedProfile>)> {
+    let body = match body {
+        serde_json::Value::Object(mut obj) if obj.len() == 1 && obj.contains_key("profile") => {
+            obj.remove("profile").unwrap_or_default()
+        }
+        other => other,
+    };
+    let mut profile: BrowserProfile = serde_json::from_value(body)
+        .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, format!("Invalid profile: {}", e)))?;
 
     let mut config = state.config.write();
-    config.profiles.push(req.profile.clone());
+    crate::commands::prepare_new_profile(&mut profile, &config).map_err(profile_error)?;
+    config.profiles.push(profile.clone());
     crate::config::save_config(&config).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let saved = SavedProfile::new(profile, &config);
     drop(config);
     state.emit("profiles-changed");
-    Ok(Json(req.profile))
+    Ok((StatusCode::CREATED, Json(saved)))
 }
 
 async fn get_profile(
     State(state): State<ApiState>,
     AxumPath(id): AxumPath<String>,
-) -> ApiResult<Json<BrowserProfile>> {
+) -> ApiResult<([(HeaderName, String); 1], Json<BrowserProfile>)> {
     let config = state.config.read();
     let profile = config
         .profiles
@@ -73,14 +123,40 @@ async fn get_profile(
         .find(|p| p.id == id)
         .cloned()
         .ok_or((StatusCode::NOT_FOUND, "Profile not found".to_string()))?;
-    Ok(Json(profile))
+    Ok(with_etag(profile))
+}
+
+/// Response body plus its `ETag` header.
+fn with_etag(profile: BrowserProfile) -> ([(HeaderName, String); 1], Json<BrowserProfile>) {
+    ([(header::ETAG, patch::profile_etag(&profile))], Json(profile))
+}
+
+/// Saved profile with its warnings, plus the profile's `ETag` header.
+fn saved_with_etag(saved: SavedProfile) -> ([(HeaderName, String); 1], Json<SavedProfile>) {
+    ([(header::ETAG, patch::profile_etag(&saved.profile))], Json(saved))
+}
+
+/// Reject with 412 when an `If-Match` header is present and does not match `current`.
+fn check_if_match(headers: &HeaderMap, current: &BrowserProfile) -> ApiResult<()> {
+    let Some(value) = headers.get(header::IF_MATCH) else {
+        return Ok(());
+    };
+    let etag = patch::profile_etag(current);
+    match value.to_str() {
+        Ok(if_match) if patch::etag_matches(if_match, &etag) => Ok(()),
+        _ => Err((
+            StatusCode::PRECONDITION_FAILED,
+            format!("Profile was modified; current ETag is {}", etag),
+        )),
+    }
 }
 
 async fn update_profile(
     State(state): State<ApiState>,
     AxumPath(id): AxumPath<String>,
+    headers: HeaderMap,
     Json(profile): Json<BrowserProfile>,
-) -> ApiResult<Json<BrowserProfile>> {
+) -> ApiResult<([(HeaderName, String); 1], Json<SavedProfile>)> {
     if profile.id != id {
         return Err((
             StatusCode::BAD_REQUEST,
@@ -90,38 +166,69 @@ async fn update_profile(
     validation::validate_profile(&profile).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
 
     let mut config = state.config.write();
-    if let Some(pos) = config.profiles.iter().position(|p| p.id == id) {
-        config.profiles[pos] = profile.clone();
-        crate::config::save_config(&config)
-            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-        drop(config);
-        state.emit("profiles-changed");
-        Ok(Json(profile))
-    } else {
-        Err((StatusCode::NOT_FOUND, "Profile not found".to_string()))
-    }
+    let pos = config
+        .profiles
+        .iter()
+        .position(|p| p.id == id)
+        .ok_or((StatusCode::NOT_FOUND, "Profile not found".to_string()))?;
+    check_if_match(&headers, &config.profiles[pos])?;
+    check_profile_group(&config, &profile).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    validation::check_profile_conflicts(&config.profiles, &profile, Some(&id)).map_err(profile_error)?;
+    config.profiles[pos] = profile.clone();
+    crate::config::save_config(&config)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let saved = SavedProfile::new(profile, &config);
+    drop(config);
+    state.emit("profiles-changed");
+    Ok(saved_with_etag(saved))
 }
 
-async fn delete_profile(
+/// Apply a JSON Merge Patch (RFC 7396) to a profile. With `If-Match`, the patch
+/// only applies if the profile still has that ETag; the check, merge and save all
+/// happen under the config write lock.
+async fn patch_profile(
     State(state): State<ApiState>,
     AxumPath(id): AxumPath<String>,
-) -> ApiResult<StatusCode> {
-    if state.process_manager.is_running(&id) {
-        return Err((
-            StatusCode::CONFLICT,
-            "Cannot delete profile while it is running".to_string(),
-        ));
-    }
+    headers: HeaderMap,
+    Json(body): Json<serde_json::Value>,
+) -> ApiResult<([(HeaderName, String); 1], Json<SavedProfile>)> {
     let mut config = state.config.write();
-    let before = config.profiles.len();
-    config.profiles.retain(|p| p.id != id);
-    if config.profiles.len() == before {
-        return Err((StatusCode::NOT_FOUND, "Profile not found".to_string()));
-    }
+    let pos = config
+        .profiles
+        .iter()
+        .position(|p| p.id == id)
+        .ok_or((StatusCode::NOT_FOUND, "Profile not found".to_string()))?;
+    check_if_match(&headers, &config.profiles[pos])?;
+
+    let profile = patch::apply_profile_patch(&config.profiles[pos], &body).map_err(profile_error)?;
+    validation::validate_profile(&profile).map_err(profile_error)?;
+    check_profile_group(&config, &profile).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    validation::check_profile_conflicts(&config.profiles, &profile, Some(&id)).map_err(profile_error)?;
+    config.profiles[pos] = profile.clone();
     crate::config::save_config(&config)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let saved = SavedProfile::new(profile, &config);
     drop(config);
     state.emit("profiles-changed");
+    Ok(saved_with_etag(saved))
+}
+
+#[derive(serde::Deserialize)]
+struct DeleteProfileQuery {
+    /// Also move the data directory into the trash.
+    #[serde(default)]
+    move_data: bool,
+}
+
+/// Move a profile to the trash (see `/api/trash`).
+async fn delete_profile(
+    State(state): State<ApiState>,
+    AxumPath(id): AxumPath<String>,
+    Query(query): Query<DeleteProfileQuery>,
+) -> ApiResult<StatusCode> {
+    crate::commands::trash::core_trash_profile(&state, &id, query.move_data)
+        .await
+        .map_err(profile_error)?;
     Ok(StatusCode::NO_CONTENT)
 }
 
@@ -147,19 +254,26 @@ async fn register_external_profile(
         ));
     }
 
+    let user_data_dir = {
+        let config = state.config.read();
+        config
+            .profiles
+            .iter()
+            .find(|p| p.id == req.profile_id)
+            .map(|p| p.user_data_dir.clone())
+    };
     state
         .process_manager
-        .register_external(&req.profile_id, req.pid, req.cdp_port);
+        .register_external(&req.profile_id, req.pid, req.cdp_port, user_data_dir);
 
     // Persist session for reconnect
-    let profile_id = req.profile_id.clone();
-    let pid = req.pid;
-    let cdp_port = req.cdp_port;
-    tokio::spawn(async move {
-        if let Err(e) = crate::process::sessions_persist::save_session(&profile_id, pid, cdp_port).await {
-            tracing::warn!("Failed to persist session for {}: {}", profile_id, e);
-        }
-    });
+    if let Some(info) = state.process_manager.get_process_info(&req.profile_id) {
+        tokio::spawn(async move {
+            if let Err(e) = crate::process::sessions_persist::save_session(&info).await {
+                tracing::warn!("Failed to persist session for {}: {}", info.profile_id, e);
+            }
+        });
+    }
 
     state.emit("browser-status-changed");
     Ok(Json(serde_json::json!({ "ok": true, "profile_id": req.profile_id })))
@@ -185,6 +299,7 @@ async fn update_app_settings(
     State(state): State<ApiState>,
     Json(req): Json<UpdateAppSettingsReq>,
 ) -> ApiResult<Json<crate::config::AppSettings>> {
+    validation::validate_settings(&req.settings).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
     let mut config = state.config.write();
     config.settings = req.settings.clone();
     crate::config::save_config(&config).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -311,10 +426,10 @@ async fn restore_snapshot(
     AxumPath((id, name)): AxumPath<(String, String)>,
 ) -> ApiResult<Json<serde_json::Value>> {
     let config = state.config.read().clone();
-    crate::commands::snapshots::core_restore_snapshot(&id, &name, &config, &state.process_manager)
+    let warnings = crate::commands::snapshots::core_restore_snapshot(&id, &name, &config, &state.process_manager)
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
-    Ok(Json(serde_json::json!({ "ok": true })))
+    Ok(Json(serde_json::json!({ "ok": true, "warnings": warnings })))
 }
 
 async fn delete_snapshot(
@@ -327,6 +442,108 @@ async fn delete_snapshot(
     Ok(StatusCode::NO_CONTENT)
 }
 
+#[derive(serde::Deserialize)]
+struct DownloadSnapshotQuery {
+    #[serde(default)]
+    compression: crate::commands::snapshot_archive::ArchiveCompression,
+}
+
+/// Stream a portable archive (zip) of a snapshot.
+async fn download_snapshot(
+    State(_state): State<ApiState>,
+    AxumPath((id, name)): AxumPath<(String, String)>,
+    Query(query): Query<DownloadSnapshotQuery>,
+) -> ApiResult<([(header::HeaderName, String); 3], Body)> {
+    // The archive is written to a temporary file first (zip needs to seek)
+    let tmp = crate::commands::snapshot_archive::TempArchive::new();
+    crate::commands::snapshots::core_export_snapshot(&id, &name, query.compression, tmp.path().to_path_buf())
+        .await
+        .map_err(profile_error)?;
+    let (len, body) = stream_temp_file(tmp).await?;
+    let headers = [
+        (header::CONTENT_TYPE, "application/zip".to_string()),
+        (header::CONTENT_LENGTH, len.to_string()),
+        (
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}-{}.snapshot.zip\"", id.replace('"', ""), name.replace('"', "")),
+        ),
+    ];
+    Ok((headers, body))
+}
+
+/// Stream a temporary file as a response body. Returns its length; the file
+/// goes away once the stream is dropped.
+pub(crate) async fn stream_temp_file(tmp: crate::commands::snapshot_archive::TempArchive) -> ApiResult<(u64, Body)> {
+    use tokio::io::AsyncReadExt;
+
+    let file = tokio::fs::File::open(tmp.path())
+        .await
+        .map_err(|e| profile_error(e.into()))?;
+    let len = file.metadata().await.map_err(|e| profile_error(e.into()))?.len();
+    let stream = futures::stream::try_unfold((file, tmp), |(mut file, tmp)| async move {
+        let mut buf = vec![0u8; 64 * 1024];
+        let n = file.read(&mut buf).await?;
+        if n == 0 {
+            return Ok::<_, std::io::Error>(None);
+        }
+        buf.truncate(n);
+        Ok(Some((Bytes::from(buf), (file, tmp))))
+    });
+    Ok((len, Body::from_stream(stream)))
+}
+
+#[derive(serde::Deserialize)]
+struct UploadSnapshotQuery {
+    /// Defaults to the name in the archive.
+    name: Option<String>,
+}
+
+/// Import a snapshot archive (the request body is the zip).
+async fn upload_snapshot(
+    State(state): State<ApiState>,
+    AxumPath(id): AxumPath<String>,
+    Query(query): Query<UploadSnapshotQuery>,
+    body: Body,
+) -> ApiResult<(StatusCode, Json<crate::config::schema::SnapshotInfo>)> {
+    let tmp = body_to_temp_file(&state, body).await?;
+    let config = state.config.read().clone();
+    let info = crate::commands::snapshots::core_import_snapshot(&config, &id, tmp.path(), query.name.as_deref())
+        .await
+        .map_err(profile_error)?;
+    Ok((StatusCode::CREATED, Json(info)))
+}
+
+/// Write an upload to a temporary file as it arrives, so archives never sit in
+/// memory. Bodies over `settings.max_upload_mb` are refused with `413`.
+pub(crate) async fn body_to_temp_file(
+    state: &ApiState,
+    body: Body,
+) -> ApiResult<crate::commands::snapshot_archive::TempArchive> {
+    use futures::StreamExt;
+    use tokio::io::AsyncWriteExt;
+
+    let limit_mb = state.config.read().settings.max_upload_mb;
+    let tmp = crate::commands::snapshot_archive::TempArchive::new();
+    let mut file = tokio::fs::File::create(tmp.path())
+        .await
+        .map_err(|e| profile_error(e.into()))?;
+    let mut stream = body.into_data_stream();
+    let mut written = 0u64;
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+        written += chunk.len() as u64;
+        if limit_mb > 0 && written > limit_mb * 1024 * 1024 {
+            return Err((
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!("Upload exceeds the {} MiB limit (max_upload_mb)", limit_mb),
+            ));
+        }
+        file.write_all(&chunk).await.map_err(|e| profile_error(e.into()))?;
+    }
+    file.flush().await.map_err(|e| profile_error(e.into()))?;
+    Ok(tmp)
+}
+
 // ---------------------------------------------------------------------------
 // Health check
 // ---------------------------------------------------------------------------
@@ -345,16 +562,73 @@ pub fn router(state: ApiState) -> Router {
         .route("/api/profiles", get(list_profiles).post(add_profile))
         .route(
             "/api/profiles/:id",
-            get(get_profile).put(update_profile).delete(delete_profile),
+            get(get_profile)
+                .put(update_profile)
+                .patch(patch_profile)
+                .delete(delete_profile),
+        )
+        .route("/api/profiles/from-template", post(crate::api::templates::create_from_template))
+        .route("/api/profiles/:id/clone", post(crate::api::templates::clone_profile))
+        .route("/api/profiles/:id/move", post(crate::api::groups::move_profile))
+        .route("/api/profiles/:id/clean", post(crate::api::clean::clean_profile))
+        // Storage routes
+        .route("/api/storage", get(crate::api::storage::storage_report))
+        .route("/api/storage/cleanup", post(crate::api::storage::cleanup_storage))
+        // Trash routes
+        .route("/api/trash", get(crate::api::trash::list_trash).delete(crate::api::trash::empty_trash))
+        .route("/api/trash/:id", delete(crate::api::trash::purge_entry))
+        .route("/api/trash/:id/restore", post(crate::api::trash::restore_entry))
+        .route("/api/profiles/:id/export", get(crate::api::bundles::export_profile))
+        .route(
+            "/api/profiles/import",
+            post(crate::api::bundles::import_profile).layer(DefaultBodyLimit::disable()),
+        )
+        // Group routes
+        .route("/api/groups", get(crate::api::groups::list_groups).post(crate::api::groups::create_group))
+        .route(
+            "/api/groups/:id",
+            get(crate::api::groups::get_group)
+                .put(crate::api::groups::update_group)
+                .delete(crate::api::groups::delete_group),
+        )
+        // Template routes
+        .route("/api/templates", get(crate::api::templates::list_templates))
+        .route(
+            "/api/templates/:name",
+            get(crate::api::templates::get_template)
+                .put(crate::api::templates::put_template)
+                .delete(crate::api::templates::delete_template),
         )
         // Lifecycle routes
         .route("/api/launch/:profile_id", post(crate::api::lifecycle::launch_profile))
         .route("/api/kill/:profile_id", post(crate::api::lifecycle::kill_profile))
         .route("/api/register-external", post(register_external_profile))
+        .route("/api/profiles/:id/attach", post(crate::api::lifecycle::attach_profile))
+        .route("/api/discover", get(crate::api::lifecycle::discover_browsers))
         .route("/api/running", get(crate::api::lifecycle::get_running_browsers))
+        // Bulk routes
+        .route("/api/bulk/launch", post(crate::api::bulk::bulk_launch))
+        .route("/api/bulk/kill", post(crate::api::bulk::bulk_kill))
+        .route("/api/bulk/snapshot", post(crate::api::bulk::bulk_snapshot))
+        .route("/api/bulk/restore", post(crate::api::bulk::bulk_restore))
+        .route("/api/bulk/import", post(crate::api::bulk::bulk_import))
+        // Extension library routes
+        .route("/api/extensions", get(crate::api::extensions::list_extensions))
+        .route("/api/extensions/install", post(crate::api::extensions::install_extension))
+        .route(
+            "/api/extensions/:id",
+            get(crate::api::extensions::get_extension).delete(crate::api::extensions::remove_extension),
+        )
+        // Proxy geography check
+        .route("/api/profiles/:id/geo-check", post(crate::api::geo::check_profile_geo))
         // Snapshots routes
         .route("/api/profiles/:id/snapshots", get(list_snapshots).post(create_snapshot))
+        .route(
+            "/api/profiles/:id/snapshots/upload",
+            post(upload_snapshot).layer(DefaultBodyLimit::disable()),
+        )
         .route("/api/profiles/:id/snapshots/:name/restore", post(restore_snapshot))
+        .route("/api/profiles/:id/snapshots/:name/download", get(download_snapshot))
         .route("/api/profiles/:id/snapshots/:name", delete(delete_snapshot))
         // Settings routes
         .route("/api/settings", get(get_app_settings).put(update_app_settings))
@@ -385,12 +659,15 @@ pub fn app(state: ApiState, _api_key: Option<String>) -> Router {
                     axum::http::Method::GET,
                     axum::http::Method::POST,
                     axum::http::Method::PUT,
+                    axum::http::Method::PATCH,
                     axum::http::Method::DELETE,
                 ])
                 .allow_headers([
                     axum::http::header::CONTENT_TYPE,
+                    axum::http::header::IF_MATCH,
                     axum::http::HeaderName::from_static("x-api-key"),
-                ]),
+                ])
+                .expose_headers([axum::http::header::ETAG, X_TOTAL_COUNT, X_NEXT_CURSOR]),
         )
 }
 
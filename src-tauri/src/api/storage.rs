@@ -0,0 +1,17 @@
+//! Disk usage report and orphan cleanup HTTP handlers.
+
+use super::{ApiResult, ApiState};
+use crate::commands::storage::{self, CleanupReport, CleanupRequest, StorageReport};
+use axum::{extract::State, Json};
+
+pub async fn storage_report(State(state): State<ApiState>) -> ApiResult<Json<StorageReport>> {
+    Ok(Json(storage::core_storage_report(&state).await))
+}
+
+/// Remove orphaned artifacts. The body may be empty (`{}`) to clean every category.
+pub async fn cleanup_storage(
+    State(state): State<ApiState>,
+    Json(req): Json<CleanupRequest>,
+) -> ApiResult<Json<CleanupReport>> {
+    Ok(Json(storage::core_cleanup_storage(&state, &req).await))
+}
@@ -0,0 +1,81 @@
+//! Profile template and clone HTTP handlers.
+
+use super::{ApiResult, ApiState};
+use crate::commands::templates::{self, CloneRequest, FromTemplateRequest, TemplateSummary};
+use crate::config::schema::{BrowserProfile, ProfileTemplate};
+use axum::{
+    body::Bytes,
+    extract::{Path as AxumPath, State},
+    http::StatusCode,
+    Json,
+};
+
+pub async fn list_templates(State(state): State<ApiState>) -> ApiResult<Json<Vec<TemplateSummary>>> {
+    let config = state.config.read();
+    Ok(Json(templates::core_list_templates(&config)))
+}
+
+pub async fn get_template(
+    State(state): State<ApiState>,
+    AxumPath(name): AxumPath<String>,
+) -> ApiResult<Json<ProfileTemplate>> {
+    let config = state.config.read();
+    templates::core_get_template(&config, &name)
+        .map(Json)
+        .map_err(|e| (StatusCode::NOT_FOUND, e))
+}
+
+/// Create or replace a template. The name in the path wins over the body.
+pub async fn put_template(
+    State(state): State<ApiState>,
+    AxumPath(name): AxumPath<String>,
+    Json(mut template): Json<ProfileTemplate>,
+) -> ApiResult<Json<ProfileTemplate>> {
+    template.name = name;
+    templates::core_save_template(&state, template.clone()).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    Ok(Json(template))
+}
+
+pub async fn delete_template(
+    State(state): State<ApiState>,
+    AxumPath(name): AxumPath<String>,
+) -> ApiResult<StatusCode> {
+    match templates::core_delete_template(&state, &name) {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err((StatusCode::NOT_FOUND, format!("Template '{}' not found", name))),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e)),
+    }
+}
+
+pub async fn create_from_template(
+    State(state): State<ApiState>,
+    Json(req): Json<FromTemplateRequest>,
+) -> ApiResult<(StatusCode, Json<Vec<BrowserProfile>>)> {
+    if !state.config.read().templates.iter().any(|t| t.name == req.template) {
+        return Err((StatusCode::NOT_FOUND, format!("Template '{}' not found", req.template)));
+    }
+    let created = templates::core_create_from_template(&state, &req).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// Clone a profile. The optional JSON body (`CloneRequest`) sets the new id, name,
+/// user data dir and whether to copy browser data.
+pub async fn clone_profile(
+    State(state): State<ApiState>,
+    AxumPath(id): AxumPath<String>,
+    body: Bytes,
+) -> ApiResult<(StatusCode, Json<BrowserProfile>)> {
+    let req: CloneRequest = if body.iter().all(u8::is_ascii_whitespace) {
+        CloneRequest::default()
+    } else {
+        serde_json::from_slice(&body)
+            .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid clone request: {}", e)))?
+    };
+    if !state.config.read().profiles.iter().any(|p| p.id == id) {
+        return Err((StatusCode::NOT_FOUND, "Profile not found".to_string()));
+    }
+    let profile = templates::core_clone_profile(&state, &id, req)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    Ok((StatusCode::CREATED, Json(profile)))
+}
@@ -0,0 +1,42 @@
+//! Trash HTTP handlers.
+
+use super::{profile_error, ApiResult, ApiState};
+use crate::commands::trash;
+use crate::config::schema::{BrowserProfile, TrashEntry};
+use axum::{
+    extract::{Path as AxumPath, State},
+    http::StatusCode,
+    Json,
+};
+
+pub async fn list_trash(State(state): State<ApiState>) -> ApiResult<Json<Vec<TrashEntry>>> {
+    Ok(Json(state.config.read().trash.clone()))
+}
+
+/// Put a trashed profile back; `409` when its id or data directory is taken.
+pub async fn restore_entry(
+    State(state): State<ApiState>,
+    AxumPath(id): AxumPath<String>,
+) -> ApiResult<Json<BrowserProfile>> {
+    let profile = trash::core_restore_profile(&state, &id).await.map_err(profile_error)?;
+    Ok(Json(profile))
+}
+
+/// Permanently delete one entry (and its moved data).
+pub async fn purge_entry(
+    State(state): State<ApiState>,
+    AxumPath(id): AxumPath<String>,
+) -> ApiResult<StatusCode> {
+    let purged = trash::core_purge_trash(&state, &[id]).await.map_err(profile_error)?;
+    if purged.is_empty() {
+        return Err((StatusCode::NOT_FOUND, "Trash entry not found".to_string()));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Permanently delete everything in the trash.
+pub async fn empty_trash(State(state): State<ApiState>) -> ApiResult<StatusCode> {
+    let ids: Vec<String> = state.config.read().trash.iter().map(|e| e.id.clone()).collect();
+    trash::core_purge_trash(&state, &ids).await.map_err(profile_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
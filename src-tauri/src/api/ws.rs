@@ -3,6 +3,7 @@
 //! Pushes browser status, action log entries, and profile changes
 //! to all connected clients.
 
+use crate::commands::bulk::BulkOperation;
 use crate::state::AppState;
 use axum::{
     extract::{
@@ -30,6 +31,21 @@ pub enum WsEvent {
     },
     /// Profile added/updated/deleted.
     ProfilesChanged,
+    /// One profile of a bulk operation finished (successfully or not).
+    BulkProgress {
+        operation: BulkOperation,
+        profile_id: String,
+        completed: usize,
+        total: usize,
+        ok: bool,
+        error: Option<String>,
+    },
+    /// A bulk operation finished for all selected profiles.
+    BulkCompleted {
+        operation: BulkOperation,
+        succeeded: usize,
+        failed: usize,
+    },
     /// Heartbeat (sent every 30s to keep connection alive).
     Heartbeat,
 }
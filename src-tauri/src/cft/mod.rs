@@ -3,6 +3,7 @@
 
 mod api;
 mod download;
+pub mod version;
 
 pub use api::{fetch_versions, CftChannelApi, CftVersionInfo, LastKnownGoodVersions};
 pub use download::{ensure_chrome_binary, find_chrome_in_dir, get_platform, CftProgress};
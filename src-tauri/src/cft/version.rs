@@ -0,0 +1,113 @@
+//! Chrome version strings: ordering, CfT download dirs and installed binaries.
+
+use crate::cft::download::find_chrome_in_dir;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Numeric parts of a version string, for ordering ("145.0.7632.117").
+pub fn version_key(version: &str) -> Vec<u64> {
+    version.split('.').map(|part| part.parse().unwrap_or(0)).collect()
+}
+
+/// Whether `name` is a dotted numeric version ("145.0.7632.117").
+pub fn is_version(name: &str) -> bool {
+    let mut parts = name.split('.');
+    parts.clone().count() >= 2 && parts.all(|p| !p.is_empty() && p.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// CfT downloads in `download_dir`, oldest first: directories named like a
+/// version that contain a Chrome binary. The dir may be shared with other files.
+pub fn downloaded_versions(download_dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(rd) = std::fs::read_dir(download_dir) else {
+        return Vec::new();
+    };
+    let mut versions: Vec<(String, PathBuf)> = rd
+        .flatten()
+        .map(|e| (e.file_name().to_string_lossy().into_owned(), e.path()))
+        .filter(|(name, dir)| is_version(name) && find_chrome_in_dir(dir).is_some())
+        .collect();
+    versions.sort_by_cached_key(|(name, _)| version_key(name));
+    versions
+}
+
+/// Newest CfT download in `download_dir`; the one in use when no version is pinned.
+pub fn newest_downloaded_version(download_dir: &Path) -> Option<String> {
+    downloaded_versions(download_dir).pop().map(|(version, _)| version)
+}
+
+/// The version in `chrome --version` output ("Google Chrome 145.0.7632.117").
+fn parse_version_output(output: &str) -> Option<String> {
+    output
+        .split_whitespace()
+        .find(|token| token.contains('.') && token.chars().all(|c| c.is_ascii_digit() || c == '.'))
+        .map(str::to_string)
+}
+
+/// File version from the `VS_FIXEDFILEINFO` block of a PE version resource.
+fn pe_file_version(bytes: &[u8]) -> Option<String> {
+    const SIGNATURE: u32 = 0xFEEF_04BD;
+    let word = |at: usize| -> Option<u32> {
+        Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+    };
+    // The block is DWORD aligned: signature, struct version, then MS/LS file version
+    let start = (0..bytes.len()).step_by(4).find(|&at| word(at) == Some(SIGNATURE))?;
+    let (ms, ls) = (word(start + 8)?, word(start + 12)?);
+    Some(format!("{}.{}.{}.{}", ms >> 16, ms & 0xFFFF, ls >> 16, ls & 0xFFFF))
+}
+
+/// Version of the Chrome binary at `path`. On Windows `chrome.exe --version`
+/// prints nothing and opens a browser instead, so the version resource is read.
+pub async fn chrome_binary_version(path: &Path) -> Option<String> {
+    if cfg!(windows) {
+        let bytes = tokio::fs::read(path).await.ok()?;
+        return tokio::task::spawn_blocking(move || pe_file_version(&bytes)).await.ok()?;
+    }
+    let mut command = tokio::process::Command::new(path);
+    command.arg("--version").kill_on_drop(true);
+    let output = tokio::time::timeout(Duration::from_secs(5), command.output())
+        .await
+        .ok()?
+        .ok()?;
+    parse_version_output(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_version_and_key() {
+        assert!(is_version("145.0.7632.117"));
+        assert!(is_version("145.0"));
+        assert!(!is_version("145"));
+        assert!(!is_version("Downloads"));
+        assert!(!is_version("145..1"));
+        assert!(!is_version("145.0-beta"));
+        assert!(version_key("145.0.7632.117") > version_key("145.0.7632.99"));
+        assert!(version_key("145.0.1") > version_key("144.9.9999.9"));
+    }
+
+    #[test]
+    fn test_parse_version_output() {
+        assert_eq!(
+            parse_version_output("Google Chrome 145.0.7632.117 \n").as_deref(),
+            Some("145.0.7632.117")
+        );
+        assert_eq!(
+            parse_version_output("Chromium 144.0.7559.96 built on Debian GNU/Linux 12").as_deref(),
+            Some("144.0.7559.96")
+        );
+        assert_eq!(parse_version_output(""), None);
+    }
+
+    #[test]
+    fn test_pe_file_version() {
+        let mut bytes = b"MZ\x90\x00 resource data ".to_vec();
+        bytes.resize(64, 0);
+        for word in [0xFEEF_04BDu32, 0x0001_0000, 145 << 16, (7632 << 16) | 117] {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        assert_eq!(pe_file_version(&bytes).as_deref(), Some("145.0.7632.117"));
+        assert_eq!(pe_file_version(b"MZ no version resource"), None);
+    }
+}
@@ -0,0 +1,411 @@
+//! Bulk operations on groups of profiles: launch / kill / snapshot / restore.
+//!
+//! Profiles are selected by tag (any match) and/or explicit id. Every profile is
+//! processed independently: a failure is recorded in its own result entry and the
+//! batch carries on. Progress is broadcast over `/api/ws` as `BulkProgress` events.
+
+use crate::api::ws::WsEvent;
+use crate::commands::get_effective_chrome_path_from_config;
+use crate::commands::snapshots::{core_create_snapshot, core_restore_snapshot};
+use crate::config::schema::AppConfig;
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::State;
+
+/// Which profiles a bulk operation applies to.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BulkSelector {
+    /// Select every profile carrying at least one of these tags.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Explicit profile ids, added to the tag selection.
+    #[serde(default)]
+    pub profile_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkOperation {
+    Launch,
+    Kill,
+    Snapshot,
+    Restore,
+}
+
+/// Outcome for a single profile in a bulk operation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkItemResult {
+    pub profile_id: String,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Set for successful launches.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+    /// Set for successful launches.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cdp_port: Option<u16>,
+    /// Launch flag and fingerprint warnings of a successful launch.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+impl BulkItemResult {
+    fn ok(profile_id: &str) -> Self {
+        Self {
+            profile_id: profile_id.to_string(),
+            ok: true,
+            error: None,
+            pid: None,
+            cdp_port: None,
+            warnings: Vec::new(),
+        }
+    }
+
+    fn failed(profile_id: &str, error: String) -> Self {
+        Self {
+            profile_id: profile_id.to_string(),
+            ok: false,
+            error: Some(error),
+            pid: None,
+            cdp_port: None,
+            warnings: Vec::new(),
+        }
+    }
+}
+
+/// Summary of a finished bulk operation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkReport {
+    pub operation: BulkOperation,
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BulkItemResult>,
+}
+
+/// Resolve a selector to profile ids, in config order, without duplicates.
+/// Unknown explicit ids are kept so they show up as per-profile failures.
+pub fn select_profiles(config: &AppConfig, selector: &BulkSelector) -> Result<Vec<String>, String> {
+    if selector.tags.is_empty() && selector.profile_ids.is_empty() {
+        return Err("Select at least one tag or profile id".to_string());
+    }
+
+    let mut ids: Vec<String> = config
+        .profiles
+        .iter()
+        .filter(|p| {
+            selector.profile_ids.contains(&p.id)
+                || p.tags.iter().any(|t| selector.tags.contains(t))
+        })
+        .map(|p| p.id.clone())
+        .collect();
+
+    for id in &selector.profile_ids {
+        if !ids.contains(id) {
+            ids.push(id.clone());
+        }
+    }
+
+    Ok(ids)
+}
+
+/// Run `op` over every selected profile, broadcasting progress after each one.
+struct BulkRun<'a> {
+    state: &'a AppState,
+    operation: BulkOperation,
+    total: usize,
+    results: Vec<BulkItemResult>,
+}
+
+impl<'a> BulkRun<'a> {
+    fn new(state: &'a AppState, operation: BulkOperation, total: usize) -> Self {
+        Self {
+            state,
+            operation,
+            total,
+            results: Vec::with_capacity(total),
+        }
+    }
+
+    fn record(&mut self, result: BulkItemResult) {
+        if let Some(err) = &result.error {
+            tracing::warn!(
+                "Bulk {:?} failed for profile {}: {}",
+                self.operation,
+                result.profile_id,
+                err
+            );
+        }
+        self.state.ws_broadcaster.broadcast(WsEvent::BulkProgress {
+            operation: self.operation,
+            profile_id: result.profile_id.clone(),
+            completed: self.results.len() + 1,
+            total: self.total,
+            ok: result.ok,
+            error: result.error.clone(),
+        });
+        self.results.push(result);
+    }
+
+    fn finish(self) -> BulkReport {
+        let succeeded = self.results.iter().filter(|r| r.ok).count();
+        let report = BulkReport {
+            operation: self.operation,
+            total: self.total,
+            succeeded,
+            failed: self.total - succeeded,
+            results: self.results,
+        };
+        self.state.ws_broadcaster.broadcast(WsEvent::BulkCompleted {
+            operation: report.operation,
+            succeeded: report.succeeded,
+            failed: report.failed,
+        });
+        report
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Core functions (shared by Tauri commands + HTTP handlers)
+// ---------------------------------------------------------------------------
+
+pub async fn core_bulk_launch(state: &AppState, selector: &BulkSelector) -> Result<BulkReport, String> {
+    let config = state.config.read().clone();
+    let ids = select_profiles(&config, selector)?;
+    // Resolving the binary may download CfT; do it once for the whole batch.
+    let chrome_path = get_effective_chrome_path_from_config(&config).await?;
+
+    let mut run = BulkRun::new(state, BulkOperation::Launch, ids.len());
+    let mut launched = Vec::new();
+    for id in &ids {
+        if state.process_manager.is_running(id) {
+            run.record(BulkItemResult::failed(id, "Profile is already running".to_string()));
+            continue;
+        }
+        match state
+            .process_manager
+            .launch_profile(id, &config, &chrome_path)
+            .await
+        {
+            Ok(outcome) => {
+                if let Some(info) = state.process_manager.get_process_info(id) {
+                    tokio::spawn(async move {
+                        if let Err(e) = crate::process::sessions_persist::save_session(&info).await {
+                            tracing::warn!("Failed to persist session for {}: {}", info.profile_id, e);
+                        }
+                    });
+                }
+                state.ws_broadcaster.broadcast(WsEvent::BrowserStatusChanged {
+                    profile_id: id.clone(),
+                    running: true,
+                });
+                launched.push(id.clone());
+                run.record(BulkItemResult {
+                    pid: Some(outcome.pid),
+                    cdp_port: Some(outcome.cdp_port),
+                    warnings: outcome.warnings,
+                    ..BulkItemResult::ok(id)
+                });
+            }
+            Err(e) => run.record(BulkItemResult::failed(id, e.to_string())),
+        }
+    }
+
+    if !launched.is_empty() {
+        let mut config = state.config.write();
+        for id in &launched {
+            config.recent_profiles.retain(|r| r != id);
+            config.recent_profiles.insert(0, id.clone());
+        }
+        config.recent_profiles.truncate(10);
+        if let Err(e) = crate::config::save_config(&config) {
+            tracing::warn!("Failed to save recent profiles after bulk launch: {}", e);
+        }
+        drop(config);
+        state.emit("browser-status-changed");
+    }
+
+    Ok(run.finish())
+}
+
+pub async fn core_bulk_kill(state: &AppState, selector: &BulkSelector) -> Result<BulkReport, String> {
+    let ids = {
+        let config = state.config.read();
+        select_profiles(&config, selector)?
+    };
+
+    let mut run = BulkRun::new(state, BulkOperation::Kill, ids.len());
+    let mut killed_any = false;
+    for id in &ids {
+        match state.process_manager.kill_profile(id).await {
+            Ok(()) => {
+                let profile_id = id.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = crate::process::sessions_persist::remove_session(&profile_id).await {
+                        tracing::warn!("Failed to remove persisted session for {}: {}", profile_id, e);
+                    }
+                });
+                state.ws_broadcaster.broadcast(WsEvent::BrowserStatusChanged {
+                    profile_id: id.clone(),
+                    running: false,
+                });
+                killed_any = true;
+                run.record(BulkItemResult::ok(id));
+            }
+            Err(e) => run.record(BulkItemResult::failed(id, e.to_string())),
+        }
+    }
+
+    if killed_any {
+        state.emit("browser-status-changed");
+    }
+
+    Ok(run.finish())
+}
+
+pub async fn core_bulk_snapshot(
+    state: &AppState,
+    selector: &BulkSelector,
+    name: &str,
+) -> Result<BulkReport, String> {
+    let config = state.config.read().clone();
+    let ids = select_profiles(&config, selector)?;
+
+    let mut run = BulkRun::new(state, BulkOperation::Snapshot, ids.len());
+    for id in &ids {
+        match core_create_snapshot(id, name, &config, &state.process_manager).await {
+            Ok(_) => run.record(BulkItemResult::ok(id)),
+            Err(e) => run.record(BulkItemResult::failed(id, e)),
+        }
+    }
+    Ok(run.finish())
+}
+
+pub async fn core_bulk_restore(
+    state: &AppState,
+    selector: &BulkSelector,
+    name: &str,
+) -> Result<BulkReport, String> {
+    let config = state.config.read().clone();
+    let ids = select_profiles(&config, selector)?;
+
+    let mut run = BulkRun::new(state, BulkOperation::Restore, ids.len());
+    for id in &ids {
+        match core_restore_snapshot(id, name, &config, &state.process_manager).await {
+            Ok(_) => run.record(BulkItemResult::ok(id)),
+            Err(e) => run.record(BulkItemResult::failed(id, e)),
+        }
+    }
+    Ok(run.finish())
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+#[tauri::command]
+pub async fn bulk_launch(
+    selector: BulkSelector,
+    state: State<'_, Arc<AppState>>,
+) -> Result<BulkReport, String> {
+    core_bulk_launch(&state, &selector).await
+}
+
+#[tauri::command]
+pub async fn bulk_kill(
+    selector: BulkSelector,
+    state: State<'_, Arc<AppState>>,
+) -> Result<BulkReport, String> {
+    core_bulk_kill(&state, &selector).await
+}
+
+#[tauri::command]
+pub async fn bulk_snapshot(
+    selector: BulkSelector,
+    name: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<BulkReport, String> {
+    core_bulk_snapshot(&state, &selector, &name).await
+}
+
+#[tauri::command]
+pub async fn bulk_restore(
+    selector: BulkSelector,
+    name: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<BulkReport, String> {
+    core_bulk_restore(&state, &selector, &name).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::schema::BrowserProfile;
+    use std::path::PathBuf;
+
+    fn profile(id: &str, tags: &[&str]) -> BrowserProfile {
+        BrowserProfile {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            user_data_dir: PathBuf::from(format!("/tmp/{}", id)),
+            proxy_server: None,
+            lang: "en-US".to_string(),
+            timezone: None,
+            fingerprint: None,
+            color: None,
+            custom_args: vec![],
+            extensions: vec![],
+            launch_flags: Default::default(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            group: None,
+            headless: false,
+            window: Default::default(),
+            startup_urls: vec![],
+            display_mode: Default::default(),
+            env: Default::default(),
+            working_dir: None,
+            clear_env: false,
+            snapshot_exclude: vec![],
+        }
+    }
+
+    fn config() -> AppConfig {
+        AppConfig {
+            profiles: vec![
+                profile("a", &["shop-a"]),
+                profile("b", &["shop-b"]),
+                profile("c", &["shop-a", "shop-b"]),
+            ],
+            ..AppConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_select_profiles_by_tag() {
+        let selector = BulkSelector {
+            tags: vec!["shop-a".to_string()],
+            profile_ids: vec![],
+        };
+        assert_eq!(select_profiles(&config(), &selector).unwrap(), vec!["a", "c"]);
+    }
+
+    #[test]
+    fn test_select_profiles_merges_ids_without_duplicates() {
+        let selector = BulkSelector {
+            tags: vec!["shop-b".to_string()],
+            profile_ids: vec!["a".to_string(), "c".to_string(), "ghost".to_string()],
+        };
+        assert_eq!(
+            select_profiles(&config(), &selector).unwrap(),
+            vec!["a", "b", "c", "ghost"]
+        );
+    }
+
+    #[test]
+    fn test_select_profiles_empty_selector_rejected() {
+        assert!(select_profiles(&config(), &BulkSelector::default()).is_err());
+    }
+}
@@ -0,0 +1,411 @@
+//! Bulk profile import from CSV or JSON lines.
+//!
+//! Every row becomes a `BrowserProfile` after column mapping and shared defaults,
+//! and goes through the same checks as a single create (`prepare_new_profile`),
+//! including conflicts with earlier rows. A dry run only reports; a commit adds
+//! the rows in one `save_config`.
+
+use crate::commands::prepare_new_profile;
+use crate::config::schema::BrowserProfile;
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tauri::State;
+
+/// Profile fields holding lists. In CSV they are `;`-separated.
+const LIST_FIELDS: &[&str] = &["tags", "custom_args", "extensions", "startup_urls"];
+/// Profile fields holding booleans. In CSV: true/false, yes/no or 1/0.
+const BOOL_FIELDS: &[&str] = &["headless", "clear_env"];
+/// Nested profile fields holding numbers. Other dotted fields (`env.*`,
+/// `fingerprint.brand_version`, ...) are strings and kept as written.
+const NUMBER_FIELDS: &[&str] = &[
+    "fingerprint.seed",
+    "fingerprint.hardware_concurrency",
+    "fingerprint.screen.width",
+    "fingerprint.screen.height",
+    "window.width",
+    "window.height",
+    "window.x",
+    "window.y",
+    "window.device_scale_factor",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    /// Comma-separated values with a header row (RFC 4180 quoting).
+    Csv,
+    /// One JSON object per line.
+    Jsonl,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BulkImportRequest {
+    /// Detected from the data when omitted (`{` on the first line means JSON lines).
+    #[serde(default)]
+    pub format: Option<ImportFormat>,
+    pub data: String,
+    /// Source column / key → profile field, e.g. `{"Account": "name", "TZ": "timezone"}`.
+    /// Fields nest with dots (`fingerprint.seed`, `window.width`). Unmapped columns
+    /// are used as field names as they are.
+    #[serde(default)]
+    pub mapping: BTreeMap<String, String>,
+    /// Field values for every row; a row's own values win.
+    #[serde(default)]
+    pub defaults: Map<String, Value>,
+    /// Only validate and report.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Add the valid rows even when others fail. By default one invalid row
+    /// means nothing is added.
+    #[serde(default)]
+    pub allow_partial: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportRowResult {
+    /// 1-based data row (the CSV header is not counted).
+    pub row: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkImportReport {
+    pub dry_run: bool,
+    /// Whether the valid rows were added.
+    pub committed: bool,
+    pub total: usize,
+    pub valid: usize,
+    pub rows: Vec<ImportRowResult>,
+    /// The added (or, on a dry run, would-be) profiles.
+    pub profiles: Vec<BrowserProfile>,
+}
+
+/// Split CSV text into records. Handles quoted fields with `""` escapes,
+/// embedded separators and newlines, and CRLF line ends. Blank lines are skipped.
+pub fn parse_csv(text: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut records = Vec::new();
+    let mut record: Vec<String> = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                if record.len() == 1 && record[0].is_empty() {
+                    record.clear();
+                } else {
+                    records.push(std::mem::take(&mut record));
+                }
+            }
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err("Unterminated quoted field".to_string());
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Set `path` (dot-separated) in `obj`, creating nested objects.
+fn set_path(obj: &mut Map<String, Value>, path: &str, value: Value) {
+    match path.split_once('.') {
+        Some((head, rest)) => {
+            let child = obj
+                .entry(head.to_string())
+                .or_insert_with(|| Value::Object(Map::new()));
+            if !child.is_object() {
+                *child = Value::Object(Map::new());
+            }
+            if let Value::Object(map) = child {
+                set_path(map, rest, value);
+            }
+        }
+        None => {
+            obj.insert(path.to_string(), value);
+        }
+    }
+}
+
+/// Typed JSON value for a CSV cell bound to `field`.
+fn csv_value(field: &str, cell: &str) -> Result<Value, String> {
+    if LIST_FIELDS.contains(&field) {
+        return Ok(Value::Array(
+            cell.split(';')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(|s| Value::String(s.to_string()))
+                .collect(),
+        ));
+    }
+    if BOOL_FIELDS.contains(&field) {
+        return match cell.to_ascii_lowercase().as_str() {
+            "true" | "yes" | "1" => Ok(Value::Bool(true)),
+            "false" | "no" | "0" => Ok(Value::Bool(false)),
+            _ => Err(format!("{}: expected true or false, got {:?}", field, cell)),
+        };
+    }
+    if NUMBER_FIELDS.contains(&field) {
+        if let Ok(n) = cell.parse::<i64>() {
+            return Ok(Value::from(n));
+        }
+        if let Ok(f) = cell.parse::<f64>() {
+            return Ok(Value::from(f));
+        }
+    }
+    Ok(Value::String(cell.to_string()))
+}
+
+fn map_field<'a>(mapping: &'a BTreeMap<String, String>, column: &'a str) -> &'a str {
+    mapping.get(column).map(String::as_str).unwrap_or(column).trim()
+}
+
+/// A data row as profile JSON, or why it could not be read.
+pub type ParsedRow = Result<Map<String, Value>, String>;
+
+/// Turn the input into one JSON object (or error) per data row.
+pub fn parse_rows(req: &BulkImportRequest) -> Result<Vec<ParsedRow>, String> {
+    let format = req.format.unwrap_or_else(|| {
+        if req.data.trim_start().starts_with('{') {
+            ImportFormat::Jsonl
+        } else {
+            ImportFormat::Csv
+        }
+    });
+    match format {
+        ImportFormat::Jsonl => Ok(req
+            .data
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|line| {
+                let Value::Object(obj) = serde_json::from_str(line).map_err(|e| format!("Invalid JSON: {}", e))? else {
+                    return Err("Line is not a JSON object".to_string());
+                };
+                let mut row = Map::new();
+                for (key, value) in obj {
+                    set_path(&mut row, map_field(&req.mapping, &key), value);
+                }
+                Ok(row)
+            })
+            .collect()),
+        ImportFormat::Csv => {
+            let mut records = parse_csv(&req.data)?.into_iter();
+            let header = records.next().ok_or_else(|| "CSV has no header row".to_string())?;
+            let fields: Vec<&str> = header.iter().map(|c| map_field(&req.mapping, c.trim())).collect();
+            Ok(records
+                .map(|record| {
+                    if record.len() != fields.len() {
+                        return Err(format!("Expected {} columns, found {}", fields.len(), record.len()));
+                    }
+                    let mut row = Map::new();
+                    for (field, cell) in fields.iter().zip(&record) {
+                        let cell = cell.trim();
+                        if field.is_empty() || cell.is_empty() {
+                            continue;
+                        }
+                        set_path(&mut row, field, csv_value(field, cell)?);
+                    }
+                    Ok(row)
+                })
+                .collect())
+        }
+    }
+}
+
+/// Build and check every row against `config` and the rows before it.
+pub fn plan_import(
+    config: &crate::config::AppConfig,
+    req: &BulkImportRequest,
+) -> Result<(Vec<ImportRowResult>, Vec<BrowserProfile>), String> {
+    let mut staged = config.clone();
+    let mut results = Vec::new();
+    let mut accepted = Vec::new();
+    for (i, row) in parse_rows(req)?.into_iter().enumerate() {
+        let outcome = row.and_then(|row| {
+            let mut value = Value::Object(req.defaults.clone());
+            crate::config::patch::merge_patch(&mut value, &Value::Object(row));
+            let mut profile: BrowserProfile =
+                serde_json::from_value(value).map_err(|e| format!("Invalid profile: {}", e))?;
+            prepare_new_profile(&mut profile, &staged).map_err(|e| e.to_string())?;
+            Ok(profile)
+        });
+        match outcome {
+            Ok(profile) => {
+                results.push(ImportRowResult {
+                    row: i + 1,
+                    id: Some(profile.id.clone()),
+                    error: None,
+                });
+                staged.profiles.push(profile.clone());
+                accepted.push(profile);
+            }
+            Err(e) => results.push(ImportRowResult {
+                row: i + 1,
+                id: None,
+                error: Some(e),
+            }),
+        }
+    }
+    Ok((results, accepted))
+}
+
+// ---------------------------------------------------------------------------
+// Core functions (shared by Tauri commands + HTTP handlers)
+// ---------------------------------------------------------------------------
+
+/// Validate all rows and, unless `dry_run`, add them. Holds the config write lock
+/// throughout, so the checks and the commit see the same profile list.
+pub fn core_bulk_import(state: &AppState, req: &BulkImportRequest) -> Result<BulkImportReport, String> {
+    let report = {
+        let mut config = state.config.write();
+        let (rows, profiles) = plan_import(&config, req)?;
+        let total = rows.len();
+        if total == 0 {
+            return Err("No rows to import".to_string());
+        }
+        let valid = profiles.len();
+        let commit = !req.dry_run && valid > 0 && (valid == total || req.allow_partial);
+        if commit {
+            let before = config.profiles.len();
+            config.profiles.extend(profiles.iter().cloned());
+            if let Err(e) = crate::config::save_config(&config) {
+                config.profiles.truncate(before);
+                return Err(format!("Failed to save profiles: {}", e));
+            }
+        }
+        BulkImportReport {
+            dry_run: req.dry_run,
+            committed: commit,
+            total,
+            valid,
+            rows,
+            profiles,
+        }
+    };
+    if report.committed {
+        state.emit("profiles-changed");
+    }
+    Ok(report)
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+#[tauri::command]
+pub async fn bulk_import_profiles(
+    request: BulkImportRequest,
+    state: State<'_, Arc<AppState>>,
+) -> Result<BulkImportReport, String> {
+    core_bulk_import(&state, &request)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::schema::AppConfig;
+
+    fn request(format: Option<ImportFormat>, data: &str) -> BulkImportRequest {
+        BulkImportRequest {
+            format,
+            data: data.to_string(),
+            mapping: BTreeMap::new(),
+            defaults: Map::new(),
+            dry_run: true,
+            allow_partial: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_csv_quoting() {
+        let records = parse_csv("a,b,c\r\n\"x, y\",\"say \"\"hi\"\"\",\"multi\nline\"\n\n1,,3").unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[1], ["x, y", "say \"hi\"", "multi\nline"]);
+        assert_eq!(records[2], ["1", "", "3"]);
+        assert!(parse_csv("a\n\"open").is_err());
+    }
+
+    #[test]
+    fn test_plan_csv_with_mapping_and_defaults() {
+        let mut req = request(
+            None,
+            "Account,Proxy,TZ,Labels,Seed\n\
+             Shop 1,socks5://10.0.0.1:1080,Europe/Berlin,shop;eu,42\n\
+             Shop 2,ftp://bad,Europe/Berlin,shop,43\n\
+             Shop 3,,Mars/Base,,\n",
+        );
+        req.mapping = [
+            ("Account", "name"),
+            ("Proxy", "proxy_server"),
+            ("TZ", "timezone"),
+            ("Labels", "tags"),
+            ("Seed", "fingerprint.seed"),
+        ]
+        .into_iter()
+        .map(|(a, b)| (a.to_string(), b.to_string()))
+        .collect();
+        req.defaults.insert("lang".to_string(), Value::String("de-DE".to_string()));
+
+        let (rows, profiles) = plan_import(&AppConfig::default(), &req).unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].name, "Shop 1");
+        assert_eq!(profiles[0].tags, ["shop", "eu"]);
+        assert_eq!(profiles[0].lang, "de-DE");
+        assert_eq!(profiles[0].fingerprint.as_ref().and_then(|f| f.seed), Some(42));
+        assert!(rows[1].error.as_deref().unwrap().contains("proxy"));
+        assert!(rows[2].error.as_deref().unwrap().contains("timezone"));
+    }
+
+    #[test]
+    fn test_plan_csv_keeps_env_values_as_strings() {
+        let req = request(
+            Some(ImportFormat::Csv),
+            "name,env.PORT,env.DEBUG,window.width,fingerprint.brand_version\n\
+             Dev,8080,true,1280,120\n",
+        );
+        let (rows, profiles) = plan_import(&AppConfig::default(), &req).unwrap();
+        assert!(rows[0].error.is_none(), "{:?}", rows[0].error);
+        assert_eq!(profiles[0].env.get("PORT").map(String::as_str), Some("8080"));
+        assert_eq!(profiles[0].env.get("DEBUG").map(String::as_str), Some("true"));
+        assert_eq!(profiles[0].window.width, Some(1280));
+        let fingerprint = profiles[0].fingerprint.as_ref().unwrap();
+        assert_eq!(fingerprint.brand_version.as_deref(), Some("120"));
+    }
+
+    #[test]
+    fn test_plan_jsonl_detects_duplicates_between_rows() {
+        let req = request(
+            None,
+            "{\"id\": \"a\", \"name\": \"A\", \"user_data_dir\": \"/tmp/imp-a\"}\n\
+             {\"id\": \"a\", \"name\": \"A again\", \"user_data_dir\": \"/tmp/imp-b\"}\n",
+        );
+        let (rows, profiles) = plan_import(&AppConfig::default(), &req).unwrap();
+        assert_eq!(profiles.len(), 1);
+        assert!(rows[1].error.as_deref().unwrap().contains("already exists"));
+    }
+}
@@ -0,0 +1,531 @@
+//! Profile export/import bundles.
+//!
+//! A bundle is a zip archive:
+//!
+//! - `manifest.json`: `BundleManifest` (format name and version, exporting app version)
+//! - `profile.json`: the `BrowserProfile`
+//! - `proxy_presets.json`: proxy presets the profile uses
+//! - `data/...`: the user data dir without caches and lock files (optional)
+//!
+//! `BUNDLE_VERSION` is bumped whenever the layout or `profile.json` changes
+//! incompatibly; `upgrade_profile` migrates older bundles on import.
+
+use crate::commands::groups::resolve_profile;
+use crate::commands::templates::skip_on_clone;
+use crate::commands::storage::data_write_guard;
+use crate::config::schema::{default_user_data_dir, AppConfig, BrowserProfile, ProxyPreset};
+use crate::config::validation;
+use crate::error::{BrowsionError, Result};
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use std::io::{BufReader, BufWriter, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::State;
+
+pub const BUNDLE_FORMAT: &str = "browsion-profile";
+/// Current bundle layout version. Bundles up to this version can be imported.
+pub const BUNDLE_VERSION: u32 = 1;
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+const PROFILE_ENTRY: &str = "profile.json";
+const PRESETS_ENTRY: &str = "proxy_presets.json";
+const DATA_PREFIX: &str = "data/";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format: String,
+    pub version: u32,
+    /// Browsion version that wrote the bundle.
+    pub app_version: String,
+    pub exported_at_ts: u64,
+    pub profile_id: String,
+    /// Whether `data/` holds the user data dir.
+    pub includes_data: bool,
+}
+
+/// How to import a bundle.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ImportOptions {
+    /// Id for the imported profile. Defaults to the bundled id.
+    pub id: Option<String>,
+    /// Defaults to the bundled name.
+    pub name: Option<String>,
+    /// Defaults to `~/.browsion/profiles/<id>`.
+    pub user_data_dir: Option<PathBuf>,
+    /// When the id is already taken, import under a generated id instead of failing.
+    pub rename_on_conflict: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportReport {
+    pub profile: BrowserProfile,
+    pub manifest: BundleManifest,
+    /// The bundled id, when the profile was imported under another one.
+    pub original_id: Option<String>,
+    /// Ids of proxy presets added to this installation.
+    pub proxy_presets_added: Vec<String>,
+    pub data_restored: bool,
+    /// Settings dropped or not resolvable on this installation.
+    pub warnings: Vec<String>,
+}
+
+fn invalid(msg: impl std::fmt::Display) -> BrowsionError {
+    BrowsionError::Validation(format!("Invalid bundle: {}", msg))
+}
+
+fn zip_err(e: zip::result::ZipError) -> BrowsionError {
+    BrowsionError::Io(std::io::Error::other(e))
+}
+
+/// Presets whose URL is the profile's effective proxy.
+fn referenced_presets(config: &AppConfig, profile: &BrowserProfile) -> Vec<ProxyPreset> {
+    let proxy = resolve_profile(profile, &config.groups).proxy_server;
+    config
+        .proxy_presets
+        .iter()
+        .filter(|p| proxy.as_deref() == Some(p.url.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// Add `dir` (recursively) to the archive under `prefix`. Symlinks and entries
+/// `skip` matches are left out.
+fn add_dir<W: Write + Seek>(
+    zip: &mut zip::ZipWriter<W>,
+    root: &Path,
+    dir: &Path,
+    prefix: &str,
+    skip: &impl Fn(&Path) -> bool,
+) -> Result<()> {
+    let options = zip::write::FileOptions::default().large_file(true);
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() || skip(relative) {
+            continue;
+        }
+        let name = format!("{}{}", prefix, relative.to_string_lossy().replace('\\', "/"));
+        if file_type.is_dir() {
+            zip.add_directory(format!("{}/", name), options).map_err(zip_err)?;
+            add_dir(zip, root, &path, prefix, skip)?;
+        } else {
+            zip.start_file(name, options).map_err(zip_err)?;
+            std::io::copy(&mut std::fs::File::open(&path)?, zip)?;
+        }
+    }
+    Ok(())
+}
+
+/// Write a bundle for `profile` to `writer`.
+pub fn write_bundle<W: Write + Seek>(
+    writer: W,
+    profile: &BrowserProfile,
+    presets: &[ProxyPreset],
+    include_data: bool,
+) -> Result<W> {
+    let manifest = BundleManifest {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at_ts: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64,
+        profile_id: profile.id.clone(),
+        includes_data: include_data && profile.user_data_dir.is_dir(),
+    };
+
+    let mut zip = zip::ZipWriter::new(writer);
+    let options = zip::write::FileOptions::default();
+    for (name, value) in [
+        (MANIFEST_ENTRY, serde_json::to_vec_pretty(&manifest)),
+        (PROFILE_ENTRY, serde_json::to_vec_pretty(profile)),
+        (PRESETS_ENTRY, serde_json::to_vec_pretty(presets)),
+    ] {
+        zip.start_file(name, options).map_err(zip_err)?;
+        zip.write_all(&value.map_err(|e| BrowsionError::Config(e.to_string()))?)?;
+    }
+    if manifest.includes_data {
+        zip.add_directory(DATA_PREFIX, options).map_err(zip_err)?;
+        add_dir(&mut zip, &profile.user_data_dir, &profile.user_data_dir, DATA_PREFIX, &skip_on_clone)?;
+    }
+    zip.finish().map_err(zip_err)
+}
+
+/// Migrate `profile.json` of an older bundle version to the current schema.
+fn upgrade_profile(version: u32, profile: serde_json::Value) -> serde_json::Value {
+    // Version 1 is the first format; later versions add their migrations here.
+    let _ = version;
+    profile
+}
+
+fn read_json<R: Read + Seek, T: serde::de::DeserializeOwned>(
+    archive: &mut zip::ZipArchive<R>,
+    name: &str,
+) -> Result<T> {
+    let entry = archive
+        .by_name(name)
+        .map_err(|_| invalid(format!("missing {}", name)))?;
+    serde_json::from_reader(entry).map_err(|e| invalid(format!("{}: {}", name, e)))
+}
+
+/// Parsed bundle contents (without the data files).
+pub struct Bundle<R> {
+    pub manifest: BundleManifest,
+    pub profile: BrowserProfile,
+    pub presets: Vec<ProxyPreset>,
+    archive: zip::ZipArchive<R>,
+}
+
+/// Open a bundle and check its manifest.
+pub fn read_bundle<R: Read + Seek>(reader: R) -> Result<Bundle<R>> {
+    let mut archive = zip::ZipArchive::new(reader).map_err(invalid)?;
+    let manifest: BundleManifest = read_json(&mut archive, MANIFEST_ENTRY)?;
+    if manifest.format != BUNDLE_FORMAT {
+        return Err(invalid(format!("unknown format {:?}", manifest.format)));
+    }
+    if manifest.version == 0 {
+        return Err(invalid("bundle version 0"));
+    }
+    if manifest.version > BUNDLE_VERSION {
+        return Err(invalid(format!(
+            "bundle version {} (written by Browsion {}) is newer than supported version {}",
+            manifest.version, manifest.app_version, BUNDLE_VERSION
+        )));
+    }
+    let profile = upgrade_profile(manifest.version, read_json(&mut archive, PROFILE_ENTRY)?);
+    let profile: BrowserProfile =
+        serde_json::from_value(profile).map_err(|e| invalid(format!("{}: {}", PROFILE_ENTRY, e)))?;
+    let presets = match archive.by_name(PRESETS_ENTRY) {
+        Ok(entry) => serde_json::from_reader(entry).map_err(|e| invalid(format!("{}: {}", PRESETS_ENTRY, e)))?,
+        Err(_) => Vec::new(),
+    };
+    Ok(Bundle {
+        manifest,
+        profile,
+        presets,
+        archive,
+    })
+}
+
+impl<R: Read + Seek> Bundle<R> {
+    /// Extract `data/` into `dst`, which must not exist yet. Entry names that
+    /// would escape `dst` are rejected.
+    pub fn extract_data(&mut self, dst: &Path) -> Result<()> {
+        std::fs::create_dir_all(dst)?;
+        for i in 0..self.archive.len() {
+            let mut entry = self.archive.by_index(i).map_err(zip_err)?;
+            let Some(name) = entry.enclosed_name().map(Path::to_path_buf) else {
+                return Err(invalid(format!("unsafe entry name {:?}", entry.name())));
+            };
+            let Ok(relative) = name.strip_prefix(DATA_PREFIX.trim_end_matches('/')) else {
+                continue;
+            };
+            let out = dst.join(relative);
+            if entry.is_dir() {
+                std::fs::create_dir_all(&out)?;
+            } else {
+                if let Some(parent) = out.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::io::copy(&mut entry, &mut std::fs::File::create(&out)?)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Work out the imported profile and the presets to add. Does not touch `config`
+/// or the filesystem.
+pub fn plan_import(
+    config: &AppConfig,
+    bundled: &BrowserProfile,
+    bundled_presets: &[ProxyPreset],
+    opts: &ImportOptions,
+) -> Result<(BrowserProfile, Vec<ProxyPreset>, Vec<String>)> {
+    let mut profile = bundled.clone();
+    let mut warnings = Vec::new();
+
+    profile.id = opts.id.clone().unwrap_or_else(|| bundled.id.clone());
+    if opts.id.is_none() && opts.rename_on_conflict && config.profiles.iter().any(|p| p.id == profile.id) {
+        profile.id = uuid::Uuid::new_v4().to_string();
+    }
+    if let Some(name) = &opts.name {
+        profile.name = name.clone();
+    }
+    // Paths from another machine are meaningless here
+    profile.user_data_dir = opts
+        .user_data_dir
+        .clone()
+        .unwrap_or_else(|| default_user_data_dir(&profile.id));
+    if profile.working_dir.take().is_some() {
+        warnings.push("working_dir was dropped (path from the exporting machine)".to_string());
+    }
+
+    if let Some(group) = &profile.group {
+        if !config.groups.iter().any(|g| &g.id == group) {
+            warnings.push(format!("Group {} does not exist here; profile imported ungrouped", group));
+            profile.group = None;
+        }
+    }
+    for ext in &profile.extensions {
+        if !crate::commands::extensions::extension_dir(ext).is_dir() {
+            warnings.push(format!("Extension {} is not in the local library", ext));
+        }
+    }
+
+    // Reuse presets with the same URL; add the rest, under a new id if theirs is taken
+    let mut added = Vec::new();
+    for preset in bundled_presets {
+        if config.proxy_presets.iter().any(|p| p.url == preset.url) {
+            continue;
+        }
+        let mut preset = preset.clone();
+        if config.proxy_presets.iter().any(|p| p.id == preset.id) {
+            preset.id = uuid::Uuid::new_v4().to_string();
+        }
+        added.push(preset);
+    }
+
+    validation::validate_profile(&profile)?;
+    validation::check_profile_conflicts(&config.profiles, &profile, None)?;
+    Ok((profile, added, warnings))
+}
+
+// ---------------------------------------------------------------------------
+// Core functions (shared by Tauri commands + HTTP handlers)
+// ---------------------------------------------------------------------------
+
+/// Export a profile bundle to `dst`, with the settings it inherits from its
+/// groups filled in. Including the user data requires the browser to be stopped.
+pub async fn core_export_profile(state: &AppState, profile_id: &str, include_data: bool, dst: PathBuf) -> Result<()> {
+    let (profile, presets) = {
+        let config = state.config.read();
+        let profile = config
+            .profiles
+            .iter()
+            .find(|p| p.id == profile_id)
+            .map(|p| resolve_profile(p, &config.groups))
+            .ok_or_else(|| BrowsionError::ProfileNotFound(profile_id.to_string()))?;
+        let presets = referenced_presets(&config, &profile);
+        (profile, presets)
+    };
+    if include_data && state.process_manager.is_running(profile_id) {
+        return Err(BrowsionError::Conflict(format!(
+            "Browser must be stopped before exporting data of profile {}",
+            profile_id
+        )));
+    }
+    tokio::task::spawn_blocking(move || {
+        let result = std::fs::File::create(&dst)
+            .map_err(BrowsionError::from)
+            .and_then(|file| write_bundle(BufWriter::new(file), &profile, &presets, include_data))
+            .and_then(|mut writer| writer.flush().map_err(BrowsionError::from));
+        if result.is_err() {
+            let _ = std::fs::remove_file(&dst);
+        }
+        result
+    })
+    .await
+    .map_err(|e| BrowsionError::Process(e.to_string()))?
+}
+
+/// Import the bundle at `path` as a new profile.
+pub async fn core_import_profile(state: &AppState, path: &Path, opts: ImportOptions) -> Result<ImportReport> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| BrowsionError::Io(std::io::Error::new(e.kind(), format!("{}: {}", path.display(), e))))?;
+    let mut bundle = read_bundle(BufReader::new(file))?;
+    let (mut profile, presets, warnings) = {
+        let config = state.config.read();
+        plan_import(&config, &bundle.profile, &bundle.presets, &opts)?
+    };
+
+    let data_restored = bundle.manifest.includes_data;
+    let _guard = data_write_guard().await;
+    if data_restored {
+        if profile.user_data_dir.exists() {
+            return Err(BrowsionError::Conflict(format!(
+                "{} already exists",
+                profile.user_data_dir.display()
+            )));
+        }
+        let dst = profile.user_data_dir.clone();
+        let (returned, result) = tokio::task::spawn_blocking(move || {
+            let result = bundle.extract_data(&dst);
+            if result.is_err() {
+                let _ = std::fs::remove_dir_all(&dst);
+            }
+            (bundle, result)
+        })
+        .await
+        .map_err(|e| BrowsionError::Process(e.to_string()))?;
+        bundle = returned;
+        result?;
+    }
+
+    // Re-check under the write lock: the config may have changed meanwhile
+    let saved = {
+        let mut config = state.config.write();
+        validation::check_profile_conflicts(&config.profiles, &profile, None).and_then(|()| {
+            crate::config::fingerprint::assign_seed_if_missing(&mut profile, &config);
+            config.proxy_presets.extend(presets.iter().cloned());
+            config.profiles.push(profile.clone());
+            crate::config::save_config(&config)
+        })
+    };
+    if let Err(e) = saved {
+        if data_restored {
+            let _ = tokio::fs::remove_dir_all(&profile.user_data_dir).await;
+        }
+        return Err(e);
+    }
+    let added_ids: Vec<String> = presets.iter().map(|p| p.id.clone()).collect();
+    state.emit("profiles-changed");
+
+    let original_id = (profile.id != bundle.profile.id).then(|| bundle.profile.id.clone());
+    Ok(ImportReport {
+        profile,
+        manifest: bundle.manifest,
+        original_id,
+        proxy_presets_added: added_ids,
+        data_restored,
+        warnings,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+/// Export a profile bundle to `path`.
+#[tauri::command]
+pub async fn export_profile(
+    profile_id: String,
+    path: PathBuf,
+    include_data: bool,
+    state: State<'_, Arc<AppState>>,
+) -> std::result::Result<(), String> {
+    core_export_profile(&state, &profile_id, include_data, path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Import the profile bundle at `path`.
+#[tauri::command]
+pub async fn import_profile(
+    path: PathBuf,
+    options: Option<ImportOptions>,
+    state: State<'_, Arc<AppState>>,
+) -> std::result::Result<ImportReport, String> {
+    core_import_profile(&state, &path, options.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn profile(id: &str, dir: &Path) -> BrowserProfile {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "name": "Shop",
+            "user_data_dir": dir,
+            "proxy_server": "http://10.0.0.1:8080",
+            "group": "gone"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_bundle_round_trip_with_data() {
+        let src = std::env::temp_dir().join(format!("browsion-bundle-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(src.join("Default/Cache")).unwrap();
+        std::fs::write(src.join("Default/Cookies"), b"cookies").unwrap();
+        std::fs::write(src.join("Default/Cache/data_0"), b"cache").unwrap();
+        std::fs::write(src.join("SingletonLock"), b"").unwrap();
+
+        let preset = ProxyPreset {
+            id: "office".to_string(),
+            name: "Office".to_string(),
+            url: "http://10.0.0.1:8080".to_string(),
+        };
+        let bytes = write_bundle(Cursor::new(Vec::new()), &profile("p1", &src), &[preset], true)
+            .unwrap()
+            .into_inner();
+        let mut bundle = read_bundle(Cursor::new(bytes)).unwrap();
+        assert_eq!(bundle.manifest.version, BUNDLE_VERSION);
+        assert!(bundle.manifest.includes_data);
+        assert_eq!(bundle.profile.id, "p1");
+        assert_eq!(bundle.presets.len(), 1);
+
+        let dst = src.with_extension("imported");
+        bundle.extract_data(&dst).unwrap();
+        assert_eq!(std::fs::read(dst.join("Default/Cookies")).unwrap(), b"cookies");
+        assert!(!dst.join("Default/Cache").exists());
+        assert!(!dst.join("SingletonLock").exists());
+
+        let _ = std::fs::remove_dir_all(&src);
+        let _ = std::fs::remove_dir_all(&dst);
+    }
+
+    #[test]
+    fn test_read_bundle_rejects_newer_version() {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file(MANIFEST_ENTRY, Default::default()).unwrap();
+        zip.write_all(
+            serde_json::json!({
+                "format": BUNDLE_FORMAT,
+                "version": BUNDLE_VERSION + 1,
+                "app_version": "99.0.0",
+                "exported_at_ts": 0,
+                "profile_id": "p1",
+                "includes_data": false
+            })
+            .to_string()
+            .as_bytes(),
+        )
+        .unwrap();
+        let bytes = zip.finish().unwrap().into_inner();
+        let err = read_bundle(Cursor::new(bytes)).err().unwrap().to_string();
+        assert!(err.contains("newer"));
+    }
+
+    #[test]
+    fn test_plan_import_remaps_and_detects_conflicts() {
+        let mut config = AppConfig::default();
+        config.profiles.push(profile("p1", Path::new("/tmp/p1")));
+        config.proxy_presets.push(ProxyPreset {
+            id: "office".to_string(),
+            name: "Other office".to_string(),
+            url: "http://10.9.9.9:3128".to_string(),
+        });
+        let bundled = profile("p1", Path::new("/home/alice/.browsion/profiles/p1"));
+        let presets = vec![ProxyPreset {
+            id: "office".to_string(),
+            name: "Office".to_string(),
+            url: "http://10.0.0.1:8080".to_string(),
+        }];
+
+        let err = plan_import(&config, &bundled, &presets, &ImportOptions::default()).unwrap_err();
+        assert!(matches!(err, BrowsionError::Conflict(_)));
+
+        let opts = ImportOptions {
+            rename_on_conflict: true,
+            ..Default::default()
+        };
+        let (profile, added, warnings) = plan_import(&config, &bundled, &presets, &opts).unwrap();
+        assert_ne!(profile.id, "p1");
+        assert_eq!(profile.user_data_dir, default_user_data_dir(&profile.id));
+        assert_eq!(profile.group, None);
+        assert!(warnings.iter().any(|w| w.contains("gone")));
+        assert_eq!(added.len(), 1);
+        assert_ne!(added[0].id, "office");
+    }
+}
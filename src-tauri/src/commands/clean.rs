@@ -0,0 +1,457 @@
+//! Per-profile cleanup of caches, history and crash dumps.
+//!
+//! With the browser stopped the matching files are deleted from the user data
+//! dir (in every Chrome profile inside it: `Default`, `Profile 1`, ...). With the
+//! browser running, caches are cleared over CDP instead and categories that
+//! have no CDP equivalent are skipped. Bytes freed are measured on disk.
+//!
+//! Download history lives in the same `History` database as browsing history,
+//! so cleaning downloads alone deletes its rows from the download tables and
+//! leaves the file in place.
+
+use crate::commands::snapshots::dir_size;
+use crate::error::{BrowsionError, Result};
+use crate::process::cdp::CdpClient;
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::State;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CleanCategory {
+    HttpCache,
+    /// V8 code cache and GPU shader caches.
+    CodeCache,
+    /// Service worker registrations, scripts and Cache Storage.
+    ServiceWorkers,
+    History,
+    Downloads,
+    CrashDumps,
+}
+
+impl CleanCategory {
+    /// Used when a request selects nothing: the caches, but no user data.
+    pub const DEFAULT: &'static [CleanCategory] =
+        &[CleanCategory::HttpCache, CleanCategory::CodeCache, CleanCategory::ServiceWorkers];
+
+    /// Entries inside each Chrome profile directory.
+    fn profile_entries(self) -> &'static [&'static str] {
+        match self {
+            CleanCategory::HttpCache => &["Cache"],
+            CleanCategory::CodeCache => &["Code Cache", "GPUCache", "DawnGraphiteCache", "DawnWebGPUCache"],
+            CleanCategory::ServiceWorkers => &["Service Worker"],
+            CleanCategory::History => &[
+                "History",
+                "History-journal",
+                "Visited Links",
+                "Top Sites",
+                "Top Sites-journal",
+                "Shortcuts",
+                "Shortcuts-journal",
+            ],
+            CleanCategory::Downloads => &["DownloadMetadata"],
+            CleanCategory::CrashDumps => &[],
+        }
+    }
+
+    /// Tables emptied inside each profile's `History` database.
+    fn history_tables(self) -> &'static [&'static str] {
+        match self {
+            CleanCategory::Downloads => &["downloads_url_chains", "downloads_slices", "downloads"],
+            _ => &[],
+        }
+    }
+
+    /// Entries at the top of the user data dir.
+    fn root_entries(self) -> &'static [&'static str] {
+        match self {
+            CleanCategory::CodeCache => &["GrShaderCache", "GraphiteDawnCache", "ShaderCache"],
+            CleanCategory::CrashDumps => &["Crashpad/completed", "Crashpad/pending", "Crashpad/reports", "Crash Reports"],
+            _ => &[],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CleanRequest {
+    /// Categories to clean; empty means `CleanCategory::DEFAULT`.
+    #[serde(default)]
+    pub categories: Vec<CleanCategory>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CleanMode {
+    /// Files were deleted with the browser stopped.
+    Offline,
+    /// The running browser was asked over CDP.
+    Cdp,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CategoryResult {
+    pub category: CleanCategory,
+    pub bytes_freed: u64,
+    /// Why the category was skipped or only partly cleaned.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CleanReport {
+    pub profile_id: String,
+    pub mode: CleanMode,
+    pub bytes_freed: u64,
+    pub categories: Vec<CategoryResult>,
+}
+
+/// Chrome profile directories inside a user data dir (those with `Preferences`).
+fn chrome_profile_dirs(user_data_dir: &Path) -> Vec<PathBuf> {
+    let Ok(rd) = std::fs::read_dir(user_data_dir) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = rd
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.join("Preferences").is_file())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// Existing files and directories that make up `category` in `user_data_dir`.
+pub fn category_paths(user_data_dir: &Path, category: CleanCategory) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = category.root_entries().iter().map(|e| user_data_dir.join(e)).collect();
+    for dir in chrome_profile_dirs(user_data_dir) {
+        paths.extend(category.profile_entries().iter().map(|e| dir.join(e)));
+    }
+    paths.retain(|p| p.symlink_metadata().is_ok());
+    paths
+}
+
+async fn paths_size(paths: &[PathBuf]) -> u64 {
+    let mut total = 0;
+    for path in paths {
+        total += match tokio::fs::symlink_metadata(path).await {
+            Ok(meta) if meta.is_dir() => dir_size(path).await,
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        };
+    }
+    total
+}
+
+fn selected(req: &CleanRequest) -> Vec<CleanCategory> {
+    let categories: BTreeSet<CleanCategory> = if req.categories.is_empty() {
+        CleanCategory::DEFAULT.iter().copied().collect()
+    } else {
+        req.categories.iter().copied().collect()
+    };
+    categories.into_iter().collect()
+}
+
+/// Empty `tables` (those that exist) in a Chrome `History` database and compact it.
+fn clear_history_tables(db: &Path, tables: &[&str]) -> rusqlite::Result<()> {
+    let conn = rusqlite::Connection::open_with_flags(db, rusqlite::OpenFlags::SQLITE_OPEN_READ_WRITE)?;
+    for table in tables {
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1)",
+            [table],
+            |row| row.get(0),
+        )?;
+        if exists {
+            conn.execute(&format!("DELETE FROM \"{}\"", table), [])?;
+        }
+    }
+    conn.execute_batch("VACUUM")
+}
+
+/// Delete the files of each category. The browser must not be running.
+pub async fn clean_offline(user_data_dir: &Path, categories: &[CleanCategory]) -> Vec<CategoryResult> {
+    let mut results = Vec::new();
+    for &category in categories {
+        let mut freed = 0;
+        let mut skipped = None;
+        for path in category_paths(user_data_dir, category) {
+            let size = paths_size(std::slice::from_ref(&path)).await;
+            let removed = if path.is_dir() {
+                tokio::fs::remove_dir_all(&path).await
+            } else {
+                tokio::fs::remove_file(&path).await
+            };
+            match removed {
+                Ok(()) => freed += size,
+                Err(e) => skipped = Some(format!("{}: {}", path.display(), e)),
+            }
+        }
+        let tables = category.history_tables();
+        if !tables.is_empty() {
+            for db in chrome_profile_dirs(user_data_dir).into_iter().map(|d| d.join("History")) {
+                if !db.is_file() {
+                    continue;
+                }
+                let before = paths_size(std::slice::from_ref(&db)).await;
+                let path = db.clone();
+                let cleared = tokio::task::spawn_blocking(move || clear_history_tables(&path, tables))
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|r| r.map_err(|e| e.to_string()));
+                match cleared {
+                    Ok(()) => freed += before.saturating_sub(paths_size(std::slice::from_ref(&db)).await),
+                    Err(e) => skipped = Some(format!("{}: {}", db.display(), e)),
+                }
+            }
+        }
+        results.push(CategoryResult {
+            category,
+            bytes_freed: freed,
+            skipped,
+        });
+    }
+    results
+}
+
+/// `scheme://host[:port]` of an http(s) URL.
+fn origin_of(url: &str) -> Option<String> {
+    let (scheme, rest) = url.split_once("://")?;
+    if scheme != "http" && scheme != "https" {
+        return None;
+    }
+    let host = rest.split(['/', '?', '#']).next().filter(|h| !h.is_empty())?;
+    Some(format!("{}://{}", scheme, host))
+}
+
+/// A flattened session on some page of the browser, opening a blank tab when
+/// there is none. Returns the session id and the tab to close afterwards.
+async fn page_session(cdp: &mut CdpClient) -> Result<(String, Option<String>)> {
+    let targets = cdp.call("Target.getTargets", json!({}), None).await?;
+    let existing = targets["targetInfos"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|t| t["type"] == "page")
+        .and_then(|t| t["targetId"].as_str())
+        .map(str::to_string);
+    let (target_id, created) = match existing {
+        Some(id) => (id, None),
+        None => {
+            let created = cdp.call("Target.createTarget", json!({ "url": "about:blank" }), None).await?;
+            let id = created["targetId"].as_str().unwrap_or_default().to_string();
+            (id.clone(), Some(id))
+        }
+    };
+    let attached = cdp
+        .call("Target.attachToTarget", json!({ "targetId": target_id, "flatten": true }), None)
+        .await?;
+    let session_id = attached["sessionId"]
+        .as_str()
+        .ok_or_else(|| BrowsionError::CdpCommand("Target.attachToTarget returned no sessionId".to_string()))?;
+    Ok((session_id.to_string(), created))
+}
+
+/// Origins with service worker registrations, plus those of open pages and workers.
+async fn service_worker_origins(cdp: &mut CdpClient, session_id: &str) -> Result<BTreeSet<String>> {
+    let mut origins = BTreeSet::new();
+    cdp.call("ServiceWorker.enable", json!({}), Some(session_id)).await?;
+    cdp.collect_events(Duration::from_millis(500)).await?;
+    for event in cdp.take_events("ServiceWorker.workerRegistrationUpdated") {
+        for registration in event["registrations"].as_array().into_iter().flatten() {
+            origins.extend(registration["scopeURL"].as_str().and_then(origin_of));
+        }
+    }
+    let _ = cdp.call("ServiceWorker.disable", json!({}), Some(session_id)).await;
+
+    let targets = cdp.call("Target.getTargets", json!({}), None).await?;
+    for target in targets["targetInfos"].as_array().into_iter().flatten() {
+        origins.extend(target["url"].as_str().and_then(origin_of));
+    }
+    Ok(origins)
+}
+
+/// Clear caches through the running browser.
+async fn clean_over_cdp(cdp_port: u16, user_data_dir: &Path, categories: &[CleanCategory]) -> Result<Vec<CategoryResult>> {
+    let mut before = Vec::new();
+    for &category in categories {
+        before.push(paths_size(&category_paths(user_data_dir, category)).await);
+    }
+
+    let mut cdp = CdpClient::connect(cdp_port).await?;
+    let (session_id, created) = page_session(&mut cdp).await?;
+    let mut skipped: Vec<Option<String>> = vec![None; categories.len()];
+    let mut cache_cleared = false;
+    for (i, &category) in categories.iter().enumerate() {
+        match category {
+            // Chrome drops the code caches together with the HTTP cache
+            CleanCategory::HttpCache | CleanCategory::CodeCache => {
+                if !cache_cleared {
+                    cdp.call("Network.clearBrowserCache", json!({}), Some(&session_id)).await?;
+                    cache_cleared = true;
+                }
+            }
+            CleanCategory::ServiceWorkers => {
+                for origin in service_worker_origins(&mut cdp, &session_id).await? {
+                    cdp.call(
+                        "Storage.clearDataForOrigin",
+                        json!({ "origin": origin, "storageTypes": "service_workers,cache_storage" }),
+                        None,
+                    )
+                    .await?;
+                }
+            }
+            _ => skipped[i] = Some("stop the browser to clean this category".to_string()),
+        }
+    }
+    if let Some(target_id) = created {
+        let _ = cdp.call("Target.closeTarget", json!({ "targetId": target_id }), None).await;
+    }
+
+    let mut results = Vec::new();
+    for ((&category, before), skipped) in categories.iter().zip(before).zip(skipped) {
+        let after = paths_size(&category_paths(user_data_dir, category)).await;
+        results.push(CategoryResult {
+            category,
+            bytes_freed: before.saturating_sub(after),
+            skipped,
+        });
+    }
+    Ok(results)
+}
+
+// ---------------------------------------------------------------------------
+// Core functions (shared by Tauri commands + HTTP handlers)
+// ---------------------------------------------------------------------------
+
+pub async fn core_clean_profile(state: &AppState, profile_id: &str, req: &CleanRequest) -> Result<CleanReport> {
+    let user_data_dir = state
+        .config
+        .read()
+        .profiles
+        .iter()
+        .find(|p| p.id == profile_id)
+        .map(|p| p.user_data_dir.clone())
+        .ok_or_else(|| BrowsionError::ProfileNotFound(profile_id.to_string()))?;
+    let categories = selected(req);
+
+    let (mode, results) = if state.process_manager.is_running(profile_id) {
+        let cdp_port = state.process_manager.get_cdp_port(profile_id).ok_or_else(|| {
+            BrowsionError::Conflict(format!(
+                "Profile {} is running without a CDP port; stop it to clean its data",
+                profile_id
+            ))
+        })?;
+        (CleanMode::Cdp, clean_over_cdp(cdp_port, &user_data_dir, &categories).await?)
+    } else {
+        // Held across the deletes so the profile cannot be launched meanwhile
+        let _reservation = state.process_manager.reserve_profile(profile_id)?;
+        // Refuses a directory held by a Chrome that Browsion did not launch
+        state.process_manager.recover_profile_lock(profile_id, &user_data_dir)?;
+        (CleanMode::Offline, clean_offline(&user_data_dir, &categories).await)
+    };
+
+    let bytes_freed = results.iter().map(|r| r.bytes_freed).sum();
+    tracing::info!("Cleaned profile {} ({:?}): {} bytes freed", profile_id, mode, bytes_freed);
+    Ok(CleanReport {
+        profile_id: profile_id.to_string(),
+        mode,
+        bytes_freed,
+        categories: results,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+#[tauri::command]
+pub async fn clean_profile(
+    profile_id: String,
+    categories: Option<Vec<CleanCategory>>,
+    state: State<'_, Arc<AppState>>,
+) -> std::result::Result<CleanReport, String> {
+    let req = CleanRequest {
+        categories: categories.unwrap_or_default(),
+    };
+    core_clean_profile(&state, &profile_id, &req).await.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_clean_offline_removes_only_selected_categories() {
+        let dir = std::env::temp_dir().join(format!("browsion-clean-{}", uuid::Uuid::new_v4()));
+        for profile in ["Default", "Profile 1"] {
+            std::fs::create_dir_all(dir.join(profile).join("Cache/Cache_Data")).unwrap();
+            std::fs::write(dir.join(profile).join("Preferences"), b"{}").unwrap();
+            std::fs::write(dir.join(profile).join("Cache/Cache_Data/data_0"), vec![0u8; 1000]).unwrap();
+            std::fs::write(dir.join(profile).join("History"), vec![0u8; 10]).unwrap();
+        }
+        std::fs::create_dir_all(dir.join("GrShaderCache")).unwrap();
+        std::fs::write(dir.join("GrShaderCache/data_1"), vec![0u8; 24]).unwrap();
+        // Not a Chrome profile: left alone
+        std::fs::create_dir_all(dir.join("Other/Cache")).unwrap();
+
+        let results = clean_offline(&dir, &[CleanCategory::HttpCache, CleanCategory::CodeCache]).await;
+        assert_eq!(results[0].bytes_freed, 2000);
+        assert_eq!(results[1].bytes_freed, 24);
+        assert!(results.iter().all(|r| r.skipped.is_none()));
+        assert!(!dir.join("Default/Cache").exists());
+        assert!(!dir.join("Profile 1/Cache").exists());
+        assert!(!dir.join("GrShaderCache").exists());
+        assert!(dir.join("Default/History").exists());
+        assert!(dir.join("Other/Cache").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_clean_offline_downloads_keeps_browsing_history() {
+        let dir = std::env::temp_dir().join(format!("browsion-clean-{}", uuid::Uuid::new_v4()));
+        let profile = dir.join("Default");
+        std::fs::create_dir_all(profile.join("DownloadMetadata")).unwrap();
+        std::fs::write(profile.join("Preferences"), b"{}").unwrap();
+        std::fs::write(profile.join("DownloadMetadata/meta"), vec![0u8; 16]).unwrap();
+        let conn = rusqlite::Connection::open(profile.join("History")).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE urls (id INTEGER PRIMARY KEY, url TEXT);
+             CREATE TABLE downloads (id INTEGER PRIMARY KEY, target_path TEXT);
+             CREATE TABLE downloads_url_chains (id INTEGER, chain_index INTEGER, url TEXT);
+             INSERT INTO urls (url) VALUES ('https://example.com/');
+             INSERT INTO downloads (target_path) VALUES ('/tmp/report.pdf');
+             INSERT INTO downloads_url_chains VALUES (1, 0, 'https://example.com/report.pdf');",
+        )
+        .unwrap();
+        drop(conn);
+
+        let results = clean_offline(&dir, &[CleanCategory::Downloads]).await;
+        assert!(results[0].skipped.is_none(), "{:?}", results[0].skipped);
+        assert!(results[0].bytes_freed >= 16);
+        assert!(!profile.join("DownloadMetadata").exists());
+        let conn = rusqlite::Connection::open(profile.join("History")).unwrap();
+        let count = |table: &str| -> i64 {
+            conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(count("downloads"), 0);
+        assert_eq!(count("downloads_url_chains"), 0);
+        assert_eq!(count("urls"), 1);
+        drop(conn);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_origin_of_and_default_selection() {
+        assert_eq!(origin_of("https://shop.example.com:8443/sw.js?v=1").as_deref(), Some("https://shop.example.com:8443"));
+        assert_eq!(origin_of("chrome://newtab/"), None);
+        assert_eq!(selected(&CleanRequest::default()), CleanCategory::DEFAULT);
+        let req = CleanRequest {
+            categories: vec![CleanCategory::History, CleanCategory::HttpCache, CleanCategory::History],
+        };
+        assert_eq!(selected(&req), [CleanCategory::HttpCache, CleanCategory::History]);
+    }
+}
@@ -0,0 +1,94 @@
+//! Discover and adopt Chrome instances that Browsion did not launch.
+
+use crate::error::{BrowsionError, Result};
+use crate::process::discovery::{same_dir, DiscoveredBrowser};
+use crate::state::AppState;
+use std::sync::Arc;
+use tauri::State;
+
+// ---------------------------------------------------------------------------
+// Core functions (shared by Tauri commands + HTTP handlers)
+// ---------------------------------------------------------------------------
+
+/// All Chrome browsers with a debugging port, matched to configured profiles.
+pub fn core_discover_browsers(state: &AppState) -> Vec<DiscoveredBrowser> {
+    let mut browsers = state.process_manager.discover_browsers();
+    let config = state.config.read();
+    for b in &mut browsers {
+        if let Some(dir) = &b.user_data_dir {
+            b.profile_id = config
+                .profiles
+                .iter()
+                .find(|p| same_dir(&p.user_data_dir, dir))
+                .map(|p| p.id.clone());
+        }
+    }
+    browsers
+}
+
+/// Register the browser already running on the profile's user_data_dir.
+/// Returns `(pid, cdp_port)`. `BrowserNotRunning` when no browser uses the
+/// directory, `CdpConnection` when its debugging port does not answer.
+pub async fn core_attach_profile(state: &AppState, profile_id: &str) -> Result<(u32, u16)> {
+    let user_data_dir = {
+        let config = state.config.read();
+        config
+            .profiles
+            .iter()
+            .find(|p| p.id == profile_id)
+            .map(|p| p.user_data_dir.clone())
+            .ok_or_else(|| BrowsionError::ProfileNotFound(profile_id.to_string()))?
+    };
+
+    if state.process_manager.is_running(profile_id) {
+        return Err(BrowsionError::Conflict(format!("Profile {} is already running", profile_id)));
+    }
+
+    let (pid, cdp_port) = state.process_manager.find_external_browser(&user_data_dir)?;
+
+    // DevToolsActivePort survives crashes; make sure the port really answers.
+    if crate::process::cdp::fetch_version(cdp_port).await.is_err() {
+        return Err(BrowsionError::CdpConnection(format!(
+            "Browser pid {} does not answer on CDP port {}",
+            pid, cdp_port
+        )));
+    }
+
+    state
+        .process_manager
+        .register_external(profile_id, pid, cdp_port, Some(user_data_dir));
+
+    if let Some(info) = state.process_manager.get_process_info(profile_id) {
+        tokio::spawn(async move {
+            if let Err(e) = crate::process::sessions_persist::save_session(&info).await {
+                tracing::warn!("Failed to persist session for {}: {}", info.profile_id, e);
+            }
+        });
+    }
+
+    state.emit("browser-status-changed");
+    Ok((pid, cdp_port))
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+#[tauri::command]
+pub async fn discover_browsers(
+    state: State<'_, Arc<AppState>>,
+) -> std::result::Result<Vec<DiscoveredBrowser>, String> {
+    Ok(core_discover_browsers(&state))
+}
+
+/// Adopt an already-running browser for a profile. Returns the CDP port.
+#[tauri::command]
+pub async fn attach_profile(
+    profile_id: String,
+    state: State<'_, Arc<AppState>>,
+) -> std::result::Result<u16, String> {
+    let (_pid, cdp_port) = core_attach_profile(&state, &profile_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(cdp_port)
+}
@@ -0,0 +1,382 @@
+//! Extension library: unpacked Chrome extensions shared by profiles.
+//!
+//! Extensions live under ~/.browsion/extensions/<id>/ (unpacked, manifest.json at
+//! the root) with an index at ~/.browsion/extensions/index.json. Profiles list the
+//! ids they use in `BrowserProfile::extensions`; at launch those directories are
+//! passed to Chrome with `--load-extension` and `--disable-extensions-except`.
+
+use crate::config::schema::{AppConfig, ExtensionInfo};
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::State;
+
+fn extensions_root() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".browsion")
+        .join("extensions")
+}
+
+/// Directory of an installed extension.
+pub fn extension_dir(id: &str) -> PathBuf {
+    extensions_root().join(id)
+}
+
+fn index_path() -> PathBuf {
+    extensions_root().join("index.json")
+}
+
+// Index: map of extension id → ExtensionInfo
+type Index = HashMap<String, ExtensionInfo>;
+
+fn load_index() -> Index {
+    match std::fs::read_to_string(index_path()) {
+        Ok(text) => serde_json::from_str(&text).unwrap_or_default(),
+        Err(_) => HashMap::new(),
+    }
+}
+
+fn save_index(index: &Index) -> Result<(), String> {
+    std::fs::create_dir_all(extensions_root()).map_err(|e| e.to_string())?;
+    let text = serde_json::to_string_pretty(index).map_err(|e| e.to_string())?;
+    std::fs::write(index_path(), text).map_err(|e| e.to_string())
+}
+
+/// Library ids: lowercase letters, digits, `-` and `_`.
+pub fn is_valid_extension_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 64
+        && id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+/// Derive a library id from an extension name ("Cookie Editor" → "cookie-editor").
+pub fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    slug.chars().take(64).collect()
+}
+
+/// Offset of the zip payload in a CRX file (versions 2 and 3); 0 for a plain zip.
+pub fn crx_zip_offset(bytes: &[u8]) -> Result<usize, String> {
+    if !bytes.starts_with(b"Cr24") {
+        return Ok(0);
+    }
+    let read_u32 = |at: usize| -> Result<usize, String> {
+        bytes
+            .get(at..at + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+            .ok_or_else(|| "Truncated CRX header".to_string())
+    };
+    let offset = match read_u32(4)? {
+        // Cr24, version, header length, header (protobuf)
+        3 => 12 + read_u32(8)?,
+        // Cr24, version, key length, signature length, key, signature
+        2 => 16 + read_u32(8)? + read_u32(12)?,
+        v => return Err(format!("Unsupported CRX version {}", v)),
+    };
+    if offset > bytes.len() {
+        return Err("Truncated CRX header".to_string());
+    }
+    Ok(offset)
+}
+
+/// The directory holding manifest.json: `dir` itself or its single subdirectory
+/// (zips made by "compress folder" wrap everything in one).
+fn find_manifest_root(dir: &Path) -> Option<PathBuf> {
+    if dir.join("manifest.json").is_file() {
+        return Some(dir.to_path_buf());
+    }
+    let entries: Vec<_> = std::fs::read_dir(dir).ok()?.flatten().collect();
+    match entries.as_slice() {
+        [only] if only.path().join("manifest.json").is_file() => Some(only.path()),
+        _ => None,
+    }
+}
+
+/// Resolve a `__MSG_key__` manifest string through `_locales/<default_locale>`.
+fn localize(root: &Path, manifest: &serde_json::Value, value: &str) -> String {
+    let Some(key) = value
+        .strip_prefix("__MSG_")
+        .and_then(|v| v.strip_suffix("__"))
+    else {
+        return value.to_string();
+    };
+    let locale = manifest
+        .get("default_locale")
+        .and_then(|v| v.as_str())
+        .unwrap_or("en");
+    let messages: serde_json::Value = std::fs::read_to_string(
+        root.join("_locales").join(locale).join("messages.json"),
+    )
+    .ok()
+    .and_then(|t| serde_json::from_str(&t).ok())
+    .unwrap_or_default();
+    messages
+        .as_object()
+        .and_then(|m| m.iter().find(|(k, _)| k.eq_ignore_ascii_case(key)))
+        .and_then(|(_, v)| v.get("message"))
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| value.to_string())
+}
+
+/// Unpack a zip/CRX file into the library. Returns the installed extension.
+fn install_from_file(path: &Path, id: Option<&str>) -> Result<ExtensionInfo, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    let offset = crx_zip_offset(&bytes)?;
+    let mut archive = zip::ZipArchive::new(Cursor::new(&bytes[offset..]))
+        .map_err(|e| format!("Invalid extension archive: {}", e))?;
+
+    let root = extensions_root();
+    let staging = root.join(format!(".install-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&staging).map_err(|e| e.to_string())?;
+    let result = (|| {
+        archive
+            .extract(&staging)
+            .map_err(|e| format!("Failed to unpack extension: {}", e))?;
+        let manifest_root = find_manifest_root(&staging)
+            .ok_or_else(|| "Archive has no manifest.json".to_string())?;
+        let manifest: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(manifest_root.join("manifest.json")).map_err(|e| e.to_string())?,
+        )
+        .map_err(|e| format!("Invalid manifest.json: {}", e))?;
+
+        let name = manifest
+            .get("name")
+            .and_then(|v| v.as_str())
+            .map(|n| localize(&manifest_root, &manifest, n))
+            .ok_or_else(|| "manifest.json has no name".to_string())?;
+        let version = manifest
+            .get("version")
+            .and_then(|v| v.as_str())
+            .unwrap_or("0")
+            .to_string();
+        let id = match id {
+            Some(id) => id.to_string(),
+            None => slugify(&name),
+        };
+        if !is_valid_extension_id(&id) {
+            return Err(format!(
+                "Invalid extension id '{}'. Use lowercase letters, digits, '-' or '_'",
+                id
+            ));
+        }
+
+        let dst = extension_dir(&id);
+        if dst.exists() {
+            std::fs::remove_dir_all(&dst).map_err(|e| format!("Failed to replace {}: {}", id, e))?;
+        }
+        std::fs::rename(&manifest_root, &dst).map_err(|e| e.to_string())?;
+
+        Ok(ExtensionInfo {
+            id,
+            name,
+            version,
+            manifest_version: manifest
+                .get("manifest_version")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0) as u32,
+            source: path.to_path_buf(),
+            installed_at_ts: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+        })
+    })();
+    let _ = std::fs::remove_dir_all(&staging);
+    result
+}
+
+/// An installed extension together with the profiles that enable it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtensionEntry {
+    #[serde(flatten)]
+    pub info: ExtensionInfo,
+    pub used_by: Vec<String>,
+}
+
+fn used_by(config: &AppConfig, id: &str) -> Vec<String> {
+    config
+        .profiles
+        .iter()
+        .filter(|p| p.extensions.iter().any(|e| e == id))
+        .map(|p| p.id.clone())
+        .collect()
+}
+
+/// Unpacked directories for the given library ids, in order.
+pub fn resolve_extension_dirs(ids: &[String]) -> Result<Vec<PathBuf>, String> {
+    ids.iter()
+        .map(|id| {
+            let dir = extension_dir(id);
+            if dir.join("manifest.json").is_file() {
+                Ok(dir)
+            } else {
+                Err(format!("Extension '{}' is not installed", id))
+            }
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Core functions (shared by Tauri commands + HTTP handlers)
+// ---------------------------------------------------------------------------
+
+pub async fn core_list_extensions(config: &AppConfig) -> Result<Vec<ExtensionEntry>, String> {
+    let index = tokio::task::spawn_blocking(load_index)
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut entries: Vec<ExtensionEntry> = index
+        .into_values()
+        .map(|info| ExtensionEntry {
+            used_by: used_by(config, &info.id),
+            info,
+        })
+        .collect();
+    entries.sort_by(|a, b| a.info.id.cmp(&b.info.id));
+    Ok(entries)
+}
+
+pub async fn core_get_extension(config: &AppConfig, id: &str) -> Result<Option<ExtensionEntry>, String> {
+    let index = tokio::task::spawn_blocking(load_index)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(index.get(id).cloned().map(|info| ExtensionEntry {
+        used_by: used_by(config, id),
+        info,
+    }))
+}
+
+/// Install (or replace) an extension from a local `.zip` or `.crx` file. The id
+/// defaults to a slug of the manifest name.
+pub async fn core_install_extension(
+    config: &AppConfig,
+    path: PathBuf,
+    id: Option<String>,
+) -> Result<ExtensionEntry, String> {
+    let info = tokio::task::spawn_blocking(move || {
+        let info = install_from_file(&path, id.as_deref())?;
+        let mut index = load_index();
+        index.insert(info.id.clone(), info.clone());
+        save_index(&index)?;
+        Ok::<_, String>(info)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    Ok(ExtensionEntry {
+        used_by: used_by(config, &info.id),
+        info,
+    })
+}
+
+/// Remove an extension from the library. Fails while profiles still enable it.
+pub async fn core_remove_extension(config: &AppConfig, id: &str) -> Result<(), String> {
+    let users = used_by(config, id);
+    if !users.is_empty() {
+        return Err(format!(
+            "Extension '{}' is used by profiles: {}",
+            id,
+            users.join(", ")
+        ));
+    }
+    let id = id.to_string();
+    tokio::task::spawn_blocking(move || {
+        let mut index = load_index();
+        if index.remove(&id).is_none() {
+            return Err(format!("Extension '{}' not found", id));
+        }
+        let dir = extension_dir(&id);
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir).map_err(|e| format!("Failed to delete {}: {}", id, e))?;
+        }
+        save_index(&index)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+#[tauri::command]
+pub async fn list_extensions(state: State<'_, Arc<AppState>>) -> Result<Vec<ExtensionEntry>, String> {
+    let config = state.config.read().clone();
+    core_list_extensions(&config).await
+}
+
+#[tauri::command]
+pub async fn install_extension(
+    path: String,
+    id: Option<String>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<ExtensionEntry, String> {
+    let config = state.config.read().clone();
+    core_install_extension(&config, PathBuf::from(path), id).await
+}
+
+#[tauri::command]
+pub async fn remove_extension(id: String, state: State<'_, Arc<AppState>>) -> Result<(), String> {
+    let config = state.config.read().clone();
+    core_remove_extension(&config, &id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Cookie Editor"), "cookie-editor");
+        assert_eq!(slugify("  MetaMask™ Wallet!! "), "metamask-wallet");
+        assert!(is_valid_extension_id(&slugify("uBlock Origin")));
+        assert!(!is_valid_extension_id("../etc"));
+    }
+
+    #[test]
+    fn test_crx_zip_offset() {
+        assert_eq!(crx_zip_offset(b"PK\x03\x04rest").unwrap(), 0);
+
+        let mut crx3 = b"Cr24".to_vec();
+        crx3.extend(3u32.to_le_bytes());
+        crx3.extend(5u32.to_le_bytes());
+        crx3.extend([0u8; 5]);
+        crx3.extend(b"PK\x03\x04");
+        assert_eq!(crx_zip_offset(&crx3).unwrap(), 17);
+
+        let mut crx2 = b"Cr24".to_vec();
+        crx2.extend(2u32.to_le_bytes());
+        crx2.extend(2u32.to_le_bytes());
+        crx2.extend(3u32.to_le_bytes());
+        crx2.extend([0u8; 5]);
+        assert_eq!(crx_zip_offset(&crx2).unwrap(), 21);
+
+        let mut bad = b"Cr24".to_vec();
+        bad.extend(3u32.to_le_bytes());
+        bad.extend(1000u32.to_le_bytes());
+        assert!(crx_zip_offset(&bad).is_err());
+    }
+
+    #[test]
+    fn test_find_manifest_root_in_wrapper_dir() {
+        let dir = std::env::temp_dir().join(format!("browsion-ext-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("my-ext")).unwrap();
+        std::fs::write(dir.join("my-ext").join("manifest.json"), "{}").unwrap();
+        assert_eq!(find_manifest_root(&dir), Some(dir.join("my-ext")));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
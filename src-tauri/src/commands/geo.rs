@@ -0,0 +1,444 @@
+//! Consistency check between a profile's proxy exit IP and its timezone / language.
+//!
+//! The exit IP is resolved by requesting the configured IP-info endpoint through the
+//! profile's proxy (directly when it has none). Its country and timezone are compared
+//! with `BrowserProfile::timezone` and the region of `BrowserProfile::lang`.
+
+use crate::commands::proxy::proxy_client;
+use crate::config::schema::{AppConfig, BrowserProfile, GeoCheckMode, GeoCheckSettings};
+use crate::error::{BrowsionError, Result as BrowsionResult};
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::State;
+
+/// What the IP-info endpoint reported for the exit IP.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GeoInfo {
+    pub ip: Option<String>,
+    /// ISO 3166-1 alpha-2, upper case.
+    pub country: Option<String>,
+    /// IANA timezone name.
+    pub timezone: Option<String>,
+    /// Languages spoken in the country, most common first (only some endpoints report them).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub languages: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeoMismatch {
+    /// `timezone` or `lang`
+    pub field: String,
+    /// The profile's current value (None = not set).
+    pub current: Option<String>,
+    /// Value matching the exit IP.
+    pub expected: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeoCheckReport {
+    pub profile_id: String,
+    pub proxy_server: Option<String>,
+    pub exit: GeoInfo,
+    pub mismatches: Vec<GeoMismatch>,
+    /// True when the mismatches were fixed in the stored profile.
+    pub aligned: bool,
+}
+
+/// Most common language tag per country, for countries whose IP-info response
+/// does not list languages.
+const COUNTRY_LANGUAGES: &[(&str, &str)] = &[
+    ("AR", "es-AR"),
+    ("AT", "de-AT"),
+    ("AU", "en-AU"),
+    ("BE", "nl-BE"),
+    ("BR", "pt-BR"),
+    ("CA", "en-CA"),
+    ("CH", "de-CH"),
+    ("CL", "es-CL"),
+    ("CN", "zh-CN"),
+    ("CO", "es-CO"),
+    ("CZ", "cs-CZ"),
+    ("DE", "de-DE"),
+    ("DK", "da-DK"),
+    ("ES", "es-ES"),
+    ("FI", "fi-FI"),
+    ("FR", "fr-FR"),
+    ("GB", "en-GB"),
+    ("GR", "el-GR"),
+    ("HK", "zh-HK"),
+    ("HU", "hu-HU"),
+    ("ID", "id-ID"),
+    ("IE", "en-IE"),
+    ("IL", "he-IL"),
+    ("IN", "en-IN"),
+    ("IT", "it-IT"),
+    ("JP", "ja-JP"),
+    ("KR", "ko-KR"),
+    ("MX", "es-MX"),
+    ("MY", "ms-MY"),
+    ("NL", "nl-NL"),
+    ("NO", "nb-NO"),
+    ("NZ", "en-NZ"),
+    ("PH", "en-PH"),
+    ("PL", "pl-PL"),
+    ("PT", "pt-PT"),
+    ("RO", "ro-RO"),
+    ("RU", "ru-RU"),
+    ("SA", "ar-SA"),
+    ("SE", "sv-SE"),
+    ("SG", "en-SG"),
+    ("TH", "th-TH"),
+    ("TR", "tr-TR"),
+    ("TW", "zh-TW"),
+    ("UA", "uk-UA"),
+    ("US", "en-US"),
+    ("VN", "vi-VN"),
+    ("ZA", "en-ZA"),
+];
+
+fn str_field<'a>(value: &'a serde_json::Value, keys: &[&str]) -> Option<&'a str> {
+    keys.iter()
+        .filter_map(|k| value.get(*k).and_then(|v| v.as_str()))
+        .map(str::trim)
+        .find(|s| !s.is_empty())
+}
+
+/// Read an IP-info response. Understands ipinfo.io (`ip`, `country`, `timezone`),
+/// ip-api.com (`query`, `countryCode`), ipapi.co (`country_code`, `languages`) and
+/// ipwho.is (`timezone.id`).
+pub fn parse_ip_info(value: &serde_json::Value) -> Result<GeoInfo, String> {
+    if value.get("status").and_then(|v| v.as_str()) == Some("fail")
+        || value.get("error").is_some_and(|v| v.as_bool() != Some(false))
+    {
+        return Err(format!("IP-info endpoint returned an error: {}", value));
+    }
+    let country = str_field(value, &["country_code", "countryCode", "country"])
+        .filter(|c| c.len() == 2 && c.chars().all(|ch| ch.is_ascii_alphabetic()))
+        .map(|c| c.to_ascii_uppercase());
+    let timezone = str_field(value, &["timezone", "time_zone"])
+        .or_else(|| value.get("timezone").and_then(|tz| str_field(tz, &["id", "name"])))
+        .map(str::to_string);
+    let languages = str_field(value, &["languages"])
+        .map(|l| {
+            l.split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    let info = GeoInfo {
+        ip: str_field(value, &["ip", "query"]).map(str::to_string),
+        country,
+        timezone,
+        languages,
+    };
+    if info.country.is_none() && info.timezone.is_none() {
+        return Err("IP-info response has neither a country nor a timezone".to_string());
+    }
+    Ok(info)
+}
+
+/// Resolve the exit IP of `proxy` (None = direct) through `ip_info_url`.
+pub async fn lookup_exit(proxy: Option<&str>, ip_info_url: &str) -> Result<GeoInfo, String> {
+    let client = proxy_client(proxy)?;
+    let response = client
+        .get(ip_info_url)
+        .header(reqwest::header::ACCEPT, "application/json")
+        .send()
+        .await
+        .map_err(|e| format!("IP lookup failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("IP lookup failed: HTTP {}", response.status()));
+    }
+    let value: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("IP lookup returned invalid JSON: {}", e))?;
+    parse_ip_info(&value)
+}
+
+/// `(language, region)` of a tag like `en-US` / `en_US` / `en`.
+fn split_lang(tag: &str) -> (String, Option<String>) {
+    let mut parts = tag.split(['-', '_']);
+    let language = parts.next().unwrap_or("").to_ascii_lowercase();
+    let region = parts
+        .find(|p| p.len() == 2 && p.chars().all(|c| c.is_ascii_alphabetic()))
+        .map(|p| p.to_ascii_uppercase());
+    (language, region)
+}
+
+/// Language tag that fits `geo`: the first listed language with a region, else the
+/// country's most common language.
+pub fn expected_lang(geo: &GeoInfo) -> Option<String> {
+    let country = geo.country.as_deref()?;
+    geo.languages
+        .iter()
+        .find(|l| split_lang(l).1.as_deref() == Some(country))
+        .cloned()
+        .or_else(|| {
+            COUNTRY_LANGUAGES
+                .iter()
+                .find(|(c, _)| *c == country)
+                .map(|(_, l)| l.to_string())
+        })
+}
+
+/// Compare `profile` with the exit IP's location.
+pub fn find_mismatches(profile: &BrowserProfile, geo: &GeoInfo) -> Vec<GeoMismatch> {
+    let mut mismatches = Vec::new();
+
+    if let Some(tz) = &geo.timezone {
+        let matches = profile
+            .timezone
+            .as_deref()
+            .is_some_and(|current| current.eq_ignore_ascii_case(tz));
+        if !matches {
+            mismatches.push(GeoMismatch {
+                field: "timezone".to_string(),
+                current: profile.timezone.clone(),
+                expected: tz.clone(),
+            });
+        }
+    }
+
+    if let Some(country) = &geo.country {
+        let (language, region) = split_lang(&crate::config::locale::primary_language(&profile.lang));
+        let expected = expected_lang(geo);
+        let matches = match &region {
+            Some(region) => region == country,
+            // A bare language is fine if it is what the country speaks
+            None => expected
+                .as_deref()
+                .is_none_or(|e| split_lang(e).0 == language),
+        };
+        if !matches {
+            mismatches.push(GeoMismatch {
+                field: "lang".to_string(),
+                current: Some(profile.lang.clone()),
+                expected: expected.unwrap_or_else(|| format!("{}-{}", language, country)),
+            });
+        }
+    }
+
+    mismatches
+}
+
+/// Copy of `profile` with every mismatch set to its expected value.
+pub fn align_profile(profile: &BrowserProfile, mismatches: &[GeoMismatch]) -> BrowserProfile {
+    let mut aligned = profile.clone();
+    for m in mismatches {
+        match m.field.as_str() {
+            "timezone" => aligned.timezone = Some(m.expected.clone()),
+            "lang" => aligned.lang = m.expected.clone(),
+            _ => {}
+        }
+    }
+    aligned
+}
+
+fn describe(m: &GeoMismatch) -> String {
+    format!(
+        "{} is {} but the proxy exit IP suggests {}",
+        m.field,
+        m.current.as_deref().unwrap_or("not set"),
+        m.expected
+    )
+}
+
+/// Check run by `ProcessManager` before a launch, according to
+/// `settings.before_launch`. Returns the profile to launch with when it was aligned.
+pub async fn pre_launch_check(
+    profile: &BrowserProfile,
+    settings: &GeoCheckSettings,
+) -> BrowsionResult<Option<BrowserProfile>> {
+    if settings.before_launch == GeoCheckMode::Off {
+        return Ok(None);
+    }
+    let geo = match lookup_exit(profile.proxy_server.as_deref(), &settings.ip_info_url).await {
+        Ok(geo) => geo,
+        Err(e) if settings.before_launch == GeoCheckMode::Block => {
+            return Err(BrowsionError::Validation(format!("Geo check failed: {}", e)));
+        }
+        Err(e) => {
+            tracing::warn!("Profile {}: geo check skipped: {}", profile.id, e);
+            return Ok(None);
+        }
+    };
+    let mismatches = find_mismatches(profile, &geo);
+    if mismatches.is_empty() {
+        return Ok(None);
+    }
+    match settings.before_launch {
+        GeoCheckMode::Off => Ok(None),
+        GeoCheckMode::Warn => {
+            for m in &mismatches {
+                tracing::warn!("Profile {}: {}", profile.id, describe(m));
+            }
+            Ok(None)
+        }
+        GeoCheckMode::Block => Err(BrowsionError::Validation(format!(
+            "Profile does not match its proxy location: {}",
+            mismatches.iter().map(describe).collect::<Vec<_>>().join("; ")
+        ))),
+        GeoCheckMode::Align => {
+            for m in &mismatches {
+                tracing::info!("Profile {}: aligning {} to {}", profile.id, m.field, m.expected);
+            }
+            Ok(Some(align_profile(profile, &mismatches)))
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Core functions (shared by Tauri commands + HTTP handlers)
+// ---------------------------------------------------------------------------
+
+/// Check one profile against its proxy's exit IP. With `auto_align`, mismatched
+/// timezone and language are written back to the stored profile; fields that
+/// match keep inheriting from the profile's group.
+pub async fn core_check_profile_geo(
+    state: &AppState,
+    profile_id: &str,
+    auto_align: bool,
+) -> Result<GeoCheckReport, String> {
+    let (profile, settings) = {
+        let config = state.config.read();
+        let profile = find_profile(&config, profile_id)?;
+        (profile, config.settings.geo_check.clone())
+    };
+
+    let exit = lookup_exit(profile.proxy_server.as_deref(), &settings.ip_info_url).await?;
+    let mismatches = find_mismatches(&profile, &exit);
+
+    let aligned = auto_align && !mismatches.is_empty();
+    if aligned {
+        {
+            let mut config = state.config.write();
+            let slot = config
+                .profiles
+                .iter_mut()
+                .find(|p| p.id == profile_id)
+                .ok_or_else(|| format!("Profile {} not found", profile_id))?;
+            // Only the mismatched fields go into the stored (unresolved) profile
+            let updated = align_profile(slot, &mismatches);
+            crate::config::validation::validate_profile(&updated).map_err(|e| e.to_string())?;
+            *slot = updated;
+            crate::config::save_config(&config).map_err(|e| e.to_string())?;
+        }
+        state.emit("profiles-changed");
+    }
+
+    Ok(GeoCheckReport {
+        profile_id: profile_id.to_string(),
+        proxy_server: profile.proxy_server,
+        exit,
+        mismatches,
+        aligned,
+    })
+}
+
+/// The profile with group settings resolved, as it would be launched.
+fn find_profile(config: &AppConfig, profile_id: &str) -> Result<BrowserProfile, String> {
+    config
+        .profiles
+        .iter()
+        .find(|p| p.id == profile_id)
+        .map(|p| crate::commands::groups::resolve_profile(p, &config.groups))
+        .ok_or_else(|| format!("Profile {} not found", profile_id))
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+#[tauri::command]
+pub async fn check_profile_geo(
+    profile_id: String,
+    auto_align: Option<bool>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<GeoCheckReport, String> {
+    core_check_profile_geo(&state, &profile_id, auto_align.unwrap_or(false)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn profile(lang: &str, timezone: Option<&str>) -> BrowserProfile {
+        BrowserProfile {
+            id: "p".to_string(),
+            name: "p".to_string(),
+            description: String::new(),
+            user_data_dir: std::path::PathBuf::from("/tmp/p"),
+            proxy_server: None,
+            lang: lang.to_string(),
+            timezone: timezone.map(str::to_string),
+            fingerprint: None,
+            color: None,
+            custom_args: vec![],
+            extensions: vec![],
+            launch_flags: Default::default(),
+            tags: vec![],
+            group: None,
+            headless: false,
+            window: Default::default(),
+            startup_urls: vec![],
+            display_mode: Default::default(),
+            env: Default::default(),
+            working_dir: None,
+            clear_env: false,
+            snapshot_exclude: vec![],
+        }
+    }
+
+    #[test]
+    fn test_parse_ip_info_shapes() {
+        let ipinfo = parse_ip_info(&json!({"ip": "1.2.3.4", "country": "US", "timezone": "America/Chicago"})).unwrap();
+        assert_eq!(ipinfo.country.as_deref(), Some("US"));
+        assert_eq!(ipinfo.timezone.as_deref(), Some("America/Chicago"));
+
+        let ip_api = parse_ip_info(&json!({"status": "success", "query": "5.6.7.8", "country": "Germany", "countryCode": "DE", "timezone": "Europe/Berlin"})).unwrap();
+        assert_eq!(ip_api.ip.as_deref(), Some("5.6.7.8"));
+        assert_eq!(ip_api.country.as_deref(), Some("DE"));
+
+        let ipwho = parse_ip_info(&json!({"ip": "9.9.9.9", "country_code": "FR", "timezone": {"id": "Europe/Paris"}})).unwrap();
+        assert_eq!(ipwho.timezone.as_deref(), Some("Europe/Paris"));
+
+        assert!(parse_ip_info(&json!({"status": "fail", "message": "reserved range"})).is_err());
+        assert!(parse_ip_info(&json!({"ip": "1.2.3.4"})).is_err());
+    }
+
+    #[test]
+    fn test_find_mismatches_and_align() {
+        let geo = GeoInfo {
+            ip: None,
+            country: Some("DE".to_string()),
+            timezone: Some("Europe/Berlin".to_string()),
+            languages: vec![],
+        };
+
+        assert!(find_mismatches(&profile("de-DE", Some("Europe/Berlin")), &geo).is_empty());
+        assert!(find_mismatches(&profile("de", Some("Europe/Berlin")), &geo).is_empty());
+
+        let p = profile("en-US", None);
+        let mismatches = find_mismatches(&p, &geo);
+        assert_eq!(mismatches.len(), 2);
+        let aligned = align_profile(&p, &mismatches);
+        assert_eq!(aligned.timezone.as_deref(), Some("Europe/Berlin"));
+        assert_eq!(aligned.lang, "de-DE");
+    }
+
+    #[test]
+    fn test_expected_lang_prefers_reported_languages() {
+        let geo = GeoInfo {
+            ip: None,
+            country: Some("CA".to_string()),
+            timezone: None,
+            languages: vec!["fr-CA".to_string(), "en-CA".to_string()],
+        };
+        assert_eq!(expected_lang(&geo).as_deref(), Some("fr-CA"));
+    }
+}
@@ -0,0 +1,235 @@
+//! Profile groups: nested folders whose proxy, language, timezone and color are
+//! inherited by member profiles that do not set their own.
+
+use crate::config::schema::{default_lang, AppConfig, BrowserProfile, ProfileGroup};
+use crate::config::validation;
+use crate::state::AppState;
+use serde::Deserialize;
+use std::sync::Arc;
+use tauri::State;
+
+/// `group_id` and its ancestors, nearest first. Stops at a missing parent or a cycle.
+pub fn group_chain<'a>(groups: &'a [ProfileGroup], group_id: Option<&str>) -> Vec<&'a ProfileGroup> {
+    let mut chain: Vec<&ProfileGroup> = Vec::new();
+    let mut next = group_id;
+    while let Some(id) = next {
+        let Some(group) = groups.iter().find(|g| g.id == id) else {
+            break;
+        };
+        if chain.iter().any(|g| g.id == group.id) {
+            break;
+        }
+        chain.push(group);
+        next = group.parent.as_deref();
+    }
+    chain
+}
+
+/// `profile` with inherited settings filled in: an unset `proxy_server`,
+/// `timezone` or `color` and an empty `lang` take the value of the nearest group
+/// that sets it. A `lang` nobody sets falls back to the default.
+pub fn resolve_profile(profile: &BrowserProfile, groups: &[ProfileGroup]) -> BrowserProfile {
+    let mut resolved = profile.clone();
+    let chain = group_chain(groups, profile.group.as_deref());
+    let inherit = |field: fn(&ProfileGroup) -> &Option<String>| {
+        chain.iter().find_map(|g| field(g).clone())
+    };
+    if resolved.proxy_server.is_none() {
+        resolved.proxy_server = inherit(|g| &g.proxy_server);
+    }
+    if resolved.timezone.is_none() {
+        resolved.timezone = inherit(|g| &g.timezone);
+    }
+    if resolved.color.is_none() {
+        resolved.color = inherit(|g| &g.color);
+    }
+    if resolved.lang.trim().is_empty() {
+        resolved.lang = inherit(|g| &g.lang).unwrap_or_else(default_lang);
+    }
+    resolved
+}
+
+/// Check that `profile.group`, if set, names an existing group.
+pub fn check_profile_group(config: &AppConfig, profile: &BrowserProfile) -> Result<(), String> {
+    match &profile.group {
+        Some(id) if !config.groups.iter().any(|g| &g.id == id) => Err(format!("Group {} not found", id)),
+        _ => Ok(()),
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MoveProfileRequest {
+    /// Target group id; `null` moves the profile out of any group.
+    pub group: Option<String>,
+}
+
+// ---------------------------------------------------------------------------
+// Core functions (shared by Tauri commands + HTTP handlers)
+// ---------------------------------------------------------------------------
+
+/// Create a group, or replace the one with the same id. An empty id is generated.
+pub fn core_save_group(state: &AppState, mut group: ProfileGroup) -> Result<ProfileGroup, String> {
+    if group.id.trim().is_empty() {
+        group.id = uuid::Uuid::new_v4().to_string();
+    }
+    {
+        let mut config = state.config.write();
+        validation::validate_group(&config.groups, &group).map_err(|e| e.to_string())?;
+        match config.groups.iter_mut().find(|g| g.id == group.id) {
+            Some(slot) => *slot = group.clone(),
+            None => config.groups.push(group.clone()),
+        }
+        crate::config::save_config(&config).map_err(|e| e.to_string())?;
+    }
+    state.emit("profiles-changed");
+    Ok(group)
+}
+
+/// Delete a group. Its subgroups and profiles move up to its parent. Returns
+/// false when no group has that id.
+pub fn core_delete_group(state: &AppState, group_id: &str) -> Result<bool, String> {
+    {
+        let mut config = state.config.write();
+        let Some(pos) = config.groups.iter().position(|g| g.id == group_id) else {
+            return Ok(false);
+        };
+        let removed = config.groups.remove(pos);
+        for group in config.groups.iter_mut() {
+            if group.parent.as_deref() == Some(group_id) {
+                group.parent = removed.parent.clone();
+            }
+        }
+        for profile in config.profiles.iter_mut() {
+            if profile.group.as_deref() == Some(group_id) {
+                profile.group = removed.parent.clone();
+            }
+        }
+        crate::config::save_config(&config).map_err(|e| e.to_string())?;
+    }
+    state.emit("profiles-changed");
+    Ok(true)
+}
+
+/// Move a profile into `group` (or out of all groups with `None`).
+pub fn core_move_profile(
+    state: &AppState,
+    profile_id: &str,
+    group: Option<String>,
+) -> Result<BrowserProfile, String> {
+    let moved = {
+        let mut config = state.config.write();
+        if let Some(id) = &group {
+            if !config.groups.iter().any(|g| &g.id == id) {
+                return Err(format!("Group {} not found", id));
+            }
+        }
+        let profile = config
+            .profiles
+            .iter_mut()
+            .find(|p| p.id == profile_id)
+            .ok_or_else(|| format!("Profile {} not found", profile_id))?;
+        profile.group = group;
+        let moved = profile.clone();
+        crate::config::save_config(&config).map_err(|e| e.to_string())?;
+        moved
+    };
+    state.emit("profiles-changed");
+    Ok(moved)
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+#[tauri::command]
+pub async fn list_groups(state: State<'_, Arc<AppState>>) -> Result<Vec<ProfileGroup>, String> {
+    Ok(state.config.read().groups.clone())
+}
+
+#[tauri::command]
+pub async fn save_group(group: ProfileGroup, state: State<'_, Arc<AppState>>) -> Result<ProfileGroup, String> {
+    core_save_group(&state, group)
+}
+
+#[tauri::command]
+pub async fn delete_group(group_id: String, state: State<'_, Arc<AppState>>) -> Result<(), String> {
+    if core_delete_group(&state, &group_id)? {
+        Ok(())
+    } else {
+        Err(format!("Group {} not found", group_id))
+    }
+}
+
+#[tauri::command]
+pub async fn move_profile_to_group(
+    profile_id: String,
+    group: Option<String>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<BrowserProfile, String> {
+    core_move_profile(&state, &profile_id, group)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(id: &str, parent: Option<&str>) -> ProfileGroup {
+        ProfileGroup {
+            id: id.to_string(),
+            name: id.to_string(),
+            parent: parent.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_resolve_profile_inherits_nearest_group() {
+        let mut client = group("client", None);
+        client.proxy_server = Some("http://10.0.0.1:8080".to_string());
+        client.lang = Some("de-DE".to_string());
+        client.timezone = Some("Europe/Berlin".to_string());
+        let mut project = group("project", Some("client"));
+        project.timezone = Some("Europe/Vienna".to_string());
+        let groups = vec![client, project];
+
+        let mut profile: BrowserProfile = serde_json::from_value(serde_json::json!({
+            "id": "p", "name": "P", "user_data_dir": "/tmp/p", "lang": "", "group": "project"
+        }))
+        .unwrap();
+        let resolved = resolve_profile(&profile, &groups);
+        assert_eq!(resolved.proxy_server.as_deref(), Some("http://10.0.0.1:8080"));
+        assert_eq!(resolved.timezone.as_deref(), Some("Europe/Vienna"));
+        assert_eq!(resolved.lang, "de-DE");
+        assert_eq!(resolved.color, None);
+
+        // The profile's own values win
+        profile.lang = "fr-FR".to_string();
+        profile.timezone = Some("Europe/Paris".to_string());
+        let resolved = resolve_profile(&profile, &groups);
+        assert_eq!(resolved.lang, "fr-FR");
+        assert_eq!(resolved.timezone.as_deref(), Some("Europe/Paris"));
+
+        // Ungrouped: empty lang falls back to the default
+        profile.group = None;
+        profile.lang = String::new();
+        assert_eq!(resolve_profile(&profile, &groups).lang, default_lang());
+    }
+
+    #[test]
+    fn test_group_chain_stops_on_cycle() {
+        let groups = vec![group("a", Some("b")), group("b", Some("a"))];
+        let chain: Vec<&str> = group_chain(&groups, Some("a")).iter().map(|g| g.id.as_str()).collect();
+        assert_eq!(chain, ["a", "b"]);
+    }
+
+    #[test]
+    fn test_validate_group_rejects_cycles_and_missing_parent() {
+        let groups = vec![group("a", None), group("b", Some("a"))];
+        assert!(validation::validate_group(&groups, &group("c", Some("b"))).is_ok());
+        assert!(validation::validate_group(&groups, &group("a", Some("b"))).is_err());
+        assert!(validation::validate_group(&groups, &group("c", Some("zzz"))).is_err());
+        let mut bad = group("c", None);
+        bad.timezone = Some("Mars/Olympus".to_string());
+        assert!(validation::validate_group(&groups, &bad).is_err());
+    }
+}
@@ -1,5 +1,19 @@
+pub mod bulk;
+pub mod bulk_import;
+pub mod bundles;
+pub mod clean;
+pub mod discovery;
+pub mod extensions;
+pub mod geo;
+pub mod groups;
 pub mod proxy;
+pub mod search;
+pub mod snapshot_archive;
+pub mod snapshot_store;
 pub mod snapshots;
+pub mod storage;
+pub mod templates;
+pub mod trash;
 pub use proxy::{add_proxy_preset, delete_proxy_preset, get_proxy_presets, test_proxy, update_proxy_preset};
 pub use snapshots::{create_snapshot, delete_snapshot, list_snapshots, restore_snapshot};
 
@@ -14,11 +28,13 @@ use std::sync::Arc;
 use tauri::Emitter;
 use tauri::State;
 
-/// Get all profiles
+/// Get a page of profiles, optionally filtered / sorted / paged (see `search::ProfileQuery`)
 #[tauri::command]
-pub async fn get_profiles(state: State<'_, Arc<AppState>>) -> Result<Vec<BrowserProfile>, String> {
-    let config = state.config.read();
-    Ok(config.profiles.clone())
+pub async fn get_profiles(
+    filter: Option<search::ProfileQuery>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<search::ProfilePage, String> {
+    search::core_list_profiles(&state, &filter.unwrap_or_default())
 }
 
 /// Resolve effective Chrome path from config (CfT or custom). Used by commands and HTTP API.
@@ -104,14 +120,18 @@ pub async fn get_chrome_path(state: State<'_, Arc<AppState>>) -> Result<String,
         .unwrap_or_default())
 }
 
-/// Launch a profile. Returns PID (cdp_port is stored internally).
+/// Launch a profile. Returns its pid, CDP port and launch warnings.
 #[tauri::command]
-pub async fn launch_profile(profile_id: String, state: State<'_, Arc<AppState>>) -> Result<u32, String> {
+pub async fn launch_profile(
+    profile_id: String,
+    overrides: Option<crate::config::LaunchOverrides>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<crate::process::manager::Launched, String> {
     let chrome_path = get_effective_chrome_path(&state).await?;
     let config = state.config.read().clone();
-    let (pid, _cdp_port) = state
+    let launched = state
         .process_manager
-        .launch_profile(&profile_id, &config, &chrome_path)
+        .launch_profile_with(&profile_id, &config, &chrome_path, &overrides.unwrap_or_default())
         .await
         .map_err(|e| e.to_string())?;
 
@@ -127,7 +147,7 @@ pub async fn launch_profile(profile_id: String, state: State<'_, Arc<AppState>>)
         }
     }
 
-    Ok(pid)
+    Ok(launched)
 }
 
 /// Activate (focus) a running profile's window
@@ -175,21 +195,57 @@ pub async fn get_running_profiles(
     Ok(status)
 }
 
+/// A saved profile plus its launch flag and fingerprint warnings.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SavedProfile {
+    #[serde(flatten)]
+    pub profile: BrowserProfile,
+    pub warnings: Vec<String>,
+}
+
+impl SavedProfile {
+    /// Warnings are those of the profile with its group settings resolved, as launched.
+    pub fn new(profile: BrowserProfile, config: &crate::config::AppConfig) -> Self {
+        let resolved = groups::resolve_profile(&profile, &config.groups);
+        let warnings = validation::profile_warnings(&resolved, &config.browser_source);
+        Self { profile, warnings }
+    }
+}
+
 /// Add a new profile
 #[tauri::command]
 pub async fn add_profile(
-    profile: BrowserProfile,
+    mut profile: BrowserProfile,
     state: State<'_, Arc<AppState>>,
-) -> Result<(), String> {
-    // Validate profile
-    validation::validate_profile(&profile).map_err(|e| e.to_string())?;
-
+) -> Result<SavedProfile, String> {
     let mut config = state.config.write();
-    config.profiles.push(profile);
+    prepare_new_profile(&mut profile, &config).map_err(|e| e.to_string())?;
+    config.profiles.push(profile.clone());
 
     // Save to disk
     crate::config::save_config(&config).map_err(|e| e.to_string())?;
 
+    Ok(SavedProfile::new(profile, &config))
+}
+
+/// Fill in server-side defaults for a profile being created and check it: an
+/// empty `id` becomes a UUID, an empty `user_data_dir` becomes
+/// `~/.browsion/profiles/<id>`. Duplicate ids and overlapping data dirs are
+/// `Conflict` errors. Shared by the Tauri command and the HTTP API.
+pub fn prepare_new_profile(
+    profile: &mut BrowserProfile,
+    config: &crate::config::AppConfig,
+) -> crate::error::Result<()> {
+    if profile.id.trim().is_empty() {
+        profile.id = uuid::Uuid::new_v4().to_string();
+    }
+    if profile.user_data_dir.as_os_str().is_empty() {
+        profile.user_data_dir = crate::config::schema::default_user_data_dir(&profile.id);
+    }
+    validation::validate_profile(profile)?;
+    groups::check_profile_group(config, profile).map_err(crate::error::BrowsionError::Validation)?;
+    validation::check_profile_conflicts(&config.profiles, profile, None)?;
+    crate::config::fingerprint::assign_seed_if_missing(profile, config);
     Ok(())
 }
 
@@ -198,45 +254,36 @@ pub async fn add_profile(
 pub async fn update_profile(
     profile: BrowserProfile,
     state: State<'_, Arc<AppState>>,
-) -> Result<(), String> {
+) -> Result<SavedProfile, String> {
     // Validate profile
     validation::validate_profile(&profile).map_err(|e| e.to_string())?;
 
     let mut config = state.config.write();
+    groups::check_profile_group(&config, &profile)?;
+    validation::check_profile_conflicts(&config.profiles, &profile, Some(&profile.id))
+        .map_err(|e| e.to_string())?;
 
     if let Some(p) = config.profiles.iter_mut().find(|p| p.id == profile.id) {
-        *p = profile;
+        *p = profile.clone();
         // Save to disk
         crate::config::save_config(&config).map_err(|e| e.to_string())?;
-        Ok(())
+        Ok(SavedProfile::new(profile, &config))
     } else {
         Err(format!("Profile {} not found", profile.id))
     }
 }
 
-/// Delete a profile
+/// Move a profile to the trash; with `move_data` its data directory goes too.
 #[tauri::command]
-pub async fn delete_profile(profile_id: String, state: State<'_, Arc<AppState>>) -> Result<(), String> {
-    // Check if profile is running
-    if state.process_manager.is_running(&profile_id) {
-        return Err(format!(
-            "Cannot delete profile {}: it is currently running",
-            profile_id
-        ));
-    }
-
-    let mut config = state.config.write();
-    let before_len = config.profiles.len();
-    config.profiles.retain(|p| p.id != profile_id);
-
-    if config.profiles.len() == before_len {
-        return Err(format!("Profile {} not found", profile_id));
-    }
-
-    // Save to disk
-    crate::config::save_config(&config).map_err(|e| e.to_string())?;
-
-    Ok(())
+pub async fn delete_profile(
+    profile_id: String,
+    move_data: Option<bool>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    trash::core_trash_profile(&state, &profile_id, move_data.unwrap_or(false))
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
 }
 
 /// Set Chrome to use a custom executable path (e.g. ungoogled Chromium).
@@ -341,6 +388,7 @@ pub async fn update_settings(
     settings: crate::config::AppSettings,
     state: State<'_, Arc<AppState>>,
 ) -> Result<(), String> {
+    validation::validate_settings(&settings).map_err(|e| e.to_string())?;
     let mut config = state.config.write();
     config.settings = settings;
 
@@ -65,18 +65,25 @@ pub async fn delete_proxy_preset(
     crate::config::save_config(&config).map_err(|e| e.to_string())
 }
 
+/// HTTP client that sends every request through `proxy_url`, or directly
+/// (ignoring system proxy settings, like the browser's `direct://`) when None.
+pub fn proxy_client(proxy_url: Option<&str>) -> Result<reqwest::Client, String> {
+    let builder = reqwest::Client::builder().timeout(std::time::Duration::from_secs(10));
+    let builder = match proxy_url {
+        Some(url) => builder
+            .proxy(reqwest::Proxy::all(url).map_err(|e| format!("Invalid proxy URL: {}", e))?),
+        None => builder.no_proxy(),
+    };
+    builder.build().map_err(|e| e.to_string())
+}
+
 /// Test a proxy by timing a GET to https://example.com through it.
 /// Returns latency in ms on success or an error message.
 #[tauri::command]
 pub async fn test_proxy(url: String) -> Result<u64, String> {
     use std::time::Instant;
 
-    let proxy = reqwest::Proxy::all(&url).map_err(|e| format!("Invalid proxy URL: {}", e))?;
-    let client = reqwest::Client::builder()
-        .proxy(proxy)
-        .timeout(std::time::Duration::from_secs(10))
-        .build()
-        .map_err(|e| e.to_string())?;
+    let client = proxy_client(Some(&url))?;
 
     let t0 = Instant::now();
     client
@@ -0,0 +1,320 @@
+//! Profile search: filters, sort order and cursor pagination for profile lists.
+
+use crate::config::schema::{AppConfig, BrowserProfile};
+use crate::state::AppState;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Most profiles one page may hold.
+pub const MAX_PAGE_SIZE: usize = 500;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum ProfileSort {
+    /// Order of the config file.
+    #[default]
+    #[serde(rename = "config")]
+    Config,
+    #[serde(rename = "name")]
+    Name,
+    #[serde(rename = "-name")]
+    NameDesc,
+    #[serde(rename = "id")]
+    Id,
+    #[serde(rename = "-id")]
+    IdDesc,
+}
+
+/// Filters for a profile list. Every given filter must match.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ProfileQuery {
+    /// Comma-separated tags; a profile must have all of them.
+    pub tag: Option<String>,
+    /// Case-insensitive substring of the profile name.
+    pub name: Option<String>,
+    pub running: Option<bool>,
+    /// Id or name of a proxy preset; matches profiles whose `proxy_server` is its URL.
+    pub proxy_preset: Option<String>,
+    /// Profile color, case-insensitive (e.g. `#4A90D9`).
+    pub color: Option<String>,
+    pub sort: ProfileSort,
+    /// `next_cursor` of the previous page.
+    pub cursor: Option<String>,
+    /// Page size; all matches when omitted.
+    pub limit: Option<usize>,
+}
+
+/// One page of matching profiles.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfilePage {
+    pub profiles: Vec<BrowserProfile>,
+    /// Ids of the running profiles on this page.
+    pub running: HashSet<String>,
+    /// Matches across all pages.
+    pub total: usize,
+    /// Pass as `cursor` to get the next page; `None` on the last page.
+    pub next_cursor: Option<String>,
+}
+
+/// Sort key and id of the last profile of a page. A page starts at the first
+/// profile ordered after it, so deleting or renaming that profile does not
+/// invalidate the cursor.
+#[derive(Debug, Serialize, Deserialize)]
+struct Cursor {
+    key: String,
+    id: String,
+}
+
+impl Cursor {
+    fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    fn decode(cursor: &str) -> Result<Self, String> {
+        URL_SAFE_NO_PAD
+            .decode(cursor)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .ok_or_else(|| format!("Invalid cursor '{}'", cursor))
+    }
+}
+
+/// Key `sort` orders by, ties broken by id. Config order has no key of its own,
+/// so it uses the position in the config file.
+fn sort_key(sort: ProfileSort, index: usize, profile: &BrowserProfile) -> String {
+    match sort {
+        ProfileSort::Config => format!("{:010}", index),
+        ProfileSort::Name | ProfileSort::NameDesc => profile.name.to_lowercase(),
+        ProfileSort::Id | ProfileSort::IdDesc => profile.id.clone(),
+    }
+}
+
+/// Filter, sort and page `config.profiles`. `running` holds the ids of running profiles.
+pub fn query_profiles(
+    config: &AppConfig,
+    running: &HashSet<String>,
+    query: &ProfileQuery,
+) -> Result<ProfilePage, String> {
+    let limit = match query.limit {
+        Some(0) => return Err("limit must be at least 1".to_string()),
+        Some(n) => n.min(MAX_PAGE_SIZE),
+        None => usize::MAX,
+    };
+    let tags: Vec<&str> = query
+        .tag
+        .as_deref()
+        .unwrap_or("")
+        .split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .collect();
+    let name = query.name.as_deref().map(str::to_lowercase);
+    let proxy_url = match query.proxy_preset.as_deref() {
+        Some(preset) => Some(
+            config
+                .proxy_presets
+                .iter()
+                .find(|p| p.id == preset || p.name == preset)
+                .map(|p| p.url.as_str())
+                .ok_or_else(|| format!("Proxy preset '{}' not found", preset))?,
+        ),
+        None => None,
+    };
+
+    let mut matches: Vec<(String, &BrowserProfile)> = config
+        .profiles
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| tags.iter().all(|t| p.tags.iter().any(|pt| pt == t)))
+        .filter(|(_, p)| name.as_ref().is_none_or(|n| p.name.to_lowercase().contains(n)))
+        .filter(|(_, p)| query.running.is_none_or(|r| running.contains(&p.id) == r))
+        .filter(|(_, p)| proxy_url.is_none_or(|url| p.proxy_server.as_deref() == Some(url)))
+        .filter(|(_, p)| {
+            query
+                .color
+                .as_deref()
+                .is_none_or(|c| p.color.as_deref().is_some_and(|pc| pc.eq_ignore_ascii_case(c)))
+        })
+        .map(|(i, p)| (sort_key(query.sort, i, p), p))
+        .collect();
+
+    let descending = matches!(query.sort, ProfileSort::NameDesc | ProfileSort::IdDesc);
+    matches.sort_by(|(ka, a), (kb, b)| (ka, &a.id).cmp(&(kb, &b.id)));
+    if descending {
+        matches.reverse();
+    }
+
+    let total = matches.len();
+    let start = match query.cursor.as_deref() {
+        Some(cursor) => {
+            let mut cursor = Cursor::decode(cursor)?;
+            if query.sort == ProfileSort::Config {
+                // Positions shift when profiles are removed: follow the profile
+                // if it is still there, else start at the position it had
+                match config.profiles.iter().position(|p| p.id == cursor.id) {
+                    Some(i) => cursor.key = sort_key(query.sort, i, &config.profiles[i]),
+                    None => cursor.id.clear(),
+                }
+            }
+            let seek = (cursor.key.as_str(), cursor.id.as_str());
+            matches.partition_point(|(k, p)| {
+                let key = (k.as_str(), p.id.as_str());
+                if descending {
+                    key >= seek
+                } else {
+                    key <= seek
+                }
+            })
+        }
+        None => 0,
+    };
+    let page: Vec<(String, &BrowserProfile)> = matches.into_iter().skip(start).take(limit).collect();
+    let next_cursor = if start + page.len() < total {
+        page.last().map(|(key, p)| {
+            Cursor {
+                key: key.clone(),
+                id: p.id.clone(),
+            }
+            .encode()
+        })
+    } else {
+        None
+    };
+    let page: Vec<BrowserProfile> = page.into_iter().map(|(_, p)| p.clone()).collect();
+    let running = page
+        .iter()
+        .filter(|p| running.contains(&p.id))
+        .map(|p| p.id.clone())
+        .collect();
+    Ok(ProfilePage {
+        profiles: page,
+        running,
+        total,
+        next_cursor,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Core functions (shared by Tauri commands + HTTP handlers)
+// ---------------------------------------------------------------------------
+
+/// Run `query` with one process refresh for the whole list.
+pub fn core_list_profiles(state: &AppState, query: &ProfileQuery) -> Result<ProfilePage, String> {
+    state.process_manager.refresh_system_processes();
+    let config = state.config.read();
+    let running: HashSet<String> = config
+        .profiles
+        .iter()
+        .filter(|p| state.process_manager.is_running_cached(&p.id))
+        .map(|p| p.id.clone())
+        .collect();
+    query_profiles(&config, &running, query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::schema::ProxyPreset;
+
+    fn config() -> AppConfig {
+        let profiles: Vec<BrowserProfile> = serde_json::from_value(serde_json::json!([
+            { "id": "p3", "name": "charlie", "user_data_dir": "/tmp/p3", "tags": ["shop", "eu"], "color": "#FF0000" },
+            { "id": "p1", "name": "Alpha", "user_data_dir": "/tmp/p1", "tags": ["shop"],
+              "proxy_server": "http://10.0.0.1:8080" },
+            { "id": "p2", "name": "bravo", "user_data_dir": "/tmp/p2", "tags": ["eu"] }
+        ]))
+        .unwrap();
+        AppConfig {
+            profiles,
+            proxy_presets: vec![ProxyPreset {
+                id: "px".to_string(),
+                name: "Office".to_string(),
+                url: "http://10.0.0.1:8080".to_string(),
+            }],
+            ..AppConfig::default()
+        }
+    }
+
+    fn ids(page: &ProfilePage) -> Vec<&str> {
+        page.profiles.iter().map(|p| p.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_query_filters() {
+        let config = config();
+        let running: HashSet<String> = ["p2".to_string()].into();
+        let run = |q: ProfileQuery| query_profiles(&config, &running, &q).unwrap();
+
+        assert_eq!(ids(&run(ProfileQuery::default())), ["p3", "p1", "p2"]);
+        let q = ProfileQuery { tag: Some("shop,eu".to_string()), ..Default::default() };
+        assert_eq!(ids(&run(q)), ["p3"]);
+        let q = ProfileQuery { name: Some("AV".to_string()), ..Default::default() };
+        assert_eq!(ids(&run(q)), ["p2"]);
+        let q = ProfileQuery { running: Some(false), ..Default::default() };
+        assert_eq!(ids(&run(q)), ["p3", "p1"]);
+        let q = ProfileQuery { proxy_preset: Some("Office".to_string()), ..Default::default() };
+        assert_eq!(ids(&run(q)), ["p1"]);
+        let q = ProfileQuery { color: Some("#ff0000".to_string()), ..Default::default() };
+        assert_eq!(ids(&run(q)), ["p3"]);
+
+        let q = ProfileQuery { proxy_preset: Some("nope".to_string()), ..Default::default() };
+        assert!(query_profiles(&config, &running, &q).is_err());
+    }
+
+    #[test]
+    fn test_query_sort_and_cursor() {
+        let config = config();
+        let running = HashSet::new();
+        let mut q = ProfileQuery {
+            sort: ProfileSort::Name,
+            limit: Some(2),
+            ..Default::default()
+        };
+        let page = query_profiles(&config, &running, &q).unwrap();
+        assert_eq!(ids(&page), ["p1", "p2"]);
+        assert_eq!(page.total, 3);
+        assert!(page.next_cursor.is_some());
+
+        q.cursor = page.next_cursor;
+        let page = query_profiles(&config, &running, &q).unwrap();
+        assert_eq!(ids(&page), ["p3"]);
+        assert_eq!(page.next_cursor, None);
+
+        q.cursor = Some("not a cursor".to_string());
+        assert!(query_profiles(&config, &running, &q).is_err());
+        q.cursor = None;
+        q.limit = Some(0);
+        assert!(query_profiles(&config, &running, &q).is_err());
+
+        let q = ProfileQuery { sort: ProfileSort::IdDesc, ..Default::default() };
+        assert_eq!(ids(&query_profiles(&config, &running, &q).unwrap()), ["p3", "p2", "p1"]);
+    }
+
+    #[test]
+    fn test_cursor_survives_removed_profile() {
+        let running = HashSet::new();
+        for (sort, first, rest) in [
+            (ProfileSort::Config, "p3", ["p2"]),
+            (ProfileSort::Name, "p1", ["p3"]),
+            (ProfileSort::IdDesc, "p3", ["p1"]),
+        ] {
+            let mut config = config();
+            let mut q = ProfileQuery {
+                sort,
+                limit: Some(2),
+                ..Default::default()
+            };
+            let page = query_profiles(&config, &running, &q).unwrap();
+            assert_eq!(ids(&page)[0], first);
+            // The last profile of the page goes away before the next request
+            let last = page.profiles[1].id.clone();
+            config.profiles.retain(|p| p.id != last);
+            q.cursor = page.next_cursor;
+            let page = query_profiles(&config, &running, &q).unwrap();
+            assert_eq!(ids(&page), rest, "{:?}", sort);
+            assert_eq!(page.total, 2);
+        }
+    }
+}
@@ -0,0 +1,357 @@
+//! Portable snapshot archives.
+//!
+//! An archive is a zip file:
+//!
+//! - `snapshot.json`: `ArchiveManifest` (format name and version, exporting app
+//!   version, source profile id and the `SnapshotInfo`, which carries the Chrome
+//!   version that created the snapshot)
+//! - `data/...`: the snapshotted files (symlinks as zip symlink entries)
+//!
+//! Entries are deflate- or zstd-compressed, or stored as is. Importing chunks
+//! `data/` straight into the blob store, so nothing is extracted to disk.
+
+use crate::commands::snapshot_store::{self, BlobStore, FileEntry, LinkEntry, SnapshotTree};
+use crate::config::schema::SnapshotInfo;
+use crate::error::{BrowsionError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::io::{Read, Seek, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const ARCHIVE_FORMAT: &str = "browsion-snapshot";
+/// Current archive layout version. Archives up to this version can be imported.
+pub const ARCHIVE_VERSION: u32 = 1;
+
+const MANIFEST_ENTRY: &str = "snapshot.json";
+const DATA_PREFIX: &str = "data/";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub format: String,
+    pub version: u32,
+    /// Browsion version that wrote the archive.
+    pub app_version: String,
+    pub exported_at_ts: u64,
+    /// Profile the snapshot was taken from.
+    pub profile_id: String,
+    pub snapshot: SnapshotInfo,
+}
+
+impl ArchiveManifest {
+    pub fn new(profile_id: &str, snapshot: SnapshotInfo) -> Self {
+        Self {
+            format: ARCHIVE_FORMAT.to_string(),
+            version: ARCHIVE_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            exported_at_ts: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            profile_id: profile_id.to_string(),
+            snapshot,
+        }
+    }
+}
+
+/// Compression of the archive entries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveCompression {
+    #[default]
+    Deflate,
+    Zstd,
+    /// No compression.
+    Stored,
+}
+
+impl ArchiveCompression {
+    fn method(self) -> zip::CompressionMethod {
+        match self {
+            Self::Deflate => zip::CompressionMethod::Deflated,
+            Self::Zstd => zip::CompressionMethod::Zstd,
+            Self::Stored => zip::CompressionMethod::Stored,
+        }
+    }
+}
+
+/// Where the snapshotted files come from.
+pub enum SnapshotData<'a> {
+    Chunked(&'a BlobStore, &'a SnapshotTree),
+    /// The directory of a full-copy snapshot.
+    Copy(&'a Path),
+}
+
+fn invalid(msg: impl std::fmt::Display) -> BrowsionError {
+    BrowsionError::Validation(format!("Invalid snapshot archive: {}", msg))
+}
+
+fn zip_err(e: zip::result::ZipError) -> BrowsionError {
+    BrowsionError::Io(std::io::Error::other(e))
+}
+
+/// Directories and files under `root`, relative and `/`-separated. Symlinks
+/// are left out.
+fn walk(root: &Path, dir: &Path, dirs: &mut Vec<String>, files: &mut Vec<String>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        let name = relative.to_string_lossy().replace('\\', "/");
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            dirs.push(name);
+            walk(root, &path, dirs, files)?;
+        } else if file_type.is_file() {
+            files.push(name);
+        }
+    }
+    Ok(())
+}
+
+/// Write an archive of `data` to `writer`.
+pub fn write_archive<W: Write + Seek>(
+    writer: W,
+    manifest: &ArchiveManifest,
+    data: SnapshotData<'_>,
+    compression: ArchiveCompression,
+) -> Result<W> {
+    let mut zip = zip::ZipWriter::new(writer);
+    let options = zip::write::FileOptions::default()
+        .compression_method(compression.method())
+        .large_file(true);
+
+    zip.start_file(MANIFEST_ENTRY, options).map_err(zip_err)?;
+    zip.write_all(&serde_json::to_vec_pretty(manifest).map_err(|e| BrowsionError::Config(e.to_string()))?)?;
+    zip.add_directory(DATA_PREFIX, options).map_err(zip_err)?;
+    match data {
+        SnapshotData::Chunked(store, tree) => {
+            for dir in &tree.dirs {
+                zip.add_directory(format!("{}{}/", DATA_PREFIX, dir), options).map_err(zip_err)?;
+            }
+            for file in &tree.files {
+                zip.start_file(format!("{}{}", DATA_PREFIX, file.path), options).map_err(zip_err)?;
+                for hash in &file.chunks {
+                    zip.write_all(&store.get(hash)?)?;
+                }
+            }
+            for link in &tree.links {
+                zip.add_symlink(format!("{}{}", DATA_PREFIX, link.path), link.target.as_str(), options)
+                    .map_err(zip_err)?;
+            }
+        }
+        SnapshotData::Copy(root) => {
+            let (mut dirs, mut files) = (Vec::new(), Vec::new());
+            walk(root, root, &mut dirs, &mut files)?;
+            for dir in dirs {
+                zip.add_directory(format!("{}{}/", DATA_PREFIX, dir), options).map_err(zip_err)?;
+            }
+            for file in files {
+                zip.start_file(format!("{}{}", DATA_PREFIX, file), options).map_err(zip_err)?;
+                std::io::copy(&mut std::fs::File::open(root.join(&file))?, &mut zip)?;
+            }
+        }
+    }
+    zip.finish().map_err(zip_err)
+}
+
+/// Open an archive and check its manifest.
+pub fn read_archive<R: Read + Seek>(reader: R) -> Result<(ArchiveManifest, zip::ZipArchive<R>)> {
+    let mut archive = zip::ZipArchive::new(reader).map_err(invalid)?;
+    let manifest: ArchiveManifest = {
+        let entry = archive
+            .by_name(MANIFEST_ENTRY)
+            .map_err(|_| invalid(format!("missing {}", MANIFEST_ENTRY)))?;
+        serde_json::from_reader(entry).map_err(|e| invalid(format!("{}: {}", MANIFEST_ENTRY, e)))?
+    };
+    if manifest.format != ARCHIVE_FORMAT {
+        return Err(invalid(format!("unknown format {:?}", manifest.format)));
+    }
+    if manifest.version == 0 || manifest.version > ARCHIVE_VERSION {
+        return Err(invalid(format!(
+            "archive version {} (written by Browsion {}) is not supported (up to {})",
+            manifest.version, manifest.app_version, ARCHIVE_VERSION
+        )));
+    }
+    Ok((manifest, archive))
+}
+
+/// Whether a zip entry is a symlink (Unix file type bits).
+fn is_symlink(entry: &zip::read::ZipFile<'_>) -> bool {
+    entry.unix_mode().is_some_and(|mode| mode & 0o170000 == 0o120000)
+}
+
+/// Chunk the archive's `data/` into the store. Returns the tree and the number
+/// of bytes newly written to the store. Entry names that could escape the
+/// profile directory are rejected, and so are symlinks pointing outside it.
+pub fn import_data<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    store: &BlobStore,
+) -> Result<(SnapshotTree, u64)> {
+    let mut dirs = BTreeSet::new();
+    let mut files = Vec::new();
+    let mut links = Vec::new();
+    let mut new_bytes = 0;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(zip_err)?;
+        let Some(name) = entry.enclosed_name().map(PathBuf::from) else {
+            return Err(invalid(format!("unsafe entry name {:?}", entry.name())));
+        };
+        let Ok(relative) = name.strip_prefix(DATA_PREFIX.trim_end_matches('/')) else {
+            continue;
+        };
+        let parts: Vec<String> = relative
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+                _ => None,
+            })
+            .collect();
+        if parts.is_empty() {
+            continue;
+        }
+        // Parents first, including those without an entry of their own
+        let last = if entry.is_dir() { parts.len() } else { parts.len() - 1 };
+        for end in 1..=last {
+            dirs.insert(parts[..end].join("/"));
+        }
+        if is_symlink(&entry) {
+            let mut target = String::new();
+            entry.read_to_string(&mut target)?;
+            if !snapshot_store::is_contained_link(Path::new(&target)) {
+                return Err(invalid(format!("link {:?} points outside the profile", entry.name())));
+            }
+            links.push(LinkEntry {
+                path: parts.join("/"),
+                target,
+            });
+        } else if !entry.is_dir() {
+            let (chunks, size, fresh) = snapshot_store::store_stream(store, &mut entry)?;
+            new_bytes += fresh;
+            files.push(FileEntry {
+                path: parts.join("/"),
+                size,
+                chunks,
+            });
+        }
+    }
+    let tree = SnapshotTree {
+        dirs: dirs.into_iter().collect(),
+        files,
+        links,
+    };
+    Ok((tree, new_bytes))
+}
+
+/// A temporary file for an archive being downloaded, deleted on drop.
+pub struct TempArchive {
+    path: PathBuf,
+}
+
+impl TempArchive {
+    pub fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("browsion-archive-{}.zip", uuid::Uuid::new_v4()));
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Default for TempArchive {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for TempArchive {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::schema::SnapshotStorage;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_archive_round_trip() {
+        let base = std::env::temp_dir().join(format!("browsion-archive-{}", uuid::Uuid::new_v4()));
+        let src = base.join("profile");
+        std::fs::create_dir_all(src.join("Default/Local Storage")).unwrap();
+        std::fs::write(src.join("Default/Preferences"), b"{\"a\":1}").unwrap();
+        std::fs::write(src.join("Last Version"), b"145.0.7632.117").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("Default/Preferences", src.join("prefs-link")).unwrap();
+        let store = BlobStore::new(base.join("blobs"));
+        let (tree, _, _) = snapshot_store::store_dir(&store, &src, &Default::default()).unwrap();
+
+        let info = SnapshotInfo {
+            name: "before".to_string(),
+            created_at_ts: 1,
+            size_bytes: tree.size(),
+            unique_bytes: tree.size(),
+            storage: SnapshotStorage::Chunked,
+            chrome_version: Some("145.0.7632.117".to_string()),
+            warnings: Vec::new(),
+        };
+        let manifest = ArchiveManifest::new("p1", info);
+        for compression in [ArchiveCompression::Deflate, ArchiveCompression::Zstd, ArchiveCompression::Stored] {
+            let bytes = write_archive(
+                Cursor::new(Vec::new()),
+                &manifest,
+                SnapshotData::Chunked(&store, &tree),
+                compression,
+            )
+            .unwrap()
+            .into_inner();
+
+            // Importing into another store yields the same tree
+            let other = BlobStore::new(base.join(format!("other-{:?}", compression)));
+            let (read, mut archive) = read_archive(Cursor::new(bytes)).unwrap();
+            assert_eq!(read.snapshot.chrome_version.as_deref(), Some("145.0.7632.117"));
+            let (imported, new_bytes) = import_data(&mut archive, &other).unwrap();
+            let mut files = imported.files.clone();
+            files.sort_by(|a, b| a.path.cmp(&b.path));
+            let mut expected = tree.files.clone();
+            expected.sort_by(|a, b| a.path.cmp(&b.path));
+            assert_eq!(files, expected);
+            assert_eq!(imported.dirs, tree.dirs);
+            assert_eq!(imported.links, tree.links);
+            assert_eq!(new_bytes, tree.size());
+        }
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_read_archive_rejects_foreign_zip() {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file(MANIFEST_ENTRY, zip::write::FileOptions::default()).unwrap();
+        zip.write_all(br#"{"format":"browsion-profile","version":1}"#).unwrap();
+        let bytes = zip.finish().unwrap().into_inner();
+        assert!(matches!(read_archive(Cursor::new(bytes)), Err(BrowsionError::Validation(_))));
+    }
+
+    #[test]
+    fn test_import_rejects_escaping_link() {
+        let base = std::env::temp_dir().join(format!("browsion-archive-link-{}", uuid::Uuid::new_v4()));
+        let info: SnapshotInfo = serde_json::from_value(serde_json::json!({
+            "name": "s", "created_at_ts": 1, "size_bytes": 0
+        }))
+        .unwrap();
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::FileOptions::default();
+        zip.start_file(MANIFEST_ENTRY, options).unwrap();
+        zip.write_all(&serde_json::to_vec(&ArchiveManifest::new("p1", info)).unwrap()).unwrap();
+        zip.add_symlink("data/evil", "../../etc", options).unwrap();
+        let bytes = zip.finish().unwrap().into_inner();
+
+        let (_, mut archive) = read_archive(Cursor::new(bytes)).unwrap();
+        let store = BlobStore::new(base.join("blobs"));
+        assert!(matches!(import_data(&mut archive, &store), Err(BrowsionError::Validation(_))));
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
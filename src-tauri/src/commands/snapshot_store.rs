@@ -0,0 +1,446 @@
+//! Content-addressed blob store behind profile snapshots.
+//!
+//! Files are split into fixed-size chunks named by their SHA-256 and stored
+//! once under `~/.browsion/snapshots/.blobs/<first two hex digits>/<hash>`, so
+//! identical data is shared between snapshots and profiles. A snapshot is a
+//! `SnapshotTree`: its directories plus, per file, the ordered chunk hashes.
+//! Fixed-size chunks suit Chrome's data well: SQLite and LevelDB files change
+//! in place or grow at the end, leaving most chunks untouched. `ExcludeRules`
+//! decide what is left out; symlinks are recorded as links, never followed.
+
+use crate::config::exclude::ExcludeRules;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+pub const CHUNK_SIZE: usize = 1 << 20;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotTree {
+    /// Every directory, relative and `/`-separated, parents first.
+    pub dirs: Vec<String>,
+    pub files: Vec<FileEntry>,
+    /// Symlinks, recreated after the files and directories.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<LinkEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileEntry {
+    /// Relative, `/`-separated.
+    pub path: String,
+    pub size: u64,
+    /// SHA-256 of each `CHUNK_SIZE` chunk, in order.
+    pub chunks: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinkEntry {
+    /// Relative, `/`-separated.
+    pub path: String,
+    /// The link's target, as stored in the link.
+    pub target: String,
+}
+
+impl FileEntry {
+    /// Each chunk hash with the chunk's length.
+    pub fn chunk_sizes(&self) -> impl Iterator<Item = (&str, u64)> {
+        let size = self.size;
+        self.chunks
+            .iter()
+            .enumerate()
+            .map(move |(i, hash)| (hash.as_str(), (size - i as u64 * CHUNK_SIZE as u64).min(CHUNK_SIZE as u64)))
+    }
+}
+
+impl SnapshotTree {
+    /// Logical size: the sum of the file sizes.
+    pub fn size(&self) -> u64 {
+        self.files.iter().map(|f| f.size).sum()
+    }
+
+    /// Distinct blobs with their sizes.
+    pub fn blobs(&self) -> HashMap<&str, u64> {
+        self.files.iter().flat_map(|f| f.chunk_sizes()).collect()
+    }
+}
+
+pub struct BlobStore {
+    root: PathBuf,
+}
+
+impl BlobStore {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    fn blob_path(&self, hash: &str) -> PathBuf {
+        self.root.join(hash.get(..2).unwrap_or("__")).join(hash)
+    }
+
+    pub fn contains(&self, hash: &str) -> bool {
+        self.blob_path(hash).is_file()
+    }
+
+    /// Store `data` unless a blob with the same hash exists. Returns the hash
+    /// and whether it was newly written.
+    pub fn put(&self, data: &[u8]) -> io::Result<(String, bool)> {
+        let hash = hex(&Sha256::digest(data));
+        let path = self.blob_path(&hash);
+        if path.is_file() {
+            return Ok((hash, false));
+        }
+        let dir = path.parent().expect("blob path has a parent");
+        fs::create_dir_all(dir)?;
+        // Write aside and rename, so a blob is never seen half-written
+        let tmp = dir.join(format!(".tmp-{}", uuid::Uuid::new_v4()));
+        let written = fs::File::create(&tmp).and_then(|mut f| f.write_all(data));
+        if let Err(e) = written.and_then(|()| fs::rename(&tmp, &path)) {
+            let _ = fs::remove_file(&tmp);
+            return Err(e);
+        }
+        Ok((hash, true))
+    }
+
+    pub fn get(&self, hash: &str) -> io::Result<Vec<u8>> {
+        if hash.len() != 64 || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("Invalid blob hash: {}", hash)));
+        }
+        fs::read(self.blob_path(hash))
+    }
+
+    /// All stored blobs with their sizes.
+    pub fn list(&self) -> io::Result<Vec<(String, u64)>> {
+        let mut blobs = Vec::new();
+        let Ok(fanout) = fs::read_dir(&self.root) else {
+            return Ok(blobs);
+        };
+        for dir in fanout.flatten() {
+            for entry in fs::read_dir(dir.path())?.flatten() {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if !name.starts_with('.') {
+                    blobs.push((name, entry.metadata()?.len()));
+                }
+            }
+        }
+        Ok(blobs)
+    }
+
+    pub fn remove(&self, hash: &str) -> io::Result<()> {
+        fs::remove_file(self.blob_path(hash))
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn relative(path: &Path, root: &Path) -> String {
+    let rel = path.strip_prefix(root).unwrap_or(path);
+    rel.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// `root` joined with a tree path, refusing anything that could leave `root`.
+fn resolve(root: &Path, rel: &str) -> io::Result<PathBuf> {
+    let rel = Path::new(rel);
+    if rel.components().all(|c| matches!(c, Component::Normal(_))) {
+        Ok(root.join(rel))
+    } else {
+        Err(io::Error::new(io::ErrorKind::InvalidData, format!("Unsafe path in snapshot: {}", rel.display())))
+    }
+}
+
+/// Chunk everything `reader` yields into the store. Returns the chunk hashes,
+/// the total size and the number of bytes newly written to the store.
+pub fn store_stream(store: &BlobStore, reader: &mut impl Read) -> io::Result<(Vec<String>, u64, u64)> {
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    let (mut chunks, mut size, mut new_bytes) = (Vec::new(), 0, 0);
+    loop {
+        let n = read_full(reader, &mut buf)?;
+        if n == 0 {
+            break;
+        }
+        let (hash, fresh) = store.put(&buf[..n])?;
+        if fresh {
+            new_bytes += n as u64;
+        }
+        chunks.push(hash);
+        size += n as u64;
+        if n < CHUNK_SIZE {
+            break;
+        }
+    }
+    Ok((chunks, size, new_bytes))
+}
+
+/// Whether a link target stays inside the profile: relative, without `..`.
+/// Other links are neither stored nor imported.
+pub fn is_contained_link(target: &Path) -> bool {
+    target.components().next().is_some()
+        && target
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+/// Chunk every file under `src` into the store, leaving out what `rules`
+/// exclude. Symlinks are recorded with their target and not followed; links
+/// that point outside the profile are skipped with a warning. Returns the tree,
+/// the number of bytes newly written to the store and the warnings.
+pub fn store_dir(
+    store: &BlobStore,
+    src: &Path,
+    rules: &ExcludeRules,
+) -> io::Result<(SnapshotTree, u64, Vec<String>)> {
+    let mut tree = SnapshotTree::default();
+    let mut new_bytes = 0;
+    let mut warnings = Vec::new();
+    let mut stack = vec![src.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let mut entries: Vec<fs::DirEntry> = fs::read_dir(&dir)?.collect::<io::Result<_>>()?;
+        entries.sort_by_key(|e| e.file_name());
+        for entry in entries {
+            let path = entry.path();
+            let rel = relative(&path, src);
+            if rules.excludes(&rel) {
+                continue;
+            }
+            let ft = entry.file_type()?;
+            if ft.is_symlink() {
+                let target = fs::read_link(&path)?;
+                if !is_contained_link(&target) {
+                    warnings.push(format!(
+                        "Skipped link {} -> {}: it points outside the profile",
+                        rel,
+                        target.display()
+                    ));
+                    continue;
+                }
+                tree.links.push(LinkEntry {
+                    path: rel,
+                    target: target.to_string_lossy().into_owned(),
+                });
+            } else if ft.is_dir() {
+                tree.dirs.push(rel);
+                stack.push(path);
+            } else if ft.is_file() {
+                let (chunks, size, fresh) = store_stream(store, &mut fs::File::open(&path)?)?;
+                new_bytes += fresh;
+                tree.files.push(FileEntry {
+                    path: rel,
+                    size,
+                    chunks,
+                });
+            }
+        }
+    }
+    tree.dirs.sort();
+    Ok((tree, new_bytes, warnings))
+}
+
+/// Fill `buf` unless the reader ends first.
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// Blobs the tree needs that are not in the store.
+pub fn missing_blobs(store: &BlobStore, tree: &SnapshotTree) -> Vec<String> {
+    let mut missing: Vec<String> = tree
+        .blobs()
+        .into_keys()
+        .filter(|hash| !store.contains(hash))
+        .map(str::to_string)
+        .collect();
+    missing.sort();
+    missing
+}
+
+/// Recreate the tree's directories and files under `dst` (which must not exist).
+pub fn rebuild_dir(store: &BlobStore, tree: &SnapshotTree, dst: &Path) -> io::Result<()> {
+    fs::create_dir_all(dst)?;
+    for dir in &tree.dirs {
+        fs::create_dir_all(resolve(dst, dir)?)?;
+    }
+    for entry in &tree.files {
+        let path = resolve(dst, &entry.path)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = io::BufWriter::new(fs::File::create(&path)?);
+        for hash in &entry.chunks {
+            file.write_all(&store.get(hash)?)?;
+        }
+        file.flush()?;
+    }
+    // Links last, and never inside another link, so nothing is written through one
+    let link_paths: HashSet<&str> = tree.links.iter().map(|l| l.path.as_str()).collect();
+    for link in &tree.links {
+        let mut parent = link.path.as_str();
+        while let Some((up, _)) = parent.rsplit_once('/') {
+            if link_paths.contains(up) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Unsafe link in snapshot: {}", link.path),
+                ));
+            }
+            parent = up;
+        }
+        let path = resolve(dst, &link.path)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        create_symlink(Path::new(&link.target), &path)?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+pub(crate) fn create_symlink(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+/// Windows needs a privilege for symlinks; links that cannot be created are
+/// skipped (Chrome's profile data does not depend on them).
+#[cfg(not(unix))]
+pub(crate) fn create_symlink(target: &Path, link: &Path) -> io::Result<()> {
+    if let Err(e) = std::os::windows::fs::symlink_file(target, link) {
+        tracing::warn!("Skipping symlink {} -> {}: {}", link.display(), target.display(), e);
+    }
+    Ok(())
+}
+
+/// Delete the blobs no tree refers to. Returns how many and their total size.
+pub fn collect_garbage(store: &BlobStore, trees: &[SnapshotTree]) -> io::Result<(usize, u64)> {
+    let live: HashSet<&str> = trees.iter().flat_map(|t| t.blobs().into_keys()).collect();
+    let (mut count, mut bytes) = (0, 0);
+    for (hash, size) in store.list()? {
+        if !live.contains(hash.as_str()) {
+            store.remove(&hash)?;
+            count += 1;
+            bytes += size;
+        }
+    }
+    Ok((count, bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("browsion-{}-{}", name, uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn test_store_dedups_and_rebuilds() {
+        let base = temp("store");
+        let store = BlobStore::new(base.join("blobs"));
+        let src = base.join("profile");
+        fs::create_dir_all(src.join("Default/Local Storage")).unwrap();
+        fs::create_dir_all(src.join("Default/empty")).unwrap();
+        let big: Vec<u8> = (0..CHUNK_SIZE * 2 + 10).map(|i| (i % 251) as u8).collect();
+        fs::write(src.join("Default/History"), &big).unwrap();
+        fs::write(src.join("Default/Local Storage/copy"), &big).unwrap();
+        fs::write(src.join("Default/Preferences"), b"{}").unwrap();
+        fs::write(src.join("Default/zero"), b"").unwrap();
+
+        let (tree, new_bytes, _) = store_dir(&store, &src, &ExcludeRules::default()).unwrap();
+        assert_eq!(tree.size(), 2 * big.len() as u64 + 2);
+        // The second copy of `big` is free
+        assert_eq!(new_bytes, big.len() as u64 + 2);
+        assert_eq!(tree.files.iter().find(|f| f.path == "Default/History").unwrap().chunks.len(), 3);
+        assert_eq!(tree.dirs, ["Default", "Default/Local Storage", "Default/empty"]);
+
+        // A second snapshot after a small change stores only the changed chunk
+        let mut changed = big.clone();
+        changed[CHUNK_SIZE + 5] ^= 0xff;
+        fs::write(src.join("Default/History"), &changed).unwrap();
+        fs::remove_file(src.join("Default/Local Storage/copy")).unwrap();
+        let (tree2, new_bytes, _) = store_dir(&store, &src, &ExcludeRules::default()).unwrap();
+        assert_eq!(new_bytes, CHUNK_SIZE as u64);
+
+        let dst = base.join("restored");
+        rebuild_dir(&store, &tree, &dst).unwrap();
+        assert_eq!(fs::read(dst.join("Default/History")).unwrap(), big);
+        assert_eq!(fs::read(dst.join("Default/zero")).unwrap(), b"");
+        assert!(dst.join("Default/empty").is_dir());
+
+        // Dropping the first tree frees only the chunk it alone used
+        assert_eq!(collect_garbage(&store, std::slice::from_ref(&tree2)).unwrap(), (1, CHUNK_SIZE as u64));
+        assert!(missing_blobs(&store, &tree2).is_empty());
+        assert_eq!(missing_blobs(&store, &tree).len(), 1);
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_rebuild_rejects_escaping_paths() {
+        let base = temp("store-escape");
+        let store = BlobStore::new(base.join("blobs"));
+        let tree = SnapshotTree {
+            dirs: vec!["../outside".to_string()],
+            ..SnapshotTree::default()
+        };
+        assert!(rebuild_dir(&store, &tree, &base.join("dst")).is_err());
+        assert!(!base.join("outside").exists());
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_store_dir_excludes_and_keeps_links() {
+        let base = temp("store-links");
+        let store = BlobStore::new(base.join("blobs"));
+        let src = base.join("profile");
+        fs::create_dir_all(src.join("Default/Cache/Cache_Data")).unwrap();
+        fs::write(src.join("Default/Cache/Cache_Data/data_0"), b"cached").unwrap();
+        fs::write(src.join("Default/Cookies"), b"cookies").unwrap();
+        // Dangling, like Chrome's lock link
+        std::os::unix::fs::symlink("host-1234", src.join("SingletonLock")).unwrap();
+        std::os::unix::fs::symlink("Default/Cookies", src.join("cookies-link")).unwrap();
+        std::os::unix::fs::symlink("/etc/hostname", src.join("absolute-link")).unwrap();
+        std::os::unix::fs::symlink("../Cookies", src.join("Default/parent-link")).unwrap();
+
+        let rules = ExcludeRules::new(&["Cache", "Singleton*"]).unwrap();
+        let (tree, _, warnings) = store_dir(&store, &src, &rules).unwrap();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings.iter().any(|w| w.contains("absolute-link")));
+        assert_eq!(tree.dirs, ["Default"]);
+        assert_eq!(tree.files.len(), 1);
+        assert_eq!(
+            tree.links,
+            [LinkEntry {
+                path: "cookies-link".to_string(),
+                target: "Default/Cookies".to_string()
+            }]
+        );
+
+        let dst = base.join("restored");
+        rebuild_dir(&store, &tree, &dst).unwrap();
+        assert_eq!(fs::read_link(dst.join("cookies-link")).unwrap(), Path::new("Default/Cookies"));
+        assert_eq!(fs::read(dst.join("cookies-link")).unwrap(), b"cookies");
+        assert!(!dst.join("Default/Cache").exists());
+
+        // A link may not be the parent of another one
+        let mut nested = tree.clone();
+        nested.links.push(LinkEntry {
+            path: "cookies-link/inner".to_string(),
+            target: "/etc".to_string(),
+        });
+        assert!(rebuild_dir(&store, &nested, &base.join("nested")).is_err());
+        fs::remove_dir_all(&base).unwrap();
+    }
+}
@@ -1,9 +1,18 @@
 //! Profile snapshot: create / restore / list / delete browser profile data snapshots.
 //!
-//! Snapshots are stored under ~/.browsion/snapshots/<profile_id>/<name>/
-//! with a manifest file at ~/.browsion/snapshots/<profile_id>/manifest.json
-
-use crate::config::schema::{AppConfig, SnapshotInfo};
+//! Snapshot data lives in the shared blob store (see `snapshot_store`) under
+//! ~/.browsion/snapshots/.blobs/, with one file list per snapshot at
+//! ~/.browsion/snapshots/<profile_id>/<name>.tree.json and a manifest file at
+//! ~/.browsion/snapshots/<profile_id>/manifest.json. Snapshots taken by older
+//! versions are full copies in ~/.browsion/snapshots/<profile_id>/<name>/ and
+//! can still be restored and deleted.
+
+use crate::commands::snapshot_archive::{self, ArchiveCompression, ArchiveManifest, SnapshotData};
+use crate::commands::snapshot_store::{self, BlobStore, SnapshotTree};
+use crate::cft::version::{chrome_binary_version, newest_downloaded_version, version_key};
+use crate::config::exclude::ExcludeRules;
+use crate::config::schema::{AppConfig, BrowserSource, SnapshotInfo, SnapshotStorage};
+use crate::error::BrowsionError;
 use crate::process::ProcessManager;
 use crate::state::AppState;
 use std::collections::HashMap;
@@ -13,7 +22,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use tauri::State;
 use tokio::io;
 
-fn snapshots_root() -> PathBuf {
+pub(crate) fn snapshots_root() -> PathBuf {
     dirs::home_dir()
         .unwrap_or_else(|| PathBuf::from("."))
         .join(".browsion")
@@ -32,6 +41,126 @@ fn manifest_path(profile_id: &str) -> PathBuf {
     profile_snapshot_dir(profile_id).join("manifest.json")
 }
 
+/// Directory of the blob store inside `snapshots_root()`. The leading dot keeps
+/// it apart from the per-profile directories.
+pub(crate) const BLOBS_DIR: &str = ".blobs";
+const TREE_SUFFIX: &str = ".tree.json";
+
+fn blob_store() -> BlobStore {
+    BlobStore::new(snapshots_root().join(BLOBS_DIR))
+}
+
+fn tree_path(profile_id: &str, name: &str) -> PathBuf {
+    profile_snapshot_dir(profile_id).join(format!("{}{}", name, TREE_SUFFIX))
+}
+
+/// Serializes snapshot writes and restores with the blob garbage collector.
+static STORE_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+async fn load_tree(profile_id: &str, name: &str) -> Result<SnapshotTree, String> {
+    let text = tokio::fs::read_to_string(tree_path(profile_id, name))
+        .await
+        .map_err(|e| format!("Failed to read snapshot '{}': {}", name, e))?;
+    serde_json::from_str(&text).map_err(|e| format!("Snapshot '{}' is damaged: {}", name, e))
+}
+
+async fn save_tree(profile_id: &str, name: &str, tree: &SnapshotTree) -> io::Result<()> {
+    let text = serde_json::to_string(tree).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    tokio::fs::write(tree_path(profile_id, name), text).await
+}
+
+/// Every snapshot tree of every profile, keyed by (profile id, snapshot name).
+/// Fails on an unreadable tree, since the garbage collector must not miss one.
+fn load_all_trees() -> Result<HashMap<(String, String), SnapshotTree>, String> {
+    let mut trees = HashMap::new();
+    let Ok(profiles) = std::fs::read_dir(snapshots_root()) else {
+        return Ok(trees);
+    };
+    for profile_dir in profiles.flatten() {
+        let profile_id = profile_dir.file_name().to_string_lossy().into_owned();
+        if profile_id == BLOBS_DIR || !profile_dir.path().is_dir() {
+            continue;
+        }
+        for entry in std::fs::read_dir(profile_dir.path()).map_err(|e| e.to_string())?.flatten() {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let Some(name) = file_name.strip_suffix(TREE_SUFFIX) else {
+                continue;
+            };
+            let tree = std::fs::read_to_string(entry.path())
+                .map_err(|e| e.to_string())
+                .and_then(|text| serde_json::from_str(&text).map_err(|e| e.to_string()))
+                .map_err(|e| format!("{}: {}", entry.path().display(), e))?;
+            trees.insert((profile_id.clone(), name.to_string()), tree);
+        }
+    }
+    Ok(trees)
+}
+
+/// Delete unreferenced blobs; the caller holds `STORE_LOCK`.
+async fn collect_garbage_locked() -> Result<(usize, u64), String> {
+    tokio::task::spawn_blocking(|| {
+        let trees: Vec<SnapshotTree> = load_all_trees()?.into_values().collect();
+        snapshot_store::collect_garbage(&blob_store(), &trees).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Snapshot names become file names: no separators, no leading dot.
+fn validate_snapshot_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(format!("Invalid snapshot name '{}'", name));
+    }
+    Ok(())
+}
+
+/// Chrome version recorded in a user data dir (Chrome rewrites `Last Version`
+/// on every start).
+async fn read_chrome_version(user_data_dir: &Path) -> Option<String> {
+    let text = tokio::fs::read_to_string(user_data_dir.join("Last Version")).await.ok()?;
+    let version = text.trim();
+    (!version.is_empty()).then(|| version.to_string())
+}
+
+/// Version of the browser the current settings launch, if it can be told
+/// without downloading anything.
+async fn current_chrome_version(config: &AppConfig) -> Option<String> {
+    let custom = match &config.browser_source {
+        BrowserSource::Custom { path, .. } => Some(path.clone()),
+        BrowserSource::ChromeForTesting { .. } => None,
+    };
+    if let Some(path) = config.chrome_path.clone().or(custom) {
+        return chrome_binary_version(&path).await;
+    }
+    let BrowserSource::ChromeForTesting { version, download_dir, .. } = &config.browser_source else {
+        return None;
+    };
+    if version.is_some() {
+        return version.clone();
+    }
+    newest_downloaded_version(download_dir)
+}
+
+/// Warning for restoring a snapshot taken with Chrome `snapshot` when the
+/// configured browser is Chrome `current`.
+fn version_warning(name: &str, snapshot: &str, current: &str) -> Option<String> {
+    if snapshot == current {
+        return None;
+    }
+    let major = |v: &str| version_key(v).first().copied().unwrap_or(0);
+    Some(if major(snapshot) > major(current) {
+        format!(
+            "Snapshot '{}' was created by Chrome {}, newer than the configured Chrome {}; Chrome may refuse to open the restored profile",
+            name, snapshot, current
+        )
+    } else {
+        format!(
+            "Snapshot '{}' was created by Chrome {}, but the configured browser is Chrome {}",
+            name, snapshot, current
+        )
+    })
+}
+
 // Manifest: map of snapshot_name → SnapshotInfo
 type Manifest = HashMap<String, SnapshotInfo>;
 
@@ -54,7 +183,7 @@ async fn save_manifest(profile_id: &str, manifest: &Manifest) -> io::Result<()>
 }
 
 /// Recursively compute total size of a directory.
-async fn dir_size(path: &Path) -> u64 {
+pub(crate) async fn dir_size(path: &Path) -> u64 {
     let mut total = 0u64;
     let mut stack = vec![path.to_path_buf()];
     while let Some(dir) = stack.pop() {
@@ -78,16 +207,35 @@ async fn dir_size(path: &Path) -> u64 {
 }
 
 /// Recursively copy src dir into dst dir (dst must not exist).
-async fn copy_dir_all(src: PathBuf, dst: PathBuf) -> io::Result<()> {
+pub(crate) async fn copy_dir_all(src: PathBuf, dst: PathBuf) -> io::Result<()> {
+    copy_dir_filtered(src, dst, |_| false).await
+}
+
+/// Like `copy_dir_all`, but entries for which `skip(relative_path)` is true (and,
+/// for directories, everything below them) are not copied. Symlinks are copied
+/// as links.
+pub(crate) async fn copy_dir_filtered(
+    src: PathBuf,
+    dst: PathBuf,
+    skip: impl Fn(&Path) -> bool,
+) -> io::Result<()> {
     tokio::fs::create_dir_all(&dst).await?;
+    let root = src.clone();
     let mut stack = vec![(src, dst)];
     while let Some((from, to)) = stack.pop() {
         let mut rd = tokio::fs::read_dir(&from).await?;
         while let Ok(Some(entry)) = rd.next_entry().await {
             let ft = entry.file_type().await?;
             let src_path = entry.path();
+            if src_path.strip_prefix(&root).is_ok_and(&skip) {
+                continue;
+            }
             let dst_path = to.join(entry.file_name());
-            if ft.is_dir() {
+            if ft.is_symlink() {
+                // Recreate the link rather than copying (or failing on) its target
+                let target = tokio::fs::read_link(&src_path).await?;
+                snapshot_store::create_symlink(&target, &dst_path)?;
+            } else if ft.is_dir() {
                 tokio::fs::create_dir_all(&dst_path).await?;
                 stack.push((src_path, dst_path));
             } else {
@@ -108,10 +256,41 @@ pub async fn core_list_snapshots(
 ) -> Result<Vec<SnapshotInfo>, String> {
     let manifest = load_manifest(profile_id).await;
     let mut infos: Vec<SnapshotInfo> = manifest.into_values().collect();
+    if infos.iter().any(|i| i.storage == SnapshotStorage::Chunked) {
+        let trees = tokio::task::spawn_blocking(load_all_trees)
+            .await
+            .map_err(|e| e.to_string())??;
+        let unique = unique_bytes(&trees);
+        for info in infos.iter_mut().filter(|i| i.storage == SnapshotStorage::Chunked) {
+            let key = (profile_id.to_string(), info.name.clone());
+            info.unique_bytes = unique.get(&key).copied().unwrap_or(0);
+        }
+    }
+    for info in infos.iter_mut().filter(|i| i.storage == SnapshotStorage::Copy) {
+        info.unique_bytes = info.size_bytes;
+    }
     infos.sort_by(|a, b| b.created_at_ts.cmp(&a.created_at_ts));
     Ok(infos)
 }
 
+/// For each tree, the total size of the blobs no other tree refers to.
+fn unique_bytes(trees: &HashMap<(String, String), SnapshotTree>) -> HashMap<(String, String), u64> {
+    let blobs: Vec<_> = trees.iter().map(|(key, tree)| (key, tree.blobs())).collect();
+    let mut refs: HashMap<&str, usize> = HashMap::new();
+    for (_, tree_blobs) in &blobs {
+        for hash in tree_blobs.keys() {
+            *refs.entry(hash).or_default() += 1;
+        }
+    }
+    blobs
+        .iter()
+        .map(|(key, tree_blobs)| {
+            let bytes = tree_blobs.iter().filter(|(hash, _)| refs[*hash] == 1).map(|(_, size)| size).sum();
+            ((*key).clone(), bytes)
+        })
+        .collect()
+}
+
 pub async fn core_create_snapshot(
     profile_id: &str,
     name: &str,
@@ -124,6 +303,7 @@ pub async fn core_create_snapshot(
             profile_id
         ));
     }
+    validate_snapshot_name(name)?;
 
     let profile = config
         .profiles
@@ -139,16 +319,45 @@ pub async fn core_create_snapshot(
         ));
     }
 
-    let dst = snapshot_data_dir(profile_id, name);
-    if dst.exists() {
+    // Global rules first, so the profile's own can override them
+    let rules = ExcludeRules::new(
+        &config
+            .settings
+            .snapshot_exclude
+            .iter()
+            .chain(&profile.snapshot_exclude)
+            .collect::<Vec<_>>(),
+    )?;
+
+    let exists = |manifest: &Manifest| {
+        manifest.contains_key(name) || snapshot_data_dir(profile_id, name).exists()
+    };
+    if exists(&load_manifest(profile_id).await) {
         return Err(format!("Snapshot '{}' already exists for profile {}", name, profile_id));
     }
+    tokio::fs::create_dir_all(profile_snapshot_dir(profile_id))
+        .await
+        .map_err(|e| e.to_string())?;
 
-    copy_dir_all(src.clone(), dst.clone())
+    let chrome_version = read_chrome_version(&src).await;
+    let _guard = STORE_LOCK.lock().await;
+    // Re-read under the lock: another create or import may have saved meanwhile
+    let mut manifest = load_manifest(profile_id).await;
+    if exists(&manifest) {
+        return Err(format!("Snapshot '{}' already exists for profile {}", name, profile_id));
+    }
+    let (tree, new_bytes, warnings) =
+        tokio::task::spawn_blocking(move || snapshot_store::store_dir(&blob_store(), &src, &rules))
         .await
-        .map_err(|e| format!("Failed to copy profile data: {}", e))?;
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("Failed to store profile data: {}", e))?;
+    save_tree(profile_id, name, &tree)
+        .await
+        .map_err(|e| format!("Failed to save snapshot: {}", e))?;
+    for warning in &warnings {
+        tracing::warn!("Snapshot '{}' of profile {}: {}", name, profile_id, warning);
+    }
 
-    let size_bytes = dir_size(&dst).await;
     let ts = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default()
@@ -157,10 +366,13 @@ pub async fn core_create_snapshot(
     let info = SnapshotInfo {
         name: name.to_string(),
         created_at_ts: ts,
-        size_bytes,
+        size_bytes: tree.size(),
+        unique_bytes: new_bytes,
+        storage: SnapshotStorage::Chunked,
+        chrome_version,
+        warnings,
     };
 
-    let mut manifest = load_manifest(profile_id).await;
     manifest.insert(name.to_string(), info.clone());
     save_manifest(profile_id, &manifest)
         .await
@@ -169,12 +381,14 @@ pub async fn core_create_snapshot(
     Ok(info)
 }
 
+/// Restore a snapshot into the profile's user data dir. Returns warnings, e.g.
+/// when the snapshot was taken with another Chrome version than the configured one.
 pub async fn core_restore_snapshot(
     profile_id: &str,
     name: &str,
     config: &AppConfig,
     process_mgr: &ProcessManager,
-) -> Result<(), String> {
+) -> Result<Vec<String>, String> {
     if process_mgr.is_running(profile_id) {
         return Err(format!(
             "Kill the browser for profile {} before restoring a snapshot",
@@ -188,13 +402,56 @@ pub async fn core_restore_snapshot(
         .find(|p| p.id == profile_id)
         .ok_or_else(|| format!("Profile {} not found", profile_id))?;
 
+    let dst = profile.user_data_dir.clone();
+    let info = load_manifest(profile_id).await.remove(name);
+    let mut warnings = Vec::new();
+    if let Some(snapshot_version) = info.as_ref().and_then(|i| i.chrome_version.as_deref()) {
+        if let Some(current) = current_chrome_version(config).await {
+            warnings.extend(version_warning(name, snapshot_version, &current));
+        }
+    }
+    for warning in &warnings {
+        tracing::warn!("{}", warning);
+    }
+    if info.map(|i| i.storage) != Some(SnapshotStorage::Chunked) {
+        return restore_copy(profile_id, name, dst).await.map(|()| warnings);
+    }
+
+    let _guard = STORE_LOCK.lock().await;
+    let tree = load_tree(profile_id, name).await?;
+    tokio::task::spawn_blocking(move || {
+        let store = blob_store();
+        let missing = snapshot_store::missing_blobs(&store, &tree);
+        if !missing.is_empty() {
+            return Err(format!("Snapshot is damaged: {} data blocks are missing", missing.len()));
+        }
+        // Rebuild next to the target, then swap, so a failure leaves the profile as it was
+        let file_name = dst.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        let staging = dst.with_file_name(format!(".{}.restore-{}", file_name, uuid::Uuid::new_v4()));
+        if let Err(e) = snapshot_store::rebuild_dir(&store, &tree, &staging) {
+            let _ = std::fs::remove_dir_all(&staging);
+            return Err(format!("Failed to restore snapshot: {}", e));
+        }
+        if dst.exists() {
+            if let Err(e) = std::fs::remove_dir_all(&dst) {
+                let _ = std::fs::remove_dir_all(&staging);
+                return Err(format!("Failed to remove existing user_data_dir: {}", e));
+            }
+        }
+        std::fs::rename(&staging, &dst).map_err(|e| format!("Failed to restore snapshot: {}", e))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    Ok(warnings)
+}
+
+/// Restore a full-copy snapshot taken by an older version.
+async fn restore_copy(profile_id: &str, name: &str, dst: PathBuf) -> Result<(), String> {
     let snap_dir = snapshot_data_dir(profile_id, name);
     if !snap_dir.exists() {
         return Err(format!("Snapshot '{}' not found for profile {}", name, profile_id));
     }
 
-    let dst = profile.user_data_dir.clone();
-
     // Remove existing user_data_dir
     if dst.exists() {
         tokio::fs::remove_dir_all(&dst)
@@ -211,18 +468,133 @@ pub async fn core_restore_snapshot(
 }
 
 pub async fn core_delete_snapshot(profile_id: &str, name: &str) -> Result<(), String> {
+    let _guard = STORE_LOCK.lock().await;
     let snap_dir = snapshot_data_dir(profile_id, name);
     if snap_dir.exists() {
         tokio::fs::remove_dir_all(&snap_dir)
             .await
             .map_err(|e| format!("Failed to delete snapshot directory: {}", e))?;
     }
+    let tree = tree_path(profile_id, name);
+    if tree.exists() {
+        tokio::fs::remove_file(&tree)
+            .await
+            .map_err(|e| format!("Failed to delete snapshot: {}", e))?;
+    }
 
     let mut manifest = load_manifest(profile_id).await;
     manifest.remove(name);
     save_manifest(profile_id, &manifest)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+
+    // Blobs that only this snapshot used
+    let (count, bytes) = collect_garbage_locked().await?;
+    if count > 0 {
+        tracing::info!("Snapshot GC removed {} blobs ({} bytes)", count, bytes);
+    }
+    Ok(())
+}
+
+/// Delete blobs no snapshot refers to any more (e.g. after snapshot
+/// directories were removed by hand or by the storage cleanup).
+pub async fn core_collect_garbage() -> Result<(usize, u64), String> {
+    let _guard = STORE_LOCK.lock().await;
+    collect_garbage_locked().await
+}
+
+/// Write a portable archive of a snapshot to `dst`.
+pub async fn core_export_snapshot(
+    profile_id: &str,
+    name: &str,
+    compression: ArchiveCompression,
+    dst: PathBuf,
+) -> crate::error::Result<ArchiveManifest> {
+    let info = load_manifest(profile_id)
+        .await
+        .remove(name)
+        .ok_or_else(|| BrowsionError::ProfileNotFound(format!("snapshot '{}' of profile {}", name, profile_id)))?;
+    let manifest = ArchiveManifest::new(profile_id, info);
+
+    // Keep the garbage collector away from the blobs being read
+    let _guard = STORE_LOCK.lock().await;
+    let tree = match manifest.snapshot.storage {
+        SnapshotStorage::Chunked => Some(load_tree(profile_id, name).await.map_err(BrowsionError::Config)?),
+        SnapshotStorage::Copy => None,
+    };
+    let copy_dir = snapshot_data_dir(profile_id, name);
+    let written = manifest.clone();
+    tokio::task::spawn_blocking(move || {
+        let store = blob_store();
+        let data = match &tree {
+            Some(tree) => SnapshotData::Chunked(&store, tree),
+            None => SnapshotData::Copy(&copy_dir),
+        };
+        let result = std::fs::File::create(&dst)
+            .map_err(BrowsionError::from)
+            .and_then(|file| snapshot_archive::write_archive(std::io::BufWriter::new(file), &written, data, compression))
+            .and_then(|mut writer| std::io::Write::flush(&mut writer).map_err(BrowsionError::from));
+        if result.is_err() {
+            let _ = std::fs::remove_file(&dst);
+        }
+        result
+    })
+    .await
+    .map_err(|e| BrowsionError::Process(e.to_string()))??;
+    Ok(manifest)
+}
+
+/// Import a snapshot archive as a snapshot of `profile_id`, named `name` or,
+/// by default, as in the archive.
+pub async fn core_import_snapshot(
+    config: &AppConfig,
+    profile_id: &str,
+    archive: &Path,
+    name: Option<&str>,
+) -> crate::error::Result<SnapshotInfo> {
+    if !config.profiles.iter().any(|p| p.id == profile_id) {
+        return Err(BrowsionError::ProfileNotFound(profile_id.to_string()));
+    }
+    let file = std::fs::File::open(archive)
+        .map_err(|e| BrowsionError::Io(std::io::Error::new(e.kind(), format!("{}: {}", archive.display(), e))))?;
+    let (archived, mut archive) = snapshot_archive::read_archive(std::io::BufReader::new(file))?;
+    let name = name.map(str::to_string).unwrap_or_else(|| archived.snapshot.name.clone());
+    validate_snapshot_name(&name).map_err(BrowsionError::Validation)?;
+
+    let _guard = STORE_LOCK.lock().await;
+    let mut manifest = load_manifest(profile_id).await;
+    if manifest.contains_key(&name) || snapshot_data_dir(profile_id, &name).exists() {
+        return Err(BrowsionError::Conflict(format!(
+            "Snapshot '{}' already exists for profile {}",
+            name, profile_id
+        )));
+    }
+    tokio::fs::create_dir_all(profile_snapshot_dir(profile_id)).await?;
+    let imported = tokio::task::spawn_blocking(move || snapshot_archive::import_data(&mut archive, &blob_store()))
+        .await
+        .map_err(|e| BrowsionError::Process(e.to_string()))?;
+    let (tree, new_bytes) = match imported {
+        Ok(imported) => imported,
+        Err(e) => {
+            // Drop the blobs stored before the failure
+            let _ = collect_garbage_locked().await;
+            return Err(e);
+        }
+    };
+    save_tree(profile_id, &name, &tree).await?;
+
+    let info = SnapshotInfo {
+        name: name.clone(),
+        created_at_ts: archived.snapshot.created_at_ts,
+        size_bytes: tree.size(),
+        unique_bytes: new_bytes,
+        storage: SnapshotStorage::Chunked,
+        chrome_version: archived.snapshot.chrome_version,
+        warnings: archived.snapshot.warnings,
+    };
+    manifest.insert(name, info.clone());
+    save_manifest(profile_id, &manifest).await?;
+    Ok(info)
 }
 
 // ---------------------------------------------------------------------------
@@ -253,7 +625,7 @@ pub async fn restore_snapshot(
     profile_id: String,
     name: String,
     state: State<'_, Arc<AppState>>,
-) -> Result<(), String> {
+) -> Result<Vec<String>, String> {
     let config = state.config.read().clone();
     core_restore_snapshot(&profile_id, &name, &config, &state.process_manager).await
 }
@@ -266,3 +638,47 @@ pub async fn delete_snapshot(
 ) -> Result<(), String> {
     core_delete_snapshot(&profile_id, &name).await
 }
+
+/// Write a snapshot archive to `path`.
+#[tauri::command]
+pub async fn export_snapshot(
+    profile_id: String,
+    name: String,
+    path: PathBuf,
+    compression: Option<ArchiveCompression>,
+    _state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    core_export_snapshot(&profile_id, &name, compression.unwrap_or_default(), path)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Import the snapshot archive at `path` into the profile's snapshots.
+#[tauri::command]
+pub async fn import_snapshot(
+    profile_id: String,
+    path: PathBuf,
+    name: Option<String>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<SnapshotInfo, String> {
+    let config = state.config.read().clone();
+    core_import_snapshot(&config, &profile_id, &path, name.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_warning() {
+        assert_eq!(version_warning("s", "145.0.7632.117", "145.0.7632.117"), None);
+        assert!(version_warning("s", "145.0.7632.117", "144.0.7559.96")
+            .unwrap()
+            .contains("may refuse"));
+        let older = version_warning("s", "144.0.7559.96", "145.0.7632.117").unwrap();
+        assert!(!older.contains("may refuse"));
+    }
+}
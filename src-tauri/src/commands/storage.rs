@@ -0,0 +1,418 @@
+//! Disk usage report and cleanup of orphaned data.
+//!
+//! Scans profile data directories, snapshots, downloaded Chrome for Testing
+//! versions and moved trash data, and marks what nothing refers to any more:
+//! directories under `~/.browsion/profiles` of deleted profiles, snapshots of
+//! deleted profiles, CfT versions that are neither configured nor running, and
+//! trash data without a trash entry. Cleanup only ever removes orphans, and
+//! only touches anything outside `~/.browsion` (a custom CfT download
+//! directory) when the caller confirms it with `include_external`.
+//! The snapshot blob store is reported but never orphaned: removing snapshots
+//! is followed by a garbage collection that frees the blobs they alone used.
+
+use crate::commands::snapshots::{self, dir_size, snapshots_root, BLOBS_DIR};
+use crate::cft::version::downloaded_versions;
+use crate::commands::trash::trash_root;
+use crate::config::schema::{AppConfig, BrowserSource};
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::State;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtifactKind {
+    /// A profile's user data directory.
+    ProfileData,
+    /// All snapshots of one profile (`snapshots/<profile_id>`).
+    Snapshots,
+    /// The chunk store shared by all snapshots (`snapshots/.blobs`).
+    SnapshotBlobs,
+    /// One downloaded Chrome for Testing version.
+    CftVersion,
+    /// A data directory moved into the trash.
+    Trash,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageArtifact {
+    pub kind: ArtifactKind,
+    pub path: PathBuf,
+    pub size_bytes: u64,
+    /// Profile id, CfT version or trash entry id the artifact belongs to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    pub orphaned: bool,
+    /// Outside `~/.browsion`; removed only with `include_external`.
+    pub external: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageReport {
+    pub artifacts: Vec<StorageArtifact>,
+    pub total_bytes: u64,
+    pub orphaned_bytes: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CleanupRequest {
+    /// Categories to clean; empty means all of them.
+    #[serde(default)]
+    pub kinds: Vec<ArtifactKind>,
+    /// Only report what would be removed (the default).
+    #[serde(default = "default_true")]
+    pub dry_run: bool,
+    /// Also remove orphans outside `~/.browsion`.
+    #[serde(default)]
+    pub include_external: bool,
+}
+
+impl Default for CleanupRequest {
+    fn default() -> Self {
+        Self {
+            kinds: Vec::new(),
+            dry_run: true,
+            include_external: false,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CleanupReport {
+    pub dry_run: bool,
+    pub removed: Vec<StorageArtifact>,
+    pub freed_bytes: u64,
+    /// Orphans outside `~/.browsion` left alone because `include_external` was not set.
+    pub skipped_external: Vec<StorageArtifact>,
+    /// Artifacts that could not be removed, with the reason.
+    pub errors: Vec<String>,
+}
+
+/// What the scan needs from the config and process manager, captured up front
+/// so no lock is held while walking the disk.
+#[derive(Debug, Clone, Default)]
+pub struct StorageContext {
+    pub profile_dirs: Vec<(String, PathBuf)>,
+    /// Ids of live and trashed profiles (snapshots of either are kept).
+    pub known_profiles: HashSet<String>,
+    /// Data directories of trashed profiles left in place.
+    pub trashed_dirs: Vec<(String, PathBuf)>,
+    pub trash_entries: HashSet<String>,
+    pub cft_dir: Option<PathBuf>,
+    pub cft_version: Option<String>,
+    /// Chrome executables and data directories of running browsers.
+    pub running_paths: Vec<PathBuf>,
+}
+
+impl StorageContext {
+    pub fn from_state(state: &AppState) -> Self {
+        let mut ctx = Self::from_config(&state.config.read());
+        for id in state.process_manager.get_running_profiles() {
+            if let Some(info) = state.process_manager.get_process_info(&id) {
+                ctx.running_paths.extend(info.chrome_path);
+                ctx.running_paths.extend(info.user_data_dir);
+            }
+        }
+        ctx
+    }
+
+    pub fn from_config(config: &AppConfig) -> Self {
+        let mut ctx = Self {
+            profile_dirs: config
+                .profiles
+                .iter()
+                .map(|p| (p.id.clone(), p.user_data_dir.clone()))
+                .collect(),
+            ..Self::default()
+        };
+        ctx.known_profiles = config.profiles.iter().map(|p| p.id.clone()).collect();
+        for entry in &config.trash {
+            ctx.known_profiles.insert(entry.profile.id.clone());
+            ctx.trash_entries.insert(entry.id.clone());
+            if entry.data_path.is_none() {
+                ctx.trashed_dirs.push((entry.profile.id.clone(), entry.profile.user_data_dir.clone()));
+            }
+        }
+        if let BrowserSource::ChromeForTesting { version, download_dir, .. } = &config.browser_source {
+            ctx.cft_dir = Some(download_dir.clone());
+            ctx.cft_version = version.clone();
+        }
+        ctx
+    }
+
+    fn is_running(&self, path: &Path) -> bool {
+        self.running_paths.iter().any(|p| p.starts_with(path))
+    }
+}
+
+/// Writers that put data on disk before the config refers to it (import,
+/// clone, trash, restore) hold this shared until they have saved; cleanup
+/// holds it exclusively, so it never sees such a directory as an orphan.
+static DATA_LOCK: tokio::sync::RwLock<()> = tokio::sync::RwLock::const_new(());
+
+/// Guard for writing profile data that the config does not know about yet.
+pub(crate) async fn data_write_guard() -> tokio::sync::RwLockReadGuard<'static, ()> {
+    DATA_LOCK.read().await
+}
+
+fn browsion_root() -> PathBuf {
+    dirs::home_dir().unwrap_or_else(|| PathBuf::from(".")).join(".browsion")
+}
+
+fn profiles_root() -> PathBuf {
+    browsion_root().join("profiles")
+}
+
+fn subdirs(root: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(rd) = std::fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<(String, PathBuf)> = rd
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|e| (e.file_name().to_string_lossy().into_owned(), e.path()))
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// Every artifact on disk with its owner and orphan flag; sizes are left at 0.
+pub fn scan_artifacts(
+    ctx: &StorageContext,
+    profiles_root: &Path,
+    snapshots_root: &Path,
+    trash_root: &Path,
+) -> Vec<StorageArtifact> {
+    let artifact = |kind, path: PathBuf, owner: Option<String>, orphaned| StorageArtifact {
+        kind,
+        path,
+        size_bytes: 0,
+        owner,
+        orphaned,
+        external: false,
+    };
+    let mut artifacts = Vec::new();
+
+    // Data directories of live profiles, wherever they are
+    for (id, dir) in &ctx.profile_dirs {
+        if dir.is_dir() {
+            artifacts.push(artifact(ArtifactKind::ProfileData, dir.clone(), Some(id.clone()), false));
+        }
+    }
+    // Trashed profiles whose data was left in place still own it
+    for (id, dir) in &ctx.trashed_dirs {
+        if dir.is_dir() && !artifacts.iter().any(|a| &a.path == dir) {
+            artifacts.push(artifact(ArtifactKind::ProfileData, dir.clone(), Some(id.clone()), false));
+        }
+    }
+    // Anything else in the default location belongs to nobody
+    for (_, dir) in subdirs(profiles_root) {
+        let owned = ctx
+            .profile_dirs
+            .iter()
+            .chain(&ctx.trashed_dirs)
+            .any(|(_, d)| d.starts_with(&dir) || dir.starts_with(d));
+        if !owned {
+            let orphaned = !ctx.is_running(&dir);
+            artifacts.push(artifact(ArtifactKind::ProfileData, dir, None, orphaned));
+        }
+    }
+
+    for (profile_id, dir) in subdirs(snapshots_root) {
+        if profile_id == BLOBS_DIR {
+            artifacts.push(artifact(ArtifactKind::SnapshotBlobs, dir, None, false));
+            continue;
+        }
+        let orphaned = !ctx.known_profiles.contains(&profile_id);
+        artifacts.push(artifact(ArtifactKind::Snapshots, dir, Some(profile_id), orphaned));
+    }
+
+    if let Some(cft_dir) = &ctx.cft_dir {
+        // The download dir may be shared: only count what is clearly a CfT download
+        let versions = downloaded_versions(cft_dir);
+        // Without a pinned version the newest download is the one in use
+        let current = ctx
+            .cft_version
+            .clone()
+            .or_else(|| versions.last().map(|(v, _)| v.clone()));
+        for (version, dir) in versions {
+            let orphaned = current.as_deref() != Some(version.as_str()) && !ctx.is_running(&dir);
+            artifacts.push(artifact(ArtifactKind::CftVersion, dir, Some(version), orphaned));
+        }
+    }
+
+    for (entry_id, dir) in subdirs(trash_root) {
+        let orphaned = !ctx.trash_entries.contains(&entry_id);
+        artifacts.push(artifact(ArtifactKind::Trash, dir, Some(entry_id), orphaned));
+    }
+    artifacts
+}
+
+async fn build_report(ctx: &StorageContext) -> StorageReport {
+    let mut artifacts = scan_artifacts(ctx, &profiles_root(), &snapshots_root(), &trash_root());
+    let root = browsion_root();
+    for artifact in artifacts.iter_mut() {
+        artifact.size_bytes = dir_size(&artifact.path).await;
+        artifact.external = !artifact.path.starts_with(&root);
+    }
+    let total_bytes = artifacts.iter().map(|a| a.size_bytes).sum();
+    let orphaned_bytes = artifacts.iter().filter(|a| a.orphaned).map(|a| a.size_bytes).sum();
+    StorageReport {
+        artifacts,
+        total_bytes,
+        orphaned_bytes,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Core functions (shared by Tauri commands + HTTP handlers)
+// ---------------------------------------------------------------------------
+
+pub async fn core_storage_report(state: &AppState) -> StorageReport {
+    build_report(&StorageContext::from_state(state)).await
+}
+
+/// Remove the orphaned artifacts of the requested kinds.
+pub async fn core_cleanup_storage(state: &AppState, req: &CleanupRequest) -> CleanupReport {
+    let _guard = DATA_LOCK.write().await;
+    let report = build_report(&StorageContext::from_state(state)).await;
+    let selected = report
+        .artifacts
+        .into_iter()
+        .filter(|a| a.orphaned && (req.kinds.is_empty() || req.kinds.contains(&a.kind)));
+
+    let mut removed = Vec::new();
+    let mut skipped_external = Vec::new();
+    let mut errors = Vec::new();
+    for artifact in selected {
+        if artifact.external && !req.include_external {
+            skipped_external.push(artifact);
+            continue;
+        }
+        if !req.dry_run {
+            if let Err(e) = tokio::fs::remove_dir_all(&artifact.path).await {
+                errors.push(format!("{}: {}", artifact.path.display(), e));
+                continue;
+            }
+        }
+        removed.push(artifact);
+    }
+    let mut freed_bytes = removed.iter().map(|a| a.size_bytes).sum();
+    if !req.dry_run && removed.iter().any(|a| a.kind == ArtifactKind::Snapshots) {
+        match snapshots::core_collect_garbage().await {
+            Ok((_, bytes)) => freed_bytes += bytes,
+            Err(e) => errors.push(format!("snapshot blobs: {}", e)),
+        }
+    }
+    if !req.dry_run && !removed.is_empty() {
+        tracing::info!("Storage cleanup removed {} orphaned artifacts", removed.len());
+    }
+    CleanupReport {
+        dry_run: req.dry_run,
+        removed,
+        freed_bytes,
+        skipped_external,
+        errors,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+#[tauri::command]
+pub async fn get_storage_report(state: State<'_, Arc<AppState>>) -> Result<StorageReport, String> {
+    Ok(core_storage_report(&state).await)
+}
+
+#[tauri::command]
+pub async fn cleanup_storage(
+    request: CleanupRequest,
+    state: State<'_, Arc<AppState>>,
+) -> Result<CleanupReport, String> {
+    Ok(core_cleanup_storage(&state, &request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lay out a fake Chrome binary the way `find_chrome_in_dir` looks for it.
+    fn fake_chrome(dir: &Path) {
+        let bin = if cfg!(target_os = "macos") {
+            dir.join("Google Chrome for Testing.app/Contents/MacOS/Google Chrome for Testing")
+        } else if cfg!(windows) {
+            dir.join("chrome-win64/chrome.exe")
+        } else {
+            dir.join("chrome-linux64/chrome")
+        };
+        std::fs::create_dir_all(bin.parent().unwrap()).unwrap();
+        std::fs::write(bin, b"").unwrap();
+    }
+
+
+    #[test]
+    fn test_scan_marks_orphans() {
+        let base = std::env::temp_dir().join(format!("browsion-storage-{}", uuid::Uuid::new_v4()));
+        let (profiles, snapshots, trash, cft) =
+            (base.join("profiles"), base.join("snapshots"), base.join("trash"), base.join("cft"));
+        for dir in [
+            profiles.join("live"),
+            profiles.join("gone"),
+            profiles.join("trashed"),
+            snapshots.join("live"),
+            snapshots.join("gone"),
+            snapshots.join(BLOBS_DIR),
+            cft.join("144.0.7559.96"),
+            cft.join("145.0.7632.117"),
+            trash.join("entry-1"),
+            trash.join("stray"),
+        ] {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        // Version dirs need a binary; anything else in the download dir is not ours
+        fake_chrome(&cft.join("144.0.7559.96"));
+        fake_chrome(&cft.join("145.0.7632.117"));
+        fake_chrome(&cft.join("old-chrome"));
+        std::fs::create_dir_all(cft.join("146.0.7680.31")).unwrap();
+
+        let ctx = StorageContext {
+            profile_dirs: vec![("live".to_string(), profiles.join("live"))],
+            known_profiles: ["live", "trashed"].iter().map(|s| s.to_string()).collect(),
+            trashed_dirs: vec![("trashed".to_string(), profiles.join("trashed"))],
+            trash_entries: ["entry-1".to_string()].into_iter().collect(),
+            cft_dir: Some(cft.clone()),
+            cft_version: None,
+            running_paths: Vec::new(),
+        };
+        let artifacts = scan_artifacts(&ctx, &profiles, &snapshots, &trash);
+        let orphans: Vec<&Path> = artifacts.iter().filter(|a| a.orphaned).map(|a| a.path.as_path()).collect();
+        assert_eq!(
+            orphans,
+            [
+                profiles.join("gone").as_path(),
+                snapshots.join("gone").as_path(),
+                cft.join("144.0.7559.96").as_path(),
+                trash.join("stray").as_path(),
+            ]
+        );
+        assert_eq!(artifacts.len(), 10);
+        assert!(artifacts.iter().any(|a| a.kind == ArtifactKind::SnapshotBlobs));
+
+        // A pinned version keeps the older download; a running browser keeps the newer
+        let ctx = StorageContext {
+            cft_version: Some("144.0.7559.96".to_string()),
+            running_paths: vec![cft.join("145.0.7632.117/chrome-linux64/chrome")],
+            ..ctx
+        };
+        let artifacts = scan_artifacts(&ctx, &profiles, &snapshots, &trash);
+        assert!(artifacts.iter().filter(|a| a.kind == ArtifactKind::CftVersion).all(|a| !a.orphaned));
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+}
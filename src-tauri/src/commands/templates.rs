@@ -0,0 +1,522 @@
+//! Profile templates and cloning.
+//!
+//! A template is a `BrowserProfile` whose string fields may contain `{{name}}`
+//! placeholders. Creating profiles from it renders one profile per instance, with
+//! `{{n}}` (instance number), `{{id}}` (the new profile id) and caller-supplied
+//! variables substituted.
+
+use crate::commands::groups::check_profile_group;
+use crate::commands::snapshots::copy_dir_filtered;
+use crate::commands::storage::data_write_guard;
+use crate::config::fingerprint::assign_seed_if_missing;
+use crate::config::schema::{default_user_data_dir, AppConfig, BrowserProfile, ProfileTemplate};
+use crate::config::validation;
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::State;
+
+/// Most profiles one `from-template` request may create.
+pub const MAX_INSTANCES: u32 = 500;
+
+/// Entries of a user data dir that `clone` skips when copying: caches, crash
+/// dumps and the singleton files of a (possibly) running browser. Matched by file
+/// name at any depth.
+pub const CLONE_SKIPPED: &[&str] = &[
+    "Cache",
+    "Code Cache",
+    "GPUCache",
+    "GrShaderCache",
+    "GraphiteDawnCache",
+    "ShaderCache",
+    "DawnCache",
+    "DawnGraphiteCache",
+    "DawnWebGPUCache",
+    "CacheStorage",
+    "ScriptCache",
+    "component_crx_cache",
+    "Crashpad",
+    "SingletonLock",
+    "SingletonSocket",
+    "SingletonCookie",
+    "DevToolsActivePort",
+    "lockfile",
+];
+
+/// Replace every `{{key}}` in `text` from `vars`. Unknown placeholders are an error.
+pub fn substitute(text: &str, vars: &BTreeMap<String, String>) -> Result<String, String> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| format!("Unclosed placeholder in {:?}", text))?;
+        let key = after[..end].trim();
+        let value = vars
+            .get(key)
+            .ok_or_else(|| format!("Unknown template variable {{{{{}}}}}", key))?;
+        out.push_str(value);
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn substitute_value(value: &mut serde_json::Value, vars: &BTreeMap<String, String>) -> Result<(), String> {
+    match value {
+        serde_json::Value::String(s) => *s = substitute(s, vars)?,
+        serde_json::Value::Array(items) => {
+            for item in items {
+                substitute_value(item, vars)?;
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for item in map.values_mut() {
+                substitute_value(item, vars)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Render one instance of `template`. `vars` must already contain `n`; `id` is
+/// added here (rendered from the template id, or generated when it is empty).
+pub fn render_template(
+    template: &BrowserProfile,
+    vars: &BTreeMap<String, String>,
+) -> Result<BrowserProfile, String> {
+    let mut vars = vars.clone();
+    let id = if template.id.trim().is_empty() {
+        uuid::Uuid::new_v4().to_string()
+    } else {
+        substitute(&template.id, &vars)?
+    };
+    vars.insert("id".to_string(), id.clone());
+
+    let mut value = serde_json::to_value(template).map_err(|e| e.to_string())?;
+    substitute_value(&mut value, &vars)?;
+    let mut profile: BrowserProfile = serde_json::from_value(value)
+        .map_err(|e| format!("Template does not render to a valid profile: {}", e))?;
+    profile.id = id;
+    if profile.user_data_dir.as_os_str().is_empty() {
+        profile.user_data_dir = default_user_data_dir(&profile.id);
+    }
+    Ok(profile)
+}
+
+pub(crate) fn skip_on_clone(relative: &Path) -> bool {
+    relative
+        .file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|name| CLONE_SKIPPED.contains(&name))
+}
+
+/// Which profiles to create from a template.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FromTemplateRequest {
+    pub template: String,
+    /// Number of profiles (ignored when `instances` is given).
+    #[serde(default = "default_count")]
+    pub count: u32,
+    /// Value of `{{n}}` for the first instance.
+    #[serde(default = "default_start")]
+    pub start: u32,
+    /// Variables shared by every instance.
+    #[serde(default)]
+    pub vars: BTreeMap<String, String>,
+    /// Per-instance variables; one profile is created per entry.
+    #[serde(default)]
+    pub instances: Vec<BTreeMap<String, String>>,
+}
+
+fn default_count() -> u32 {
+    1
+}
+
+fn default_start() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CloneRequest {
+    /// New profile id (generated when omitted).
+    #[serde(default)]
+    pub id: Option<String>,
+    /// Defaults to "<source name> Copy".
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Defaults to `~/.browsion/profiles/<new id>`.
+    #[serde(default)]
+    pub user_data_dir: Option<PathBuf>,
+    /// Copy the source's user data dir (minus caches and lock files).
+    #[serde(default)]
+    pub copy_data: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplateSummary {
+    pub name: String,
+    pub description: String,
+    /// Placeholders used by the template, besides `n` and `id`.
+    pub variables: Vec<String>,
+}
+
+/// Placeholder names used anywhere in `template`.
+pub fn template_variables(template: &BrowserProfile) -> Vec<String> {
+    let text = serde_json::to_string(template).unwrap_or_default();
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = text.as_str();
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else { break };
+        let name = after[..end].trim().to_string();
+        if name != "n" && name != "id" && !names.contains(&name) {
+            names.push(name);
+        }
+        rest = &after[end + 2..];
+    }
+    names
+}
+
+/// Check that `template` renders to a valid profile with sample values.
+pub fn validate_template(template: &ProfileTemplate) -> Result<(), String> {
+    if template.name.trim().is_empty() {
+        return Err("Template name cannot be empty".to_string());
+    }
+    let mut vars: BTreeMap<String, String> = template_variables(&template.profile)
+        .into_iter()
+        .map(|name| (name, "x".to_string()))
+        .collect();
+    vars.insert("n".to_string(), "1".to_string());
+    let sample = render_template(&template.profile, &vars)?;
+    validation::validate_profile(&sample).map_err(|e| format!("Template: {}", e))
+}
+
+/// Render every requested instance and check them against each other and `config`.
+pub fn plan_from_template(config: &AppConfig, req: &FromTemplateRequest) -> Result<Vec<BrowserProfile>, String> {
+    let template = config
+        .templates
+        .iter()
+        .find(|t| t.name == req.template)
+        .ok_or_else(|| format!("Template '{}' not found", req.template))?;
+
+    let instances: Vec<BTreeMap<String, String>> = if req.instances.is_empty() {
+        vec![BTreeMap::new(); req.count as usize]
+    } else {
+        req.instances.clone()
+    };
+    if instances.is_empty() || instances.len() > MAX_INSTANCES as usize {
+        return Err(format!("Instance count must be between 1 and {}", MAX_INSTANCES));
+    }
+
+    let mut created: Vec<BrowserProfile> = Vec::with_capacity(instances.len());
+    for (i, own) in instances.iter().enumerate() {
+        let mut vars = req.vars.clone();
+        vars.extend(own.iter().map(|(k, v)| (k.clone(), v.clone())));
+        vars.insert("n".to_string(), (req.start as usize + i).to_string());
+
+        let profile = render_template(&template.profile, &vars)
+            .map_err(|e| format!("Instance {}: {}", i + 1, e))?;
+        validation::validate_profile(&profile).map_err(|e| format!("Instance {}: {}", i + 1, e))?;
+        check_profile_group(config, &profile).map_err(|e| format!("Instance {}: {}", i + 1, e))?;
+        validation::check_profile_conflicts(&config.profiles, &profile, None)
+            .map_err(|e| format!("Instance {}: {}", i + 1, e))?;
+        validation::check_profile_conflicts(&created, &profile, None).map_err(|e| {
+            format!(
+                "Instance {}: {}; add {{{{n}}}} or {{{{id}}}} to id and user_data_dir",
+                i + 1,
+                e
+            )
+        })?;
+        created.push(profile);
+    }
+    Ok(created)
+}
+
+// ---------------------------------------------------------------------------
+// Core functions (shared by Tauri commands + HTTP handlers)
+// ---------------------------------------------------------------------------
+
+pub fn core_list_templates(config: &AppConfig) -> Vec<TemplateSummary> {
+    config
+        .templates
+        .iter()
+        .map(|t| TemplateSummary {
+            name: t.name.clone(),
+            description: t.description.clone(),
+            variables: template_variables(&t.profile),
+        })
+        .collect()
+}
+
+pub fn core_get_template(config: &AppConfig, name: &str) -> Result<ProfileTemplate, String> {
+    config
+        .templates
+        .iter()
+        .find(|t| t.name == name)
+        .cloned()
+        .ok_or_else(|| format!("Template '{}' not found", name))
+}
+
+/// Add or replace the template with `template.name`.
+pub fn core_save_template(state: &AppState, template: ProfileTemplate) -> Result<(), String> {
+    validate_template(&template)?;
+    let mut config = state.config.write();
+    match config.templates.iter_mut().find(|t| t.name == template.name) {
+        Some(slot) => *slot = template,
+        None => config.templates.push(template),
+    }
+    crate::config::save_config(&config).map_err(|e| e.to_string())
+}
+
+/// Returns false when no template has that name.
+pub fn core_delete_template(state: &AppState, name: &str) -> Result<bool, String> {
+    let mut config = state.config.write();
+    let before = config.templates.len();
+    config.templates.retain(|t| t.name != name);
+    if config.templates.len() == before {
+        return Ok(false);
+    }
+    crate::config::save_config(&config).map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/// Create profiles from a template. All instances are checked before any is added.
+pub fn core_create_from_template(
+    state: &AppState,
+    req: &FromTemplateRequest,
+) -> Result<Vec<BrowserProfile>, String> {
+    let created = {
+        let mut config = state.config.write();
+        let mut created = plan_from_template(&config, req)?;
+        for profile in &mut created {
+            assign_seed_if_missing(profile, &config);
+            config.profiles.push(profile.clone());
+        }
+        crate::config::save_config(&config).map_err(|e| e.to_string())?;
+        created
+    };
+    state.emit("profiles-changed");
+    Ok(created)
+}
+
+/// Clone profile `source_id`, optionally copying its browser data.
+pub async fn core_clone_profile(
+    state: &AppState,
+    source_id: &str,
+    req: CloneRequest,
+) -> Result<BrowserProfile, String> {
+    let source = state
+        .config
+        .read()
+        .profiles
+        .iter()
+        .find(|p| p.id == source_id)
+        .cloned()
+        .ok_or_else(|| format!("Profile {} not found", source_id))?;
+    if req.copy_data && state.process_manager.is_running(source_id) {
+        return Err(format!(
+            "Browser must be stopped before copying data of profile {}",
+            source_id
+        ));
+    }
+
+    let mut profile = source.clone();
+    profile.id = req.id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    profile.name = req.name.unwrap_or_else(|| format!("{} Copy", source.name));
+    profile.user_data_dir = req
+        .user_data_dir
+        .unwrap_or_else(|| default_user_data_dir(&profile.id));
+    // A clone must not share the source's fingerprint
+    if let Some(fp) = profile.fingerprint.as_mut() {
+        fp.seed = None;
+    }
+    validation::validate_profile(&profile).map_err(|e| e.to_string())?;
+    validation::check_profile_conflicts(&state.config.read().profiles, &profile, None)
+        .map_err(|e| e.to_string())?;
+
+    let _guard = data_write_guard().await;
+    if req.copy_data && source.user_data_dir.exists() {
+        if profile.user_data_dir.exists() {
+            return Err(format!("{:?} already exists", profile.user_data_dir));
+        }
+        if let Err(e) = copy_dir_filtered(
+            source.user_data_dir.clone(),
+            profile.user_data_dir.clone(),
+            skip_on_clone,
+        )
+        .await
+        {
+            let _ = tokio::fs::remove_dir_all(&profile.user_data_dir).await;
+            return Err(format!("Failed to copy profile data: {}", e));
+        }
+    }
+
+    {
+        let mut config = state.config.write();
+        validation::check_profile_conflicts(&config.profiles, &profile, None).map_err(|e| e.to_string())?;
+        assign_seed_if_missing(&mut profile, &config);
+        if profile.fingerprint.as_ref().is_some_and(|fp| *fp == Default::default()) {
+            profile.fingerprint = None;
+        }
+        config.profiles.push(profile.clone());
+        crate::config::save_config(&config).map_err(|e| e.to_string())?;
+    }
+    state.emit("profiles-changed");
+    Ok(profile)
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+#[tauri::command]
+pub async fn list_templates(state: State<'_, Arc<AppState>>) -> Result<Vec<TemplateSummary>, String> {
+    Ok(core_list_templates(&state.config.read()))
+}
+
+#[tauri::command]
+pub async fn get_template(name: String, state: State<'_, Arc<AppState>>) -> Result<ProfileTemplate, String> {
+    core_get_template(&state.config.read(), &name)
+}
+
+#[tauri::command]
+pub async fn save_template(template: ProfileTemplate, state: State<'_, Arc<AppState>>) -> Result<(), String> {
+    core_save_template(&state, template)
+}
+
+#[tauri::command]
+pub async fn delete_template(name: String, state: State<'_, Arc<AppState>>) -> Result<(), String> {
+    if core_delete_template(&state, &name)? {
+        Ok(())
+    } else {
+        Err(format!("Template '{}' not found", name))
+    }
+}
+
+#[tauri::command]
+pub async fn create_profiles_from_template(
+    request: FromTemplateRequest,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<BrowserProfile>, String> {
+    core_create_from_template(&state, &request)
+}
+
+#[tauri::command]
+pub async fn clone_profile(
+    profile_id: String,
+    request: Option<CloneRequest>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<BrowserProfile, String> {
+    core_clone_profile(&state, &profile_id, request.unwrap_or_default()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template_profile() -> BrowserProfile {
+        BrowserProfile {
+            id: "shop-{{n}}".to_string(),
+            name: "Shop {{n}} ({{city}})".to_string(),
+            description: String::new(),
+            user_data_dir: PathBuf::from("/tmp/browsion-tpl/{{id}}"),
+            proxy_server: Some("socks5://10.0.0.{{n}}:1080".to_string()),
+            lang: "en-US".to_string(),
+            timezone: None,
+            fingerprint: None,
+            color: None,
+            custom_args: vec![],
+            extensions: vec![],
+            launch_flags: Default::default(),
+            tags: vec!["shop".to_string(), "{{city}}".to_string()],
+            group: None,
+            headless: false,
+            window: Default::default(),
+            startup_urls: vec![],
+            display_mode: Default::default(),
+            env: Default::default(),
+            working_dir: None,
+            clear_env: false,
+            snapshot_exclude: vec![],
+        }
+    }
+
+    fn config() -> AppConfig {
+        AppConfig {
+            templates: vec![ProfileTemplate {
+                name: "shop".to_string(),
+                description: String::new(),
+                profile: template_profile(),
+            }],
+            ..AppConfig::default()
+        }
+    }
+
+    fn vars(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_substitute() {
+        let v = vars(&[("n", "3"), ("city", "Berlin")]);
+        assert_eq!(substitute("p-{{n}}-{{ city }}", &v).unwrap(), "p-3-Berlin");
+        assert!(substitute("{{missing}}", &v).is_err());
+        assert!(substitute("{{n", &v).is_err());
+    }
+
+    #[test]
+    fn test_plan_from_template() {
+        let req = FromTemplateRequest {
+            template: "shop".to_string(),
+            count: 3,
+            start: 5,
+            vars: vars(&[("city", "Berlin")]),
+            instances: vec![],
+        };
+        let profiles = plan_from_template(&config(), &req).unwrap();
+        assert_eq!(profiles.len(), 3);
+        assert_eq!(profiles[0].id, "shop-5");
+        assert_eq!(profiles[2].name, "Shop 7 (Berlin)");
+        assert_eq!(profiles[1].user_data_dir, PathBuf::from("/tmp/browsion-tpl/shop-6"));
+        assert_eq!(profiles[1].proxy_server.as_deref(), Some("socks5://10.0.0.6:1080"));
+        assert_eq!(profiles[0].tags, vec!["shop", "Berlin"]);
+
+        let missing_var = FromTemplateRequest { vars: BTreeMap::new(), ..req.clone() };
+        assert!(plan_from_template(&config(), &missing_var).is_err());
+
+        let per_instance = FromTemplateRequest {
+            instances: vec![vars(&[("city", "Paris")]), vars(&[("city", "Rome")])],
+            ..req
+        };
+        let profiles = plan_from_template(&config(), &per_instance).unwrap();
+        assert_eq!(profiles[1].name, "Shop 6 (Rome)");
+    }
+
+    #[test]
+    fn test_plan_rejects_shared_user_data_dir() {
+        let mut config = config();
+        config.templates[0].profile.id = String::new();
+        config.templates[0].profile.user_data_dir = PathBuf::from("/tmp/browsion-tpl/shared");
+        let req = FromTemplateRequest {
+            template: "shop".to_string(),
+            count: 2,
+            start: 1,
+            vars: vars(&[("city", "Berlin")]),
+            instances: vec![],
+        };
+        assert!(plan_from_template(&config, &req).unwrap_err().contains("user_data_dir"));
+    }
+
+    #[test]
+    fn test_skip_on_clone() {
+        assert!(skip_on_clone(Path::new("Default/Cache")));
+        assert!(skip_on_clone(Path::new("SingletonLock")));
+        assert!(!skip_on_clone(Path::new("Default/Cookies")));
+    }
+}
@@ -0,0 +1,304 @@
+//! Profile trash: deleting a profile moves it here instead of dropping it.
+//!
+//! Entries live in `AppConfig.trash`. The data directory stays where it is
+//! unless the caller asks to move it into `~/.browsion/trash/<entry id>`, in
+//! which case purging the entry deletes it for good. Entries older than
+//! `settings.trash_retention_days` are purged by the background maintenance loop.
+
+use crate::commands::snapshots::copy_dir_all;
+use crate::commands::storage::data_write_guard;
+use crate::config::schema::{BrowserProfile, TrashEntry};
+use crate::config::validation;
+use crate::error::{BrowsionError, Result};
+use crate::state::AppState;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::State;
+
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+pub(crate) fn trash_root() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".browsion")
+        .join("trash")
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Ids of the entries older than `retention_days` at `now` (Unix ms).
+/// A retention of 0 never expires anything.
+pub fn expired_entries(trash: &[TrashEntry], retention_days: u32, now: u64) -> Vec<String> {
+    if retention_days == 0 {
+        return Vec::new();
+    }
+    let max_age = retention_days as u64 * DAY_MS;
+    trash
+        .iter()
+        .filter(|e| now.saturating_sub(e.deleted_at_ts) >= max_age)
+        .map(|e| e.id.clone())
+        .collect()
+}
+
+/// Move a directory, falling back to copy + delete across filesystems.
+async fn move_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    if let Some(parent) = to.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    if tokio::fs::rename(from, to).await.is_ok() {
+        return Ok(());
+    }
+    if let Err(e) = copy_dir_all(from.to_path_buf(), to.to_path_buf()).await {
+        let _ = tokio::fs::remove_dir_all(to).await;
+        return Err(e);
+    }
+    tokio::fs::remove_dir_all(from).await
+}
+
+async fn remove_data(path: &Path) {
+    if let Err(e) = tokio::fs::remove_dir_all(path).await {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            tracing::warn!("Failed to delete trashed data {}: {}", path.display(), e);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Core functions (shared by Tauri commands + HTTP handlers)
+// ---------------------------------------------------------------------------
+
+/// Move a profile to the trash. With `move_data`, its data directory (if any)
+/// moves into `~/.browsion/trash` too.
+pub async fn core_trash_profile(state: &AppState, profile_id: &str, move_data: bool) -> Result<TrashEntry> {
+    if state.process_manager.is_running(profile_id) {
+        return Err(BrowsionError::Conflict(format!(
+            "Cannot delete profile {}: it is currently running",
+            profile_id
+        )));
+    }
+    let profile = state
+        .config
+        .read()
+        .profiles
+        .iter()
+        .find(|p| p.id == profile_id)
+        .cloned()
+        .ok_or_else(|| BrowsionError::ProfileNotFound(profile_id.to_string()))?;
+
+    let mut entry = TrashEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        profile,
+        deleted_at_ts: now_ms(),
+        data_path: None,
+    };
+    let data_dir = entry.profile.user_data_dir.clone();
+    let _guard = data_write_guard().await;
+    // Rechecks the running state and keeps launches out until the profile is gone
+    let _reservation = state.process_manager.reserve_profile(profile_id)?;
+    if move_data && data_dir.is_dir() {
+        let dst = trash_root().join(&entry.id);
+        move_dir(&data_dir, &dst).await?;
+        entry.data_path = Some(dst);
+    }
+
+    let saved = {
+        let mut config = state.config.write();
+        match config.profiles.iter().position(|p| p.id == profile_id) {
+            Some(pos) => {
+                let removed = config.profiles.remove(pos);
+                config.recent_profiles.retain(|id| id != profile_id);
+                config.trash.push(entry.clone());
+                let saved = crate::config::save_config(&config);
+                if saved.is_err() {
+                    config.trash.pop();
+                    config.profiles.insert(pos, removed);
+                }
+                saved
+            }
+            None => Err(BrowsionError::ProfileNotFound(profile_id.to_string())),
+        }
+    };
+    if let Err(e) = saved {
+        if let Some(path) = &entry.data_path {
+            if let Err(err) = move_dir(path, &data_dir).await {
+                tracing::error!("Failed to move {} back to {}: {}", path.display(), data_dir.display(), err);
+            }
+        }
+        return Err(e);
+    }
+    state.emit("profiles-changed");
+    Ok(entry)
+}
+
+/// Put a trashed profile back. Fails with a conflict when its id or data
+/// directory has been taken in the meantime. A group that no longer exists is
+/// dropped.
+pub async fn core_restore_profile(state: &AppState, entry_id: &str) -> Result<BrowserProfile> {
+    let entry = {
+        let config = state.config.read();
+        let entry = config
+            .trash
+            .iter()
+            .find(|e| e.id == entry_id)
+            .cloned()
+            .ok_or_else(|| BrowsionError::ProfileNotFound(format!("trash entry {}", entry_id)))?;
+        validation::check_profile_conflicts(&config.profiles, &entry.profile, None)?;
+        entry
+    };
+    let mut profile = entry.profile.clone();
+    let _guard = data_write_guard().await;
+    if let Some(path) = &entry.data_path {
+        if profile.user_data_dir.exists() {
+            return Err(BrowsionError::Conflict(format!(
+                "{} already exists",
+                profile.user_data_dir.display()
+            )));
+        }
+        if path.is_dir() {
+            move_dir(path, &profile.user_data_dir).await?;
+        }
+    }
+
+    // Re-check under the write lock: the config may have changed meanwhile
+    let saved = {
+        let mut config = state.config.write();
+        if !config.groups.iter().any(|g| Some(&g.id) == profile.group.as_ref()) {
+            profile.group = None;
+        }
+        match config.trash.iter().position(|e| e.id == entry_id) {
+            Some(pos) => validation::check_profile_conflicts(&config.profiles, &profile, None).and_then(|()| {
+                config.trash.remove(pos);
+                config.profiles.push(profile.clone());
+                let saved = crate::config::save_config(&config);
+                if saved.is_err() {
+                    config.profiles.pop();
+                    config.trash.insert(pos, entry.clone());
+                }
+                saved
+            }),
+            None => Err(BrowsionError::ProfileNotFound(format!("trash entry {}", entry_id))),
+        }
+    };
+    if let Err(e) = saved {
+        if let Some(path) = &entry.data_path {
+            if let Err(err) = move_dir(&profile.user_data_dir, path).await {
+                tracing::error!("Failed to move {} back to the trash: {}", profile.user_data_dir.display(), err);
+            }
+        }
+        return Err(e);
+    }
+    state.emit("profiles-changed");
+    Ok(profile)
+}
+
+/// Permanently delete the given trash entries and their moved data. Unknown
+/// ids are ignored. Returns the purged entries.
+pub async fn core_purge_trash(state: &AppState, entry_ids: &[String]) -> Result<Vec<TrashEntry>> {
+    let purged: Vec<TrashEntry> = {
+        let mut config = state.config.write();
+        let (purged, kept) = std::mem::take(&mut config.trash)
+            .into_iter()
+            .partition(|e| entry_ids.contains(&e.id));
+        config.trash = kept;
+        if purged.is_empty() {
+            return Ok(purged);
+        }
+        if let Err(e) = crate::config::save_config(&config) {
+            config.trash.extend(purged);
+            return Err(e);
+        }
+        purged
+    };
+    for path in purged.iter().filter_map(|e| e.data_path.as_deref()) {
+        remove_data(path).await;
+    }
+    Ok(purged)
+}
+
+/// Purge the entries past the retention period (run periodically).
+pub async fn core_purge_expired(state: &AppState) -> Result<Vec<TrashEntry>> {
+    let expired = {
+        let config = state.config.read();
+        expired_entries(&config.trash, config.settings.trash_retention_days, now_ms())
+    };
+    core_purge_trash(state, &expired).await
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+#[tauri::command]
+pub async fn list_trash(state: State<'_, Arc<AppState>>) -> std::result::Result<Vec<TrashEntry>, String> {
+    Ok(state.config.read().trash.clone())
+}
+
+#[tauri::command]
+pub async fn restore_profile(
+    entry_id: String,
+    state: State<'_, Arc<AppState>>,
+) -> std::result::Result<BrowserProfile, String> {
+    core_restore_profile(&state, &entry_id).await.map_err(|e| e.to_string())
+}
+
+/// Purge one trash entry, or the whole trash when `entry_id` is omitted.
+#[tauri::command]
+pub async fn purge_trash(
+    entry_id: Option<String>,
+    state: State<'_, Arc<AppState>>,
+) -> std::result::Result<(), String> {
+    let ids = match entry_id {
+        Some(id) => vec![id],
+        None => state.config.read().trash.iter().map(|e| e.id.clone()).collect(),
+    };
+    core_purge_trash(&state, &ids).await.map(|_| ()).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, deleted_at_ts: u64) -> TrashEntry {
+        TrashEntry {
+            id: id.to_string(),
+            profile: serde_json::from_value(serde_json::json!({
+                "id": id, "name": id, "user_data_dir": "/tmp/x"
+            }))
+            .unwrap(),
+            deleted_at_ts,
+            data_path: None,
+        }
+    }
+
+    #[test]
+    fn test_expired_entries() {
+        let now = 100 * DAY_MS;
+        let trash = vec![entry("old", now - 31 * DAY_MS), entry("new", now - DAY_MS)];
+        assert_eq!(expired_entries(&trash, 30, now), ["old"]);
+        assert_eq!(expired_entries(&trash, 1, now), ["old", "new"]);
+        assert!(expired_entries(&trash, 0, now).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_move_dir_round_trip() {
+        let base = std::env::temp_dir().join(format!("browsion-trash-{}", uuid::Uuid::new_v4()));
+        let src = base.join("profile");
+        std::fs::create_dir_all(src.join("Default")).unwrap();
+        std::fs::write(src.join("Default/Cookies"), b"cookies").unwrap();
+
+        let dst = base.join("trash/entry");
+        move_dir(&src, &dst).await.unwrap();
+        assert!(!src.exists());
+        assert_eq!(std::fs::read(dst.join("Default/Cookies")).unwrap(), b"cookies");
+
+        move_dir(&dst, &src).await.unwrap();
+        assert!(src.join("Default/Cookies").exists());
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+}
@@ -0,0 +1,108 @@
+//! Snapshot exclusion rules (`snapshot_exclude` in app settings and profiles).
+
+/// Glob rules deciding what a snapshot leaves out, evaluated in order against
+/// `/`-separated paths relative to the user data dir; the last matching rule
+/// wins. A rule without `/` matches an entry of that name at any depth
+/// (`Cache`, `Singleton*`); one with `/` matches the whole path
+/// (`Default/History*`, `*/Service Worker`). A leading `!` keeps what earlier
+/// rules exclude. Excluding a directory excludes everything below it.
+#[derive(Debug, Default)]
+pub struct ExcludeRules {
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug)]
+struct Rule {
+    pattern: glob::Pattern,
+    keep: bool,
+    anchored: bool,
+}
+
+impl ExcludeRules {
+    /// Parse `rules`; blank ones are ignored.
+    pub fn new<S: AsRef<str>>(rules: &[S]) -> Result<Self, String> {
+        let rules = rules
+            .iter()
+            .map(|rule| rule.as_ref().trim())
+            .filter(|rule| !rule.is_empty())
+            .map(|rule| {
+                let (keep, glob) = match rule.strip_prefix('!') {
+                    Some(rest) => (true, rest),
+                    None => (false, rule),
+                };
+                let glob = glob.trim_matches('/');
+                if glob.is_empty() {
+                    return Err(format!("Empty snapshot exclusion rule {:?}", rule));
+                }
+                let pattern = glob::Pattern::new(glob)
+                    .map_err(|e| format!("Invalid snapshot exclusion rule {:?}: {}", rule, e))?;
+                Ok(Rule {
+                    pattern,
+                    keep,
+                    anchored: glob.contains('/'),
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Self { rules })
+    }
+
+    /// Whether the entry at `path` is left out.
+    pub fn excludes(&self, path: &str) -> bool {
+        let options = glob::MatchOptions {
+            case_sensitive: true,
+            require_literal_separator: true,
+            require_literal_leading_dot: false,
+        };
+        let name = path.rsplit('/').next().unwrap_or(path);
+        let mut excluded = false;
+        for rule in &self.rules {
+            let subject = if rule.anchored { path } else { name };
+            if rule.pattern.matches_with(subject, options) {
+                excluded = !rule.keep;
+            }
+        }
+        excluded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::schema::DEFAULT_SNAPSHOT_EXCLUDE;
+
+    #[test]
+    fn test_exclude_rules() {
+        let rules = ExcludeRules::new(&["Cache", "Singleton*", "Default/History*", "*/Service Worker", "!Default/Cache"]).unwrap();
+        assert!(rules.excludes("Default/Network/Cache"));
+        assert!(rules.excludes("SingletonLock"));
+        assert!(rules.excludes("Default/History"));
+        assert!(rules.excludes("Profile 1/Service Worker"));
+        assert!(!rules.excludes("Default/Cache"));
+        assert!(!rules.excludes("Profile 1/History"));
+        assert!(!rules.excludes("Default/Cookies"));
+        assert!(!rules.excludes("Default/Service Worker/Database"));
+        assert!(ExcludeRules::new(&["!"]).is_err());
+        assert!(ExcludeRules::new(&["[a"]).is_err());
+
+        let defaults = ExcludeRules::new(DEFAULT_SNAPSHOT_EXCLUDE).unwrap();
+        for kept in ["Default", "Default/Cookies", "Default/Cookies-journal", "Default/Network/Cookies",
+            "Default/Local Storage", "Default/Local Storage/leveldb/000003.log", "Default/IndexedDB",
+            "Default/IndexedDB/https_example.com_0.indexeddb.leveldb", "Default/Preferences",
+            "Default/Login Data", "Default/Service Worker/Database", "Local State", "Last Version"]
+        {
+            assert!(!defaults.excludes(kept), "{}", kept);
+        }
+        for dropped in ["Default/History", "Default/History-journal", "Default/Cache", "Default/Code Cache",
+            "Default/Service Worker/CacheStorage", "GrShaderCache", "Crashpad", "SingletonLock", "Profile 1"]
+        {
+            assert!(defaults.excludes(dropped), "{}", dropped);
+        }
+        // Later rules (a profile's own) can keep more
+        let rules: Vec<&str> = DEFAULT_SNAPSHOT_EXCLUDE
+            .iter()
+            .copied()
+            .chain(["!Default/History"])
+            .collect();
+        assert!(!ExcludeRules::new(&rules).unwrap().excludes("Default/History"));
+    }
+}
@@ -0,0 +1,123 @@
+//! Fingerprint helpers: legacy config compatibility and deterministic seeds.
+
+use crate::config::schema::{AppConfig, BrowserProfile, FingerprintConfig};
+use serde::{Deserialize, Deserializer};
+
+/// 32-bit FNV-1a hash.
+pub fn fnv1a_32(data: &[u8]) -> u32 {
+    let mut hash: u32 = 0x811c_9dc5;
+    for byte in data {
+        hash ^= u32::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    hash
+}
+
+/// Seed for a legacy string fingerprint: the number itself, or a hash of the text.
+pub fn seed_from_str(value: &str) -> u32 {
+    let value = value.trim();
+    value
+        .parse::<u32>()
+        .unwrap_or_else(|_| fnv1a_32(value.as_bytes()))
+}
+
+/// Accepts the structured object, a bare number, or the legacy string seed.
+pub fn deserialize_fingerprint<'de, D>(deserializer: D) -> Result<Option<FingerprintConfig>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+        Seed(u32),
+        Legacy(String),
+        Full(FingerprintConfig),
+    }
+
+    Ok(match Option::<Repr>::deserialize(deserializer)? {
+        None => None,
+        Some(Repr::Seed(seed)) => Some(FingerprintConfig {
+            seed: Some(seed),
+            ..Default::default()
+        }),
+        Some(Repr::Legacy(s)) if s.trim().is_empty() => None,
+        Some(Repr::Legacy(s)) => Some(FingerprintConfig {
+            seed: Some(seed_from_str(&s)),
+            ..Default::default()
+        }),
+        Some(Repr::Full(config)) => Some(config),
+    })
+}
+
+/// Deterministic seed for `profile_id` that no seed in `taken` already uses.
+/// The same id and the same set of taken seeds always give the same result.
+pub fn generate_seed(profile_id: &str, taken: &[u32]) -> u32 {
+    let mut attempt: u32 = 0;
+    loop {
+        let input = format!("browsion:{}:{}", profile_id, attempt);
+        let seed = fnv1a_32(input.as_bytes());
+        if !taken.contains(&seed) {
+            return seed;
+        }
+        attempt += 1;
+    }
+}
+
+/// Give a new profile a unique seed when the browser is fingerprint-chromium and
+/// the profile does not set one.
+pub fn assign_seed_if_missing(profile: &mut BrowserProfile, config: &AppConfig) {
+    if !config.browser_source.is_fingerprint_chromium() {
+        return;
+    }
+    if profile.fingerprint.as_ref().is_some_and(|f| f.seed.is_some()) {
+        return;
+    }
+    let taken: Vec<u32> = config
+        .profiles
+        .iter()
+        .filter_map(|p| p.fingerprint.as_ref().and_then(|f| f.seed))
+        .collect();
+    let seed = generate_seed(&profile.id, &taken);
+    profile.fingerprint.get_or_insert_with(Default::default).seed = Some(seed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Holder {
+        #[serde(default, deserialize_with = "deserialize_fingerprint")]
+        fingerprint: Option<FingerprintConfig>,
+    }
+
+    fn parse(json: &str) -> Option<FingerprintConfig> {
+        serde_json::from_str::<Holder>(json).unwrap().fingerprint
+    }
+
+    #[test]
+    fn test_legacy_and_structured_forms() {
+        assert_eq!(parse(r#"{"fingerprint": "10000"}"#).unwrap().seed, Some(10000));
+        assert_eq!(parse(r#"{"fingerprint": 42}"#).unwrap().seed, Some(42));
+        assert_eq!(
+            parse(r#"{"fingerprint": "not-a-number"}"#).unwrap().seed,
+            Some(fnv1a_32(b"not-a-number"))
+        );
+        assert!(parse(r#"{"fingerprint": ""}"#).is_none());
+        assert!(parse(r#"{}"#).is_none());
+
+        let full = parse(r#"{"fingerprint": {"seed": 7, "platform": "windows", "hardware_concurrency": 8}}"#)
+            .unwrap();
+        assert_eq!(full.seed, Some(7));
+        assert_eq!(full.hardware_concurrency, Some(8));
+    }
+
+    #[test]
+    fn test_generate_seed_is_deterministic_and_unique() {
+        let a = generate_seed("profile-a", &[]);
+        assert_eq!(a, generate_seed("profile-a", &[]));
+        assert_ne!(a, generate_seed("profile-b", &[]));
+        let again = generate_seed("profile-a", &[a]);
+        assert_ne!(again, a);
+    }
+}
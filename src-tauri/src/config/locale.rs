@@ -0,0 +1,337 @@
+//! Timezone and language validation for profiles.
+//!
+//! Timezones are checked against the IANA database bundled with `chrono-tz`.
+//! `lang` is either a single BCP-47 tag (`de-DE`) or an Accept-Language list
+//! (`de-DE,de;q=0.9,en;q=0.8`); the first (highest weighted) tag becomes Chrome's UI
+//! locale and must map to one Chrome ships.
+
+use chrono_tz::{Tz, TZ_VARIANTS};
+use std::str::FromStr;
+
+/// UI locales Chrome ships (`<chrome>/locales/*.pak`).
+pub const CHROME_LOCALES: &[&str] = &[
+    "af", "am", "ar", "as", "az", "be", "bg", "bn", "bs", "ca", "cs", "cy", "da", "de", "el",
+    "en-AU", "en-CA", "en-GB", "en-IN", "en-NZ", "en-US", "en-ZA", "es", "es-419", "et", "eu",
+    "fa", "fi", "fil", "fr", "fr-CA", "gl", "gu", "he", "hi", "hr", "hu", "hy", "id", "is", "it",
+    "ja", "ka", "kk", "km", "kn", "ko", "ky", "lo", "lt", "lv", "mk", "ml", "mn", "mr", "ms",
+    "my", "nb", "ne", "nl", "or", "pa", "pl", "pt-BR", "pt-PT", "ro", "ru", "si", "sk", "sl",
+    "sq", "sr", "sr-Latn", "sv", "sw", "ta", "te", "th", "tr", "uk", "ur", "uz", "vi", "zh-CN",
+    "zh-HK", "zh-TW", "zu",
+];
+
+/// Similarity (Jaro-Winkler) above which a candidate is offered as a suggestion.
+const SUGGEST_THRESHOLD: f64 = 0.85;
+
+/// Up to three candidates closest to `input`, best first.
+fn suggest<'a>(input: &str, candidates: impl Iterator<Item = &'a str>) -> Vec<&'a str> {
+    let needle = input.to_ascii_lowercase().replace(' ', "_");
+    let mut scored: Vec<(f64, &str)> = candidates
+        .map(|c| (strsim::jaro_winkler(&needle, &c.to_ascii_lowercase()), c))
+        .filter(|(score, _)| *score >= SUGGEST_THRESHOLD)
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.into_iter().take(3).map(|(_, c)| c).collect()
+}
+
+fn did_you_mean(suggestions: &[&str]) -> String {
+    if suggestions.is_empty() {
+        String::new()
+    } else {
+        format!(". Did you mean {}?", suggestions.join(", "))
+    }
+}
+
+/// Check an IANA timezone name. The error names the field and suggests close names.
+pub fn validate_timezone(tz: &str) -> Result<(), String> {
+    if Tz::from_str(tz).is_ok() {
+        return Ok(());
+    }
+    let suggestions = suggest(tz, TZ_VARIANTS.iter().map(|t| t.name()));
+    Err(format!(
+        "timezone: unknown IANA timezone {:?}{}",
+        tz,
+        did_you_mean(&suggestions)
+    ))
+}
+
+/// A parsed BCP-47 tag, normalized to canonical case (`zh-Hant-TW`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageTag {
+    pub language: String,
+    pub script: Option<String>,
+    pub region: Option<String>,
+    /// Variants, extensions and private-use subtags, lower case.
+    pub rest: Vec<String>,
+}
+
+impl LanguageTag {
+    /// Parse `language[-script][-region][-variant...][-x-private...]` with a 2-3
+    /// letter language. `_` is accepted as a separator.
+    pub fn parse(tag: &str) -> Result<Self, String> {
+        let subtags: Vec<&str> = tag.split(['-', '_']).collect();
+        let valid_len = |s: &str, min: usize, max: usize| {
+            (min..=max).contains(&s.len()) && s.chars().all(|c| c.is_ascii_alphanumeric())
+        };
+
+        let language = subtags[0];
+        if !valid_len(language, 2, 3) || !language.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(format!("invalid language subtag {:?}", language));
+        }
+
+        let mut parsed = LanguageTag {
+            language: language.to_ascii_lowercase(),
+            script: None,
+            region: None,
+            rest: Vec::new(),
+        };
+        let mut iter = subtags[1..].iter().peekable();
+
+        if let Some(s) = iter.peek() {
+            if s.len() == 4 && s.chars().all(|c| c.is_ascii_alphabetic()) {
+                let lower = s.to_ascii_lowercase();
+                parsed.script = Some(lower[..1].to_ascii_uppercase() + &lower[1..]);
+                iter.next();
+            }
+        }
+        if let Some(s) = iter.peek() {
+            let alpha2 = s.len() == 2 && s.chars().all(|c| c.is_ascii_alphabetic());
+            let digit3 = s.len() == 3 && s.chars().all(|c| c.is_ascii_digit());
+            if alpha2 || digit3 {
+                parsed.region = Some(s.to_ascii_uppercase());
+                iter.next();
+            }
+        }
+
+        let mut after_singleton = false;
+        for s in iter {
+            let ok = if after_singleton {
+                valid_len(s, 1, 8)
+            } else if s.len() == 1 {
+                after_singleton = s.chars().all(|c| c.is_ascii_alphanumeric());
+                after_singleton
+            } else {
+                valid_len(s, 5, 8)
+                    || (s.len() == 4 && s.starts_with(|c: char| c.is_ascii_digit()) && valid_len(s, 4, 4))
+            };
+            if !ok {
+                return Err(format!("invalid subtag {:?}", s));
+            }
+            parsed.rest.push(s.to_ascii_lowercase());
+        }
+        if after_singleton && parsed.rest.last().is_some_and(|s| s.len() == 1) {
+            return Err("extension or private-use singleton without a value".to_string());
+        }
+        Ok(parsed)
+    }
+}
+
+impl std::fmt::Display for LanguageTag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.language)?;
+        for part in self.script.iter().chain(&self.region).chain(&self.rest) {
+            write!(f, "-{}", part)?;
+        }
+        Ok(())
+    }
+}
+
+/// Chrome UI locale to use for `tag`, or None when Chrome has no locale for the language.
+pub fn match_chrome_locale(tag: &LanguageTag) -> Option<&'static str> {
+    let find = |candidate: &str| {
+        CHROME_LOCALES
+            .iter()
+            .copied()
+            .find(|l| l.eq_ignore_ascii_case(candidate))
+    };
+    let language = tag.language.as_str();
+    let region = tag.region.as_deref();
+
+    if let Some(region) = region {
+        if let Some(l) = find(&format!("{}-{}", language, region)) {
+            return Some(l);
+        }
+    }
+    if let Some(script) = tag.script.as_deref() {
+        if let Some(l) = find(&format!("{}-{}", language, script)) {
+            return Some(l);
+        }
+    }
+    match (language, tag.script.as_deref(), region) {
+        ("zh", Some("Hant"), _) | ("zh", _, Some("MO")) => return find("zh-TW"),
+        ("zh", _, _) => return find("zh-CN"),
+        ("pt", _, Some(r)) if r != "BR" => return find("pt-PT"),
+        ("pt", _, _) => return find("pt-BR"),
+        // Latin America and the US use Chrome's regional Spanish
+        ("es", _, Some(r)) if r != "ES" => return find("es-419"),
+        ("en", _, _) => return find("en-US"),
+        ("no" | "nn", _, _) => return find("nb"),
+        ("tl", _, _) => return find("fil"),
+        ("iw", _, _) => return find("he"),
+        ("in", _, _) => return find("id"),
+        _ => {}
+    }
+    find(language)
+}
+
+/// One entry of an Accept-Language list.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightedLanguage {
+    pub tag: LanguageTag,
+    /// q-value, 0.0 to 1.0 (1.0 when not given).
+    pub q: f32,
+}
+
+/// Parse `lang`: a single tag or a comma-separated Accept-Language list with
+/// optional `;q=` weights. Entries are returned highest weight first (stable).
+pub fn parse_accept_language(lang: &str) -> Result<Vec<WeightedLanguage>, String> {
+    let mut entries = Vec::new();
+    for item in lang.split(',') {
+        let item = item.trim();
+        if item.is_empty() {
+            return Err("empty entry in language list".to_string());
+        }
+        let (tag, q) = match item.split_once(';') {
+            None => (item, 1.0),
+            Some((tag, param)) => {
+                let value = param
+                    .trim()
+                    .strip_prefix("q=")
+                    .ok_or_else(|| format!("invalid parameter {:?} (expected q=)", param.trim()))?;
+                let q: f32 = value
+                    .parse()
+                    .ok()
+                    .filter(|q: &f32| (0.0..=1.0).contains(q) && value.len() <= 5)
+                    .ok_or_else(|| format!("invalid q-value {:?} (expected 0 to 1)", value))?;
+                (tag.trim(), q)
+            }
+        };
+        let tag = LanguageTag::parse(tag).map_err(|e| format!("{:?}: {}", tag, e))?;
+        entries.push(WeightedLanguage { tag, q });
+    }
+    entries.sort_by(|a, b| b.q.total_cmp(&a.q));
+    Ok(entries)
+}
+
+/// Validate `lang` and return the parsed list. Errors name the field and, when the
+/// UI language is unsupported, suggest close Chrome locales.
+pub fn validate_lang(lang: &str) -> Result<Vec<WeightedLanguage>, String> {
+    if lang.trim().is_empty() {
+        return Err("lang: language code cannot be empty".to_string());
+    }
+    let entries = parse_accept_language(lang).map_err(|e| {
+        let first = lang.split([',', ';']).next().unwrap_or("").trim();
+        format!(
+            "lang: {:?} is not a valid BCP-47 tag or Accept-Language list: {}{}",
+            lang,
+            e,
+            did_you_mean(&suggest(first, CHROME_LOCALES.iter().copied()))
+        )
+    })?;
+    let primary = &entries[0].tag;
+    if match_chrome_locale(primary).is_none() {
+        let suggestions = suggest(&primary.to_string(), CHROME_LOCALES.iter().copied());
+        return Err(format!(
+            "lang: Chrome has no UI locale for {:?}{}",
+            primary.to_string(),
+            did_you_mean(&suggestions)
+        ));
+    }
+    Ok(entries)
+}
+
+/// `--lang` value (Chrome UI locale) and the `--accept-lang` value, given for a
+/// list or when the UI locale is not the tag itself (`de-AT` runs as `de`). Falls
+/// back to the raw string when `lang` does not parse (validation reports it).
+pub fn chrome_lang_args(lang: &str) -> (String, Option<String>) {
+    let Ok(entries) = parse_accept_language(lang) else {
+        return (lang.to_string(), None);
+    };
+    let ui = match_chrome_locale(&entries[0].tag)
+        .map(str::to_string)
+        .unwrap_or_else(|| entries[0].tag.to_string());
+    let accept = (entries.len() > 1 || ui != entries[0].tag.to_string()).then(|| {
+        entries
+            .iter()
+            .filter(|e| e.q > 0.0)
+            .map(|e| e.tag.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    });
+    (ui, accept)
+}
+
+/// The highest weighted tag of `lang` (the whole string if it does not parse).
+pub fn primary_language(lang: &str) -> String {
+    parse_accept_language(lang)
+        .ok()
+        .and_then(|entries| entries.into_iter().next())
+        .map(|e| e.tag.to_string())
+        .unwrap_or_else(|| lang.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_timezone_suggests() {
+        assert!(validate_timezone("America/Los_Angeles").is_ok());
+        assert!(validate_timezone("UTC").is_ok());
+        let err = validate_timezone("America/Los Angeles").unwrap_err();
+        assert!(err.starts_with("timezone:"));
+        assert!(err.contains("America/Los_Angeles"));
+        assert!(validate_timezone("Mars/Olympus").is_err());
+    }
+
+    #[test]
+    fn test_parse_language_tag() {
+        let tag = LanguageTag::parse("zh_hant_tw").unwrap();
+        assert_eq!(tag.to_string(), "zh-Hant-TW");
+        assert_eq!(LanguageTag::parse("es-419").unwrap().region.as_deref(), Some("419"));
+        assert_eq!(LanguageTag::parse("de-CH-1996").unwrap().rest, vec!["1996"]);
+        assert!(LanguageTag::parse("en-US-x-private").is_ok());
+        assert!(LanguageTag::parse("english").is_err());
+        assert!(LanguageTag::parse("en-").is_err());
+        assert!(LanguageTag::parse("en-x").is_err());
+    }
+
+    #[test]
+    fn test_match_chrome_locale() {
+        let locale = |s: &str| match_chrome_locale(&LanguageTag::parse(s).unwrap());
+        assert_eq!(locale("en-US"), Some("en-US"));
+        assert_eq!(locale("en-IE"), Some("en-US"));
+        assert_eq!(locale("es-MX"), Some("es-419"));
+        assert_eq!(locale("de-AT"), Some("de"));
+        assert_eq!(locale("zh-Hant"), Some("zh-TW"));
+        assert_eq!(locale("pt"), Some("pt-BR"));
+        assert_eq!(locale("tlh"), None);
+    }
+
+    #[test]
+    fn test_accept_language_list() {
+        let entries = parse_accept_language("en;q=0.8, de-DE, de;q=0.9").unwrap();
+        let tags: Vec<String> = entries.iter().map(|e| e.tag.to_string()).collect();
+        assert_eq!(tags, vec!["de-DE", "de", "en"]);
+        assert!(parse_accept_language("de-DE;q=2").is_err());
+        assert!(parse_accept_language("de-DE;level=1").is_err());
+        assert!(parse_accept_language("de-DE,,en").is_err());
+
+        assert_eq!(
+            chrome_lang_args("de-DE,de;q=0.9,en;q=0.8"),
+            ("de".to_string(), Some("de-DE,de,en".to_string()))
+        );
+        assert_eq!(chrome_lang_args("en-US"), ("en-US".to_string(), None));
+        assert_eq!(
+            chrome_lang_args("de-AT"),
+            ("de".to_string(), Some("de-AT".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_validate_lang_errors_name_field() {
+        assert!(validate_lang("fr-FR").is_ok());
+        let err = validate_lang("en-UK-").unwrap_err();
+        assert!(err.starts_with("lang:"));
+        assert!(validate_lang("tlh").unwrap_err().starts_with("lang:"));
+    }
+}
@@ -1,3 +1,7 @@
+pub mod exclude;
+pub mod fingerprint;
+pub mod locale;
+pub mod patch;
 pub mod schema;
 pub mod storage;
 pub mod validation;
@@ -0,0 +1,125 @@
+//! Partial profile updates: JSON Merge Patch (RFC 7396) and content ETags for
+//! optimistic concurrency.
+
+use crate::config::schema::BrowserProfile;
+use crate::error::{BrowsionError, Result};
+use serde_json::Value;
+
+fn fnv1a_64(data: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in data {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+/// Strong ETag of a profile: a hash of its serialized form, so any change (from
+/// the API or the GUI) produces a new tag.
+pub fn profile_etag(profile: &BrowserProfile) -> String {
+    let bytes = serde_json::to_vec(profile).unwrap_or_default();
+    format!("\"{:016x}\"", fnv1a_64(&bytes))
+}
+
+/// Whether an `If-Match` header value matches `etag`: `*`, or a comma-separated
+/// list of tags. `If-Match` uses strong comparison, so weak tags never match.
+pub fn etag_matches(if_match: &str, etag: &str) -> bool {
+    if_match.split(',').map(str::trim).any(|tag| tag == "*" || tag == etag)
+}
+
+/// Apply an RFC 7396 merge patch to `target`: `null` removes a member, objects
+/// merge recursively, anything else replaces.
+pub fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Default::default());
+    }
+    if let Value::Object(map) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                map.remove(key);
+            } else {
+                merge_patch(map.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+/// `profile` with `patch` merged in. The patch must be an object and cannot
+/// change the id. Removed fields fall back to their defaults.
+pub fn apply_profile_patch(profile: &BrowserProfile, patch: &Value) -> Result<BrowserProfile> {
+    if !patch.is_object() {
+        return Err(BrowsionError::Validation(
+            "Merge patch must be a JSON object".to_string(),
+        ));
+    }
+    if let Some(id) = patch.get("id") {
+        if id.as_str() != Some(profile.id.as_str()) {
+            return Err(BrowsionError::Validation(
+                "Profile id cannot be changed".to_string(),
+            ));
+        }
+    }
+    let mut value = serde_json::to_value(profile).map_err(|e| BrowsionError::Config(e.to_string()))?;
+    merge_patch(&mut value, patch);
+    let mut patched: BrowserProfile = serde_json::from_value(value)
+        .map_err(|e| BrowsionError::Validation(format!("Invalid patch: {}", e)))?;
+    patched.id = profile.id.clone();
+    Ok(patched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_merge_patch_rfc7396() {
+        let mut target = json!({"a": "b", "c": {"d": "e", "f": "g"}, "keep": [1]});
+        merge_patch(&mut target, &json!({"a": "z", "c": {"f": null}, "n": {"x": 1}}));
+        assert_eq!(
+            target,
+            json!({"a": "z", "c": {"d": "e"}, "keep": [1], "n": {"x": 1}})
+        );
+
+        let mut target = json!({"tags": ["a", "b"]});
+        merge_patch(&mut target, &json!({"tags": ["c"]}));
+        assert_eq!(target, json!({"tags": ["c"]}));
+    }
+
+    #[test]
+    fn test_apply_profile_patch_and_etag() {
+        let profile: BrowserProfile = serde_json::from_value(json!({
+            "id": "p1",
+            "name": "One",
+            "user_data_dir": "/tmp/p1",
+            "proxy_server": "http://127.0.0.1:8080",
+            "tags": ["a"]
+        }))
+        .unwrap();
+        let etag = profile_etag(&profile);
+
+        let patched = apply_profile_patch(
+            &profile,
+            &json!({"proxy_server": null, "tags": ["a", "b"]}),
+        )
+        .unwrap();
+        assert_eq!(patched.proxy_server, None);
+        assert_eq!(patched.tags, vec!["a", "b"]);
+        assert_eq!(patched.name, "One");
+        assert_ne!(profile_etag(&patched), etag);
+
+        assert!(apply_profile_patch(&profile, &json!({"id": "p2"})).is_err());
+        assert!(apply_profile_patch(&profile, &json!(["tags"])).is_err());
+        assert!(apply_profile_patch(&profile, &json!({"tags": "oops"})).is_err());
+
+        assert!(etag_matches(&etag, &etag));
+        assert!(etag_matches("*", &etag));
+        assert!(etag_matches(&format!("\"other\", {}", etag), &etag));
+        assert!(!etag_matches(&format!("W/{}", etag), &etag));
+        assert!(!etag_matches("\"other\"", &etag));
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 /// How the app obtains the Chrome binary: CfT (default) or custom path (e.g. ungoogled).
@@ -101,6 +102,18 @@ pub struct AppConfig {
     /// Proxy presets for reuse across profiles.
     #[serde(default)]
     pub proxy_presets: Vec<ProxyPreset>,
+
+    /// Named templates for creating similar profiles.
+    #[serde(default)]
+    pub templates: Vec<ProfileTemplate>,
+
+    /// Profile folders (see `ProfileGroup`).
+    #[serde(default)]
+    pub groups: Vec<ProfileGroup>,
+
+    /// Deleted profiles, kept until restored or purged.
+    #[serde(default)]
+    pub trash: Vec<TrashEntry>,
 }
 
 impl Default for AppConfig {
@@ -114,6 +127,9 @@ impl Default for AppConfig {
             mcp: McpConfig::default(),
             api_port: None,
             proxy_presets: Vec::new(),
+            templates: Vec::new(),
+            groups: Vec::new(),
+            trash: Vec::new(),
         }
     }
 }
@@ -151,7 +167,8 @@ impl Default for McpConfig {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BrowserProfile {
-    /// Unique identifier
+    /// Unique identifier. May be omitted on create; a UUID is generated.
+    #[serde(default)]
     pub id: String,
 
     /// Display name
@@ -161,40 +178,402 @@ pub struct BrowserProfile {
     #[serde(default)]
     pub description: String,
 
-    /// User data directory
+    /// User data directory. May be omitted on create; defaults to
+    /// `default_user_data_dir(id)`.
+    #[serde(default)]
     pub user_data_dir: PathBuf,
 
     /// Proxy server (e.g., "http://192.168.0.220:8889")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub proxy_server: Option<String>,
 
-    /// Language
-    #[serde(default = "default_lang")]
+    /// Language. Empty inherits the group's, or falls back to `default_lang()`.
+    #[serde(default)]
     pub lang: String,
 
     /// Timezone (e.g., "America/Los_Angeles")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timezone: Option<String>,
 
-    /// Fingerprint ID
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub fingerprint: Option<String>,
+    /// Fingerprint settings (fingerprint-chromium). Older configs store just the
+    /// seed as a string; that form is still accepted.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "crate::config::fingerprint::deserialize_fingerprint"
+    )]
+    pub fingerprint: Option<FingerprintConfig>,
 
     /// UI color tag
     #[serde(skip_serializing_if = "Option::is_none")]
     pub color: Option<String>,
 
-    /// Custom launch arguments
+    /// Custom launch arguments. Merged with the preset switches: a repeated switch
+    /// takes the last value, feature lists are combined.
     #[serde(default)]
     pub custom_args: Vec<String>,
 
+    /// Library extensions (ids under ~/.browsion/extensions) loaded at launch.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extensions: Vec<String>,
+
+    /// Built-in switch preset and per-profile adjustments to it.
+    #[serde(default, skip_serializing_if = "LaunchFlags::is_default")]
+    pub launch_flags: LaunchFlags,
+
     /// Tags for categorization and filtering
     #[serde(default)]
     pub tags: Vec<String>,
 
+    /// Id of the `ProfileGroup` this profile belongs to. Unset proxy, timezone,
+    /// color and an empty `lang` are inherited from the group chain.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+
     /// Launch Chrome in headless mode (no visible window). Default false.
+    /// Legacy switch: superseded by `display_mode`, honoured while that is `window`.
     #[serde(default)]
     pub headless: bool,
+
+    /// Window size, position and mode.
+    #[serde(default, skip_serializing_if = "WindowSettings::is_default")]
+    pub window: WindowSettings,
+
+    /// Pages opened at startup instead of the new-tab page. In `app` window mode
+    /// the first one is opened as the app.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub startup_urls: Vec<String>,
+
+    /// Where the browser window goes: a normal window, `--headless=new`, or a
+    /// Browsion-managed virtual X display (Linux).
+    #[serde(default, skip_serializing_if = "DisplayMode::is_window")]
+    pub display_mode: DisplayMode,
+
+    /// Extra environment variables for the Chrome process (e.g. `LD_PRELOAD`, `DISPLAY`).
+    /// Applied after the built-in ones, so they win over `TZ` and the IME variables.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+
+    /// Working directory for the Chrome process. Inherits Browsion's when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<PathBuf>,
+
+    /// Start Chrome with an empty environment plus `env` (and `TZ`), instead of
+    /// inheriting Browsion's. `env` must then provide e.g. `HOME` and `DISPLAY`.
+    #[serde(default)]
+    pub clear_env: bool,
+
+    /// Snapshot exclusion rules applied after `settings.snapshot_exclude`
+    /// (globs; a leading `!` keeps what earlier rules exclude).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub snapshot_exclude: Vec<String>,
+}
+
+impl BrowserProfile {
+    /// Display mode after applying the legacy `headless` flag.
+    pub fn effective_display_mode(&self) -> DisplayMode {
+        match self.display_mode {
+            DisplayMode::Window if self.headless => DisplayMode::Headless,
+            mode => mode,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowMode {
+    #[default]
+    Normal,
+    /// `--start-maximized`
+    Maximized,
+    /// `--kiosk` (fullscreen, no browser UI)
+    Kiosk,
+    /// `--app=<first startup URL>` (own window without tabs or address bar)
+    App,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct WindowSettings {
+    /// Window width and height in pixels; both or neither.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+
+    /// Window position of the top-left corner; both or neither.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub x: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub y: Option<i32>,
+
+    #[serde(default)]
+    pub mode: WindowMode,
+
+    /// `--force-device-scale-factor`, e.g. 2.0 for a HiDPI layout.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_scale_factor: Option<f64>,
+}
+
+impl WindowSettings {
+    pub fn is_default(&self) -> bool {
+        *self == WindowSettings::default()
+    }
+}
+
+/// One-off changes for a single launch; the stored profile is not modified.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LaunchOverrides {
+    #[serde(default)]
+    pub window: Option<WindowSettings>,
+    #[serde(default)]
+    pub startup_urls: Option<Vec<String>>,
+}
+
+impl LaunchOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.window.is_none() && self.startup_urls.is_none()
+    }
+
+    /// Copy of `profile` with the overrides applied.
+    pub fn apply(&self, profile: &BrowserProfile) -> BrowserProfile {
+        let mut profile = profile.clone();
+        if let Some(window) = &self.window {
+            profile.window = window.clone();
+        }
+        if let Some(urls) = &self.startup_urls {
+            profile.startup_urls = urls.clone();
+        }
+        profile
+    }
+}
+
+/// Browser fingerprint for adryfish/fingerprint-chromium. Every field is optional;
+/// unset fields keep the values fingerprint-chromium derives from the seed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct FingerprintConfig {
+    /// `--fingerprint`: 32-bit seed all randomized values derive from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform: Option<FingerprintPlatform>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform_version: Option<String>,
+
+    /// Browser brand reported in the user agent, e.g. "Chrome", "Edge".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub brand: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub brand_version: Option<String>,
+
+    /// `navigator.hardwareConcurrency`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hardware_concurrency: Option<u32>,
+
+    /// WebGL `UNMASKED_VENDOR_WEBGL` / `UNMASKED_RENDERER_WEBGL`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gpu_vendor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gpu_renderer: Option<String>,
+
+    /// Screen size; also the default window size when `window` sets none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub screen: Option<ScreenResolution>,
+
+    /// WebRTC IP handling (`--force-webrtc-ip-handling-policy`), works on any Chromium.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webrtc_policy: Option<WebRtcPolicy>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FingerprintPlatform {
+    Windows,
+    Linux,
+    Macos,
+}
+
+impl FingerprintPlatform {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FingerprintPlatform::Windows => "windows",
+            FingerprintPlatform::Linux => "linux",
+            FingerprintPlatform::Macos => "macos",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScreenResolution {
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebRtcPolicy {
+    Default,
+    DefaultPublicAndPrivateInterfaces,
+    DefaultPublicInterfaceOnly,
+    DisableNonProxiedUdp,
+}
+
+impl WebRtcPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebRtcPolicy::Default => "default",
+            WebRtcPolicy::DefaultPublicAndPrivateInterfaces => "default_public_and_private_interfaces",
+            WebRtcPolicy::DefaultPublicInterfaceOnly => "default_public_interface_only",
+            WebRtcPolicy::DisableNonProxiedUdp => "disable_non_proxied_udp",
+        }
+    }
+}
+
+impl BrowserSource {
+    /// Whether the configured browser is adryfish/fingerprint-chromium.
+    pub fn is_fingerprint_chromium(&self) -> bool {
+        matches!(
+            self,
+            BrowserSource::Custom {
+                fingerprint_chromium: true,
+                ..
+            }
+        )
+    }
+}
+
+/// Named set of built-in Chrome switches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FlagPreset {
+    /// Quiet, automation-hiding defaults (the historical behaviour).
+    #[default]
+    Stealth,
+    /// Only skip the first-run and default-browser prompts.
+    Minimal,
+    /// Deterministic automation: no throttling, popups or background updates.
+    Testing,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct LaunchFlags {
+    #[serde(default)]
+    pub preset: FlagPreset,
+
+    /// Preset switches to leave out, by name without dashes (e.g. `disable-sync`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disabled: Vec<String>,
+
+    /// Features merged into `--enable-features`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub enable_features: Vec<String>,
+
+    /// Features merged into `--disable-features`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disable_features: Vec<String>,
+}
+
+impl LaunchFlags {
+    pub fn is_default(&self) -> bool {
+        *self == LaunchFlags::default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DisplayMode {
+    /// Headful, on the desktop Browsion runs on.
+    #[default]
+    Window,
+    /// `--headless=new`.
+    Headless,
+    /// Headful, on an Xvfb/Xephyr display spawned and owned by Browsion.
+    Virtual,
+}
+
+impl DisplayMode {
+    pub fn is_window(&self) -> bool {
+        *self == DisplayMode::Window
+    }
+}
+
+/// X server used for `DisplayMode::Virtual`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum VirtualDisplayServer {
+    #[default]
+    Xvfb,
+    /// Nested server; needs a parent `DISPLAY` but lets you watch the browser.
+    Xephyr,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VirtualDisplaySettings {
+    #[serde(default)]
+    pub server: VirtualDisplayServer,
+
+    /// Screen geometry, `WIDTHxHEIGHTxDEPTH`.
+    #[serde(default = "default_virtual_screen")]
+    pub screen: String,
+
+    /// 0 = one display per profile. N > 0 = share at most N displays between
+    /// all virtual-mode profiles.
+    #[serde(default)]
+    pub pool_size: u32,
+
+    /// First display number to try (`:99` by default).
+    #[serde(default = "default_first_display")]
+    pub first_display: u32,
+}
+
+impl Default for VirtualDisplaySettings {
+    fn default() -> Self {
+        Self {
+            server: VirtualDisplayServer::default(),
+            screen: default_virtual_screen(),
+            pool_size: 0,
+            first_display: default_first_display(),
+        }
+    }
+}
+
+/// What to do with proxy/timezone/language mismatches before a launch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum GeoCheckMode {
+    /// No check; launches never wait on the IP-info endpoint.
+    #[default]
+    Off,
+    /// Log mismatches and launch anyway.
+    Warn,
+    /// Refuse to launch when there are mismatches or the exit IP cannot be resolved.
+    Block,
+    /// Launch with timezone and language taken from the proxy's exit IP.
+    Align,
+}
+
+fn default_ip_info_url() -> String {
+    "https://ipinfo.io/json".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeoCheckSettings {
+    /// Endpoint returning JSON about the caller's IP (ipinfo.io, ip-api.com,
+    /// ipapi.co and ipwho.is response shapes are understood).
+    #[serde(default = "default_ip_info_url")]
+    pub ip_info_url: String,
+
+    #[serde(default)]
+    pub before_launch: GeoCheckMode,
+}
+
+impl Default for GeoCheckSettings {
+    fn default() -> Self {
+        Self {
+            ip_info_url: default_ip_info_url(),
+            before_launch: GeoCheckMode::default(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -206,6 +585,30 @@ pub struct AppSettings {
     /// Minimize to tray on close
     #[serde(default = "default_true")]
     pub minimize_to_tray: bool,
+
+    /// Virtual X displays for profiles with `display_mode: virtual`.
+    #[serde(default)]
+    pub virtual_display: VirtualDisplaySettings,
+
+    /// Proxy geography vs. timezone/language consistency check.
+    #[serde(default)]
+    pub geo_check: GeoCheckSettings,
+
+    /// Days a deleted profile stays in the trash before it is purged
+    /// automatically. 0 keeps it until purged by hand.
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u32,
+
+    /// What snapshots leave out: globs matched against paths in the user data
+    /// dir, see `exclude::ExcludeRules`. Defaults to keeping only what a
+    /// session needs (`DEFAULT_SNAPSHOT_EXCLUDE`).
+    #[serde(default = "default_snapshot_exclude")]
+    pub snapshot_exclude: Vec<String>,
+
+    /// Largest snapshot archive or profile bundle accepted by the HTTP API, in
+    /// MiB. Uploads are spooled to a temporary file. 0 means no limit.
+    #[serde(default = "default_max_upload_mb")]
+    pub max_upload_mb: u64,
 }
 
 impl Default for AppSettings {
@@ -213,6 +616,11 @@ impl Default for AppSettings {
         Self {
             auto_start: false,
             minimize_to_tray: true,
+            virtual_display: VirtualDisplaySettings::default(),
+            geo_check: GeoCheckSettings::default(),
+            trash_retention_days: default_trash_retention_days(),
+            snapshot_exclude: default_snapshot_exclude(),
+            max_upload_mb: default_max_upload_mb(),
         }
     }
 }
@@ -225,6 +633,19 @@ pub struct ProcessInfo {
     /// CDP remote-debugging port (if browser was launched with --remote-debugging-port).
     #[serde(default)]
     pub cdp_port: Option<u16>,
+    /// Chrome executable the process was started from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chrome_path: Option<PathBuf>,
+    /// `--user-data-dir` the process was started with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_data_dir: Option<PathBuf>,
+    /// Id from the browser's `webSocketDebuggerUrl` (`/devtools/browser/<id>`);
+    /// changes whenever a new browser instance owns the CDP port.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub browser_ws_id: Option<String>,
+    /// Managed virtual display number (`:N`) the browser runs on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<u32>,
 }
 
 /// Snapshot metadata (stored in manifest.json next to snapshot data).
@@ -232,7 +653,47 @@ pub struct ProcessInfo {
 pub struct SnapshotInfo {
     pub name: String,
     pub created_at_ts: u64, // Unix ms
+    /// Logical size: the total size of the snapshotted files.
     pub size_bytes: u64,
+    /// Bytes no other snapshot shares, i.e. what deleting this one frees.
+    /// Recomputed when snapshots are listed.
+    #[serde(default)]
+    pub unique_bytes: u64,
+    #[serde(default)]
+    pub storage: SnapshotStorage,
+    /// Chrome version that last used the profile when the snapshot was taken
+    /// (from its `Last Version` file).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chrome_version: Option<String>,
+    /// What was left out while taking the snapshot, e.g. links pointing
+    /// outside the profile.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// How a snapshot's data is kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotStorage {
+    /// A full copy of the user data dir (snapshots from older versions).
+    #[default]
+    Copy,
+    /// Chunks in the shared content-addressed blob store.
+    Chunked,
+}
+
+/// An unpacked extension in the library (stored in ~/.browsion/extensions/index.json).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtensionInfo {
+    pub id: String,
+    /// Name from manifest.json (localized via `default_locale` when needed).
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub manifest_version: u32,
+    /// File the extension was installed from.
+    pub source: PathBuf,
+    pub installed_at_ts: u64, // Unix ms
 }
 
 /// A reusable proxy server preset.
@@ -244,7 +705,112 @@ pub struct ProxyPreset {
     pub url: String,
 }
 
-fn default_lang() -> String {
+/// A named profile blueprint. String fields of `profile` may contain `{{n}}`,
+/// `{{id}}` and user-defined `{{var}}` placeholders, filled in per instance.
+/// An empty `profile.id` means "generate one"; an empty `user_data_dir` means
+/// `~/.browsion/profiles/<id>`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileTemplate {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub profile: BrowserProfile,
+}
+
+/// A folder of profiles. Groups nest through `parent`; member profiles inherit
+/// `proxy_server`, `lang`, `timezone` and `color` from the nearest group that
+/// sets them, unless the profile sets its own.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProfileGroup {
+    /// Unique identifier (generated when empty on create).
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    /// Id of the enclosing group; `None` for a top-level group.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy_server: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+}
+
+/// A deleted profile in the trash.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashEntry {
+    /// Trash entry id (the same profile id can be deleted more than once).
+    pub id: String,
+    pub profile: BrowserProfile,
+    pub deleted_at_ts: u64, // Unix ms
+    /// Where the data directory was moved, when it was moved into the trash.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_path: Option<PathBuf>,
+}
+
+/// `~/.browsion/profiles/<id>`: data directory for profiles created without one.
+pub fn default_user_data_dir(profile_id: &str) -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".browsion")
+        .join("profiles")
+        .join(profile_id)
+}
+
+fn default_trash_retention_days() -> u32 {
+    30
+}
+
+fn default_max_upload_mb() -> u64 {
+    4096
+}
+
+/// Default snapshot rules: exclude everything, then keep what a logged-in
+/// session needs (cookies, Local/Session Storage, IndexedDB, preferences,
+/// logins, extensions and their settings). Caches, history, crash dumps and lock
+/// files stay out. A kept directory needs its own rule and one for its contents.
+pub const DEFAULT_SNAPSHOT_EXCLUDE: &[&str] = &[
+    "*",
+    "!Local State",
+    "!Last Version",
+    "!Default",
+    "!Default/Preferences",
+    "!Default/Secure Preferences",
+    "!Default/Bookmarks",
+    "!Default/Cookies*",
+    "!Default/Extension Cookies*",
+    "!Default/Login Data*",
+    "!Default/Web Data*",
+    "!Default/Network",
+    "!Default/Network/**",
+    "!Default/Local Storage",
+    "!Default/Local Storage/**",
+    "!Default/Session Storage",
+    "!Default/Session Storage/**",
+    "!Default/Sessions",
+    "!Default/Sessions/**",
+    "!Default/IndexedDB",
+    "!Default/IndexedDB/**",
+    "!Default/databases",
+    "!Default/databases/**",
+    "!Default/Extensions",
+    "!Default/Extensions/**",
+    "!Default/Local Extension Settings",
+    "!Default/Local Extension Settings/**",
+    "!Default/Service Worker",
+    "!Default/Service Worker/**",
+    "Default/Service Worker/CacheStorage",
+    "Default/Service Worker/ScriptCache",
+];
+
+fn default_snapshot_exclude() -> Vec<String> {
+    DEFAULT_SNAPSHOT_EXCLUDE.iter().map(|s| s.to_string()).collect()
+}
+
+pub fn default_lang() -> String {
     "en-US".to_string()
 }
 
@@ -252,6 +818,14 @@ fn default_true() -> bool {
     true
 }
 
+fn default_virtual_screen() -> String {
+    "1920x1080x24".to_string()
+}
+
+fn default_first_display() -> u32 {
+    99
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
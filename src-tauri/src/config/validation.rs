@@ -1,6 +1,8 @@
-use crate::config::schema::BrowserProfile;
+use crate::config::locale;
+use crate::config::schema::{AppSettings, BrowserProfile, BrowserSource, FingerprintConfig, ProfileGroup};
 use crate::error::{BrowsionError, Result};
-use std::path::Path;
+use crate::process::flags;
+use std::path::{Component, Path, PathBuf};
 
 /// Validate Chrome executable path
 pub fn validate_chrome_path(path: &Path) -> Result<()> {
@@ -62,42 +64,412 @@ pub fn validate_profile(profile: &BrowserProfile) -> Result<()> {
 
     // Validate proxy server format if provided
     if let Some(proxy) = &profile.proxy_server {
-        if !proxy.starts_with("http://")
-            && !proxy.starts_with("https://")
-            && !proxy.starts_with("socks4://")
-            && !proxy.starts_with("socks5://")
-        {
+        validate_proxy_server(proxy)?;
+    }
+
+    // Validate language: BCP-47 tag or Accept-Language list with a Chrome UI locale.
+    // An empty lang is inherited from the profile's group.
+    if !profile.lang.trim().is_empty() {
+        locale::validate_lang(&profile.lang).map_err(BrowsionError::Validation)?;
+    }
+
+    // Validate timezone against the bundled IANA database
+    if let Some(tz) = &profile.timezone {
+        locale::validate_timezone(tz).map_err(BrowsionError::Validation)?;
+    }
+
+    // Validate color format if provided
+    if let Some(color) = &profile.color {
+        validate_color(color)?;
+    }
+
+    // Validate environment variables
+    for (key, value) in &profile.env {
+        if key.is_empty() || key.contains('=') || key.contains('\0') {
+            return Err(BrowsionError::Validation(format!(
+                "Invalid environment variable name: {:?}. Must be non-empty and contain no '=' or NUL",
+                key
+            )));
+        }
+        if value.contains('\0') {
+            return Err(BrowsionError::Validation(format!(
+                "Environment variable {} contains a NUL byte",
+                key
+            )));
+        }
+    }
+
+    // Validate working directory if provided (it must already exist at launch time)
+    if let Some(dir) = &profile.working_dir {
+        if !dir.is_absolute() {
+            return Err(BrowsionError::Validation(format!(
+                "Working directory {:?} must be an absolute path",
+                dir
+            )));
+        }
+        if dir.exists() && !dir.is_dir() {
+            return Err(BrowsionError::Validation(format!(
+                "Working directory {:?} is not a directory",
+                dir
+            )));
+        }
+    }
+
+    // Validate extension ids (installation is checked at launch)
+    for id in &profile.extensions {
+        if !crate::commands::extensions::is_valid_extension_id(id) {
             return Err(BrowsionError::Validation(format!(
-                "Invalid proxy server format: {}. Must start with http://, https://, socks4://, or socks5://",
-                proxy
+                "Invalid extension id: {:?}",
+                id
             )));
         }
     }
 
-    // Validate language code format (basic check)
-    if profile.lang.is_empty() {
+    crate::config::exclude::ExcludeRules::new(&profile.snapshot_exclude)
+        .map_err(BrowsionError::Validation)?;
+
+    validate_window(profile)?;
+    validate_fingerprint(profile)?;
+
+    Ok(())
+}
+
+/// Validate application settings before they are saved.
+pub fn validate_settings(settings: &AppSettings) -> Result<()> {
+    crate::config::exclude::ExcludeRules::new(&settings.snapshot_exclude)
+        .map_err(BrowsionError::Validation)?;
+    Ok(())
+}
+
+fn validate_proxy_server(proxy: &str) -> Result<()> {
+    if !proxy.starts_with("http://")
+        && !proxy.starts_with("https://")
+        && !proxy.starts_with("socks4://")
+        && !proxy.starts_with("socks5://")
+    {
+        return Err(BrowsionError::Validation(format!(
+            "Invalid proxy server format: {}. Must start with http://, https://, socks4://, or socks5://",
+            proxy
+        )));
+    }
+    Ok(())
+}
+
+fn validate_color(color: &str) -> Result<()> {
+    if !color.starts_with('#') || (color.len() != 4 && color.len() != 7) {
+        return Err(BrowsionError::Validation(format!(
+            "Invalid color format: {}. Must be hex color like #RGB or #RRGGBB",
+            color
+        )));
+    }
+    Ok(())
+}
+
+/// Validate a profile group: its own fields, that `parent` exists and that it
+/// does not make the group its own ancestor.
+pub fn validate_group(groups: &[ProfileGroup], group: &ProfileGroup) -> Result<()> {
+    if group.id.is_empty() {
+        return Err(BrowsionError::Validation("Group ID cannot be empty".to_string()));
+    }
+    if group.name.trim().is_empty() {
+        return Err(BrowsionError::Validation("Group name cannot be empty".to_string()));
+    }
+    if let Some(proxy) = &group.proxy_server {
+        validate_proxy_server(proxy)?;
+    }
+    if let Some(lang) = &group.lang {
+        locale::validate_lang(lang).map_err(BrowsionError::Validation)?;
+    }
+    if let Some(tz) = &group.timezone {
+        locale::validate_timezone(tz).map_err(BrowsionError::Validation)?;
+    }
+    if let Some(color) = &group.color {
+        validate_color(color)?;
+    }
+
+    // Walk up the parents; reaching this group again means a cycle
+    let mut parent = group.parent.clone();
+    let mut depth = 0;
+    while let Some(parent_id) = parent {
+        if parent_id == group.id || depth > groups.len() {
+            return Err(BrowsionError::Validation(format!(
+                "Group {} cannot be nested inside itself",
+                group.id
+            )));
+        }
+        let p = groups.iter().find(|g| g.id == parent_id).ok_or_else(|| {
+            BrowsionError::Validation(format!("Parent group {} not found", parent_id))
+        })?;
+        depth += 1;
+        parent = p.parent.clone();
+    }
+    Ok(())
+}
+
+/// Validate structured fingerprint values
+fn validate_fingerprint(profile: &BrowserProfile) -> Result<()> {
+    let Some(fp) = &profile.fingerprint else {
+        return Ok(());
+    };
+    if let Some(n) = fp.hardware_concurrency {
+        if !(1..=256).contains(&n) {
+            return Err(BrowsionError::Validation(format!(
+                "Invalid fingerprint hardware_concurrency: {}. Must be between 1 and 256",
+                n
+            )));
+        }
+    }
+    if let Some(screen) = fp.screen {
+        if screen.width == 0 || screen.height == 0 {
+            return Err(BrowsionError::Validation(
+                "Fingerprint screen width and height must be greater than 0".to_string(),
+            ));
+        }
+    }
+    let strings = [
+        ("platform_version", &fp.platform_version),
+        ("brand", &fp.brand),
+        ("brand_version", &fp.brand_version),
+        ("gpu_vendor", &fp.gpu_vendor),
+        ("gpu_renderer", &fp.gpu_renderer),
+    ];
+    for (field, value) in strings {
+        if value.as_ref().is_some_and(|v| v.trim().is_empty()) {
+            return Err(BrowsionError::Validation(format!(
+                "Fingerprint {} cannot be empty; omit it instead",
+                field
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Fingerprint settings that will have no effect with the configured browser.
+/// Only the WebRTC policy works on stock Chromium.
+pub fn fingerprint_warnings(profile: &BrowserProfile, source: &BrowserSource) -> Vec<String> {
+    let Some(fp) = &profile.fingerprint else {
+        return Vec::new();
+    };
+    if source.is_fingerprint_chromium() {
+        return Vec::new();
+    }
+    let ignored = FingerprintConfig {
+        webrtc_policy: None,
+        screen: None,
+        ..fp.clone()
+    };
+    if ignored == FingerprintConfig::default() {
+        return Vec::new();
+    }
+    vec![
+        "Fingerprint settings are ignored: the browser is not fingerprint-chromium \
+         (enable it under Settings > Browser)"
+            .to_string(),
+    ]
+}
+
+/// Launch flag and fingerprint warnings for `profile`, as reported when it is
+/// saved or launched.
+pub fn profile_warnings(profile: &BrowserProfile, source: &BrowserSource) -> Vec<String> {
+    let mut warnings = launch_flag_warnings(profile);
+    warnings.extend(fingerprint_warnings(profile, source));
+    warnings
+}
+
+/// URL schemes accepted in `startup_urls`.
+const STARTUP_URL_SCHEMES: [&str; 6] = ["http", "https", "file", "about", "chrome", "data"];
+
+/// Validate window geometry, window mode and startup URLs
+fn validate_window(profile: &BrowserProfile) -> Result<()> {
+    let window = &profile.window;
+    match (window.width, window.height) {
+        (None, None) => {}
+        (Some(w), Some(h)) if w > 0 && h > 0 => {}
+        _ => {
+            return Err(BrowsionError::Validation(
+                "Window width and height must both be set and greater than 0".to_string(),
+            ))
+        }
+    }
+    if window.x.is_some() != window.y.is_some() {
         return Err(BrowsionError::Validation(
-            "Language code cannot be empty".to_string(),
+            "Window x and y must be set together".to_string(),
         ));
     }
-
-    // Validate color format if provided
-    if let Some(color) = &profile.color {
-        if !color.starts_with('#') || (color.len() != 4 && color.len() != 7) {
+    if let Some(factor) = window.device_scale_factor {
+        if !(factor > 0.0 && factor <= 10.0) {
+            return Err(BrowsionError::Validation(format!(
+                "Invalid device scale factor: {}. Must be between 0 and 10",
+                factor
+            )));
+        }
+    }
+    if window.mode == crate::config::schema::WindowMode::App && profile.startup_urls.is_empty() {
+        return Err(BrowsionError::Validation(
+            "App window mode needs at least one startup URL".to_string(),
+        ));
+    }
+    for url in &profile.startup_urls {
+        let parsed = reqwest::Url::parse(url)
+            .map_err(|e| BrowsionError::Validation(format!("Invalid startup URL {}: {}", url, e)))?;
+        if !STARTUP_URL_SCHEMES.contains(&parsed.scheme()) {
             return Err(BrowsionError::Validation(format!(
-                "Invalid color format: {}. Must be hex color like #RGB or #RRGGBB",
-                color
+                "Unsupported startup URL scheme in {}. Must be one of: {}",
+                url,
+                STARTUP_URL_SCHEMES.join(", ")
             )));
         }
     }
+    Ok(())
+}
+
+/// Switches Browsion sets from profile fields, with the field that controls them.
+const PROFILE_SWITCHES: [(&str, &str); 6] = [
+    ("proxy-server", "proxy_server"),
+    ("lang", "lang"),
+    ("accept-lang", "lang"),
+    ("timezone", "timezone"),
+    ("fingerprint", "fingerprint"),
+    ("headless", "display_mode"),
+];
+
+/// Non-fatal problems with a profile's launch switches: duplicated or conflicting
+/// switches, custom args that shadow profile fields or managed switches, features
+/// both enabled and disabled, and unknown entries in `launch_flags.disabled`.
+pub fn launch_flag_warnings(profile: &BrowserProfile) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let custom = &profile.custom_args;
+    let preset = flags::effective_preset(&profile.launch_flags);
+
+    let mut seen: Vec<&str> = Vec::new();
+    for arg in custom {
+        let Some(name) = flags::switch_name(arg) else {
+            continue;
+        };
+        if flags::MANAGED_SWITCHES.contains(&name) {
+            warnings.push(format!("--{} is managed by Browsion; custom value is ignored", name));
+        } else if let Some((_, field)) = PROFILE_SWITCHES.iter().find(|(s, _)| *s == name) {
+            warnings.push(format!("--{} overrides the profile's {} setting", name, field));
+        } else if !flags::LIST_SWITCHES.contains(&name)
+            && preset.iter().any(|p| flags::switch_name(p) == Some(name))
+        {
+            warnings.push(format!("--{} is already set by the {:?} preset", name, profile.launch_flags.preset));
+        }
+        if flags::LIST_SWITCHES.contains(&name) {
+            continue;
+        }
+        if seen.contains(&name) {
+            warnings.push(format!("--{} is given more than once; the last value wins", name));
+        } else {
+            seen.push(name);
+        }
+    }
+
+    let mut present: Vec<&str> = preset
+        .iter()
+        .chain(custom)
+        .filter_map(|a| flags::switch_name(a))
+        .collect();
+    if profile.proxy_server.is_some() {
+        present.push("proxy-server");
+    }
+    if profile.effective_display_mode() == crate::config::schema::DisplayMode::Headless {
+        present.push("headless");
+    }
+    for (a, b) in flags::CONFLICTING_SWITCHES {
+        if present.contains(&a) && present.contains(&b) {
+            warnings.push(format!("--{} conflicts with --{}", a, b));
+        }
+    }
+
+    let all: Vec<String> = preset.iter().chain(custom).cloned().collect();
+    let mut enabled = flags::list_values(&all, "enable-features");
+    enabled.extend(profile.launch_flags.enable_features.iter().cloned());
+    let mut disabled = flags::list_values(&all, "disable-features");
+    disabled.extend(profile.launch_flags.disable_features.iter().cloned());
+    for feature in &enabled {
+        if disabled.contains(feature) {
+            warnings.push(format!(
+                "Feature {} is both enabled and disabled; it stays disabled",
+                feature
+            ));
+        }
+    }
+
+    let preset_names: Vec<&str> = flags::preset_switches(profile.launch_flags.preset)
+        .iter()
+        .filter_map(|a| flags::switch_name(a))
+        .collect();
+    for name in &profile.launch_flags.disabled {
+        if !preset_names.contains(&name.as_str()) {
+            warnings.push(format!(
+                "Disabled switch {} is not part of the {:?} preset",
+                name, profile.launch_flags.preset
+            ));
+        }
+    }
+
+    warnings.dedup();
+    warnings
+}
+
+/// Lexically normalize a path: drop `.` components and resolve `..` against the
+/// preceding component. Does not touch the filesystem.
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !out.pop() {
+                    out.push("..");
+                }
+            }
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
+/// True when `a` and `b` are the same directory or one contains the other.
+pub fn paths_overlap(a: &Path, b: &Path) -> bool {
+    let (a, b) = (normalize_path(a), normalize_path(b));
+    a.starts_with(&b) || b.starts_with(&a)
+}
 
+/// Reject `profile` if its id is taken or its user data dir overlaps another
+/// profile's. `existing_id` is the id being updated, which is skipped.
+pub fn check_profile_conflicts(
+    profiles: &[BrowserProfile],
+    profile: &BrowserProfile,
+    existing_id: Option<&str>,
+) -> Result<()> {
+    for other in profiles {
+        if Some(other.id.as_str()) == existing_id {
+            continue;
+        }
+        if other.id == profile.id {
+            return Err(BrowsionError::Conflict(format!(
+                "Profile ID already exists: {}",
+                profile.id
+            )));
+        }
+        if paths_overlap(&other.user_data_dir, &profile.user_data_dir) {
+            return Err(BrowsionError::Conflict(format!(
+                "User data directory {} overlaps with profile {} ({})",
+                profile.user_data_dir.display(),
+                other.id,
+                other.user_data_dir.display()
+            )));
+        }
+    }
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::schema::BrowserProfile;
+    use crate::config::schema::{BrowserProfile, BrowserSource, FingerprintConfig};
     use std::path::PathBuf;
 
     #[test]
@@ -110,11 +482,24 @@ mod tests {
             proxy_server: Some("http://localhost:8080".to_string()),
             lang: "en-US".to_string(),
             timezone: Some("America/New_York".to_string()),
-            fingerprint: Some("12345".to_string()),
+            fingerprint: Some(FingerprintConfig {
+                seed: Some(12345),
+                ..Default::default()
+            }),
             color: Some("#FF5733".to_string()),
             custom_args: vec![],
+            extensions: vec![],
+            launch_flags: Default::default(),
             tags: vec![],
+            group: None,
             headless: false,
+            window: Default::default(),
+            startup_urls: vec![],
+            display_mode: Default::default(),
+            env: Default::default(),
+            working_dir: None,
+            clear_env: false,
+            snapshot_exclude: vec![],
         };
 
         assert!(validate_profile(&profile).is_ok());
@@ -133,8 +518,18 @@ mod tests {
             fingerprint: None,
             color: None,
             custom_args: vec![],
+            extensions: vec![],
+            launch_flags: Default::default(),
             tags: vec![],
+            group: None,
             headless: false,
+            window: Default::default(),
+            startup_urls: vec![],
+            display_mode: Default::default(),
+            env: Default::default(),
+            working_dir: None,
+            clear_env: false,
+            snapshot_exclude: vec![],
         };
 
         assert!(validate_profile(&profile).is_err());
@@ -153,8 +548,18 @@ mod tests {
             fingerprint: None,
             color: None,
             custom_args: vec![],
+            extensions: vec![],
+            launch_flags: Default::default(),
             tags: vec![],
+            group: None,
             headless: false,
+            window: Default::default(),
+            startup_urls: vec![],
+            display_mode: Default::default(),
+            env: Default::default(),
+            working_dir: None,
+            clear_env: false,
+            snapshot_exclude: vec![],
         };
 
         assert!(validate_profile(&profile).is_err());
@@ -173,10 +578,287 @@ mod tests {
             fingerprint: None,
             color: Some("red".to_string()),
             custom_args: vec![],
+            extensions: vec![],
+            launch_flags: Default::default(),
+            tags: vec![],
+            group: None,
+            headless: false,
+            window: Default::default(),
+            startup_urls: vec![],
+            display_mode: Default::default(),
+            env: Default::default(),
+            working_dir: None,
+            clear_env: false,
+            snapshot_exclude: vec![],
+        };
+
+        assert!(validate_profile(&profile).is_err());
+    }
+
+    #[test]
+    fn test_validate_env_and_working_dir() {
+        let mut profile = BrowserProfile {
+            id: "test".to_string(),
+            name: "Test".to_string(),
+            description: "".to_string(),
+            user_data_dir: PathBuf::from("/tmp/test"),
+            proxy_server: None,
+            lang: "en-US".to_string(),
+            timezone: None,
+            fingerprint: None,
+            color: None,
+            custom_args: vec![],
+            extensions: vec![],
+            launch_flags: Default::default(),
             tags: vec![],
+            group: None,
             headless: false,
+            window: Default::default(),
+            startup_urls: vec![],
+            display_mode: Default::default(),
+            env: [("LD_PRELOAD".to_string(), "/opt/hook.so".to_string())].into(),
+            working_dir: Some(std::env::temp_dir()),
+            clear_env: false,
+            snapshot_exclude: vec![],
         };
+        assert!(validate_profile(&profile).is_ok());
+
+        profile.env.insert("BAD=KEY".to_string(), "x".to_string());
+        assert!(validate_profile(&profile).is_err());
+        profile.env.remove("BAD=KEY");
 
+        profile.working_dir = Some(PathBuf::from("relative/dir"));
         assert!(validate_profile(&profile).is_err());
     }
+
+    #[test]
+    fn test_launch_flag_warnings() {
+        let mut profile = BrowserProfile {
+            id: "test".to_string(),
+            name: "Test".to_string(),
+            description: "".to_string(),
+            user_data_dir: PathBuf::from("/tmp/test"),
+            proxy_server: Some("http://localhost:8080".to_string()),
+            lang: "en-US".to_string(),
+            timezone: None,
+            fingerprint: None,
+            color: None,
+            custom_args: vec!["--disable-gpu".to_string()],
+            extensions: vec![],
+            launch_flags: Default::default(),
+            tags: vec![],
+            group: None,
+            headless: false,
+            window: Default::default(),
+            startup_urls: vec![],
+            display_mode: Default::default(),
+            env: Default::default(),
+            working_dir: None,
+            clear_env: false,
+            snapshot_exclude: vec![],
+        };
+        assert!(launch_flag_warnings(&profile).is_empty());
+
+        profile.custom_args = vec![
+            "--no-proxy-server".to_string(),
+            "--remote-debugging-port=1".to_string(),
+            "--window-size=800,600".to_string(),
+            "--window-size=1024,768".to_string(),
+            "--enable-features=Translate".to_string(),
+        ];
+        profile.launch_flags.disable_features = vec!["Translate".to_string()];
+        profile.launch_flags.disabled = vec!["no-such-switch".to_string()];
+        let warnings = launch_flag_warnings(&profile);
+        assert!(warnings.iter().any(|w| w.contains("--proxy-server conflicts with --no-proxy-server")));
+        assert!(warnings.iter().any(|w| w.contains("managed by Browsion")));
+        assert!(warnings.iter().any(|w| w.contains("--window-size is given more than once")));
+        assert!(warnings.iter().any(|w| w.contains("Translate is both enabled and disabled")));
+        assert!(warnings.iter().any(|w| w.contains("no-such-switch")));
+    }
+
+    #[test]
+    fn test_validate_window_and_startup_urls() {
+        let mut profile = BrowserProfile {
+            id: "test".to_string(),
+            name: "Test".to_string(),
+            description: "".to_string(),
+            user_data_dir: PathBuf::from("/tmp/test"),
+            proxy_server: None,
+            lang: "en-US".to_string(),
+            timezone: None,
+            fingerprint: None,
+            color: None,
+            custom_args: vec![],
+            extensions: vec![],
+            launch_flags: Default::default(),
+            tags: vec![],
+            group: None,
+            headless: false,
+            window: Default::default(),
+            startup_urls: vec!["https://example.com".to_string()],
+            display_mode: Default::default(),
+            env: Default::default(),
+            working_dir: None,
+            clear_env: false,
+            snapshot_exclude: vec![],
+        };
+        profile.window.mode = crate::config::schema::WindowMode::App;
+        assert!(validate_profile(&profile).is_ok());
+
+        profile.window.width = Some(800);
+        assert!(validate_profile(&profile).is_err());
+        profile.window.height = Some(600);
+        assert!(validate_profile(&profile).is_ok());
+
+        profile.startup_urls = vec!["javascript:alert(1)".to_string()];
+        assert!(validate_profile(&profile).is_err());
+
+        profile.startup_urls.clear();
+        assert!(validate_profile(&profile).is_err());
+    }
+
+    #[test]
+    fn test_validate_fingerprint() {
+        let mut profile = BrowserProfile {
+            id: "test".to_string(),
+            name: "Test".to_string(),
+            description: "".to_string(),
+            user_data_dir: PathBuf::from("/tmp/test"),
+            proxy_server: None,
+            lang: "en-US".to_string(),
+            timezone: None,
+            fingerprint: Some(FingerprintConfig {
+                seed: Some(1),
+                hardware_concurrency: Some(8),
+                ..Default::default()
+            }),
+            color: None,
+            custom_args: vec![],
+            extensions: vec![],
+            launch_flags: Default::default(),
+            tags: vec![],
+            group: None,
+            headless: false,
+            window: Default::default(),
+            startup_urls: vec![],
+            display_mode: Default::default(),
+            env: Default::default(),
+            working_dir: None,
+            clear_env: false,
+            snapshot_exclude: vec![],
+        };
+        assert!(validate_profile(&profile).is_ok());
+
+        let stock = BrowserSource::default();
+        let fingerprint_chromium = BrowserSource::Custom {
+            path: PathBuf::from("/opt/fingerprint-chromium/chrome"),
+            fingerprint_chromium: true,
+        };
+        assert_eq!(fingerprint_warnings(&profile, &stock).len(), 1);
+        assert!(fingerprint_warnings(&profile, &fingerprint_chromium).is_empty());
+
+        let fp = profile.fingerprint.as_mut().unwrap();
+        fp.hardware_concurrency = Some(0);
+        assert!(validate_profile(&profile).is_err());
+
+        let fp = profile.fingerprint.as_mut().unwrap();
+        fp.hardware_concurrency = None;
+        fp.gpu_renderer = Some("  ".to_string());
+        assert!(validate_profile(&profile).is_err());
+
+        // WebRTC policy alone works on any Chromium
+        profile.fingerprint = Some(FingerprintConfig {
+            webrtc_policy: Some(crate::config::schema::WebRtcPolicy::DisableNonProxiedUdp),
+            ..Default::default()
+        });
+        assert!(validate_profile(&profile).is_ok());
+        assert!(fingerprint_warnings(&profile, &stock).is_empty());
+    }
+
+    #[test]
+    fn test_validate_timezone_and_lang() {
+        let mut profile = BrowserProfile {
+            id: "test".to_string(),
+            name: "Test".to_string(),
+            description: "".to_string(),
+            user_data_dir: PathBuf::from("/tmp/test"),
+            proxy_server: None,
+            lang: "de-DE,de;q=0.9,en;q=0.5".to_string(),
+            timezone: Some("Europe/Berlin".to_string()),
+            fingerprint: None,
+            color: None,
+            custom_args: vec![],
+            extensions: vec![],
+            launch_flags: Default::default(),
+            tags: vec![],
+            group: None,
+            headless: false,
+            window: Default::default(),
+            startup_urls: vec![],
+            display_mode: Default::default(),
+            env: Default::default(),
+            working_dir: None,
+            clear_env: false,
+            snapshot_exclude: vec![],
+        };
+        assert!(validate_profile(&profile).is_ok());
+
+        profile.timezone = Some("America/Los Angeles".to_string());
+        let err = validate_profile(&profile).unwrap_err().to_string();
+        assert!(err.contains("timezone"));
+        assert!(err.contains("America/Los_Angeles"));
+
+        profile.timezone = None;
+        profile.lang = "en_US;q=high".to_string();
+        assert!(validate_profile(&profile).unwrap_err().to_string().contains("lang"));
+    }
+
+    #[test]
+    fn test_check_profile_conflicts() {
+        let existing = BrowserProfile {
+            id: "a".to_string(),
+            name: "A".to_string(),
+            description: "".to_string(),
+            user_data_dir: PathBuf::from("/data/profiles/a"),
+            proxy_server: None,
+            lang: "en-US".to_string(),
+            timezone: None,
+            fingerprint: None,
+            color: None,
+            custom_args: vec![],
+            extensions: vec![],
+            launch_flags: Default::default(),
+            tags: vec![],
+            group: None,
+            headless: false,
+            window: Default::default(),
+            startup_urls: vec![],
+            display_mode: Default::default(),
+            env: Default::default(),
+            working_dir: None,
+            clear_env: false,
+            snapshot_exclude: vec![],
+        };
+        let profiles = vec![existing.clone()];
+
+        let mut new = existing.clone();
+        new.id = "b".to_string();
+        new.user_data_dir = PathBuf::from("/data/profiles/b");
+        assert!(check_profile_conflicts(&profiles, &new, None).is_ok());
+
+        new.user_data_dir = PathBuf::from("/data/profiles/b/../a/Default");
+        assert!(matches!(
+            check_profile_conflicts(&profiles, &new, None),
+            Err(BrowsionError::Conflict(_))
+        ));
+        new.user_data_dir = PathBuf::from("/data");
+        assert!(check_profile_conflicts(&profiles, &new, None).is_err());
+        new.user_data_dir = PathBuf::from("/data/profiles/ab");
+        assert!(check_profile_conflicts(&profiles, &new, None).is_ok());
+
+        new.id = "a".to_string();
+        assert!(check_profile_conflicts(&profiles, &new, None).is_err());
+        // Updating profile "a" itself is not a conflict.
+        assert!(check_profile_conflicts(&profiles, &existing, Some("a")).is_ok());
+    }
 }
@@ -26,6 +26,12 @@ pub enum BrowsionError {
     #[error("Validation error: {0}")]
     Validation(String),
 
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Profile locked: {0}")]
+    ProfileLocked(String),
+
     #[error("Browser not running: {0}")]
     BrowserNotRunning(String),
 
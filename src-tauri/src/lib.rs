@@ -53,40 +53,17 @@ pub fn run() {
                 *guard = Some(Box::new(move || handle.abort()));
             }
 
-            // Session reconnect: probe previously-running browsers from saved sessions
+            // Session reconnect: re-adopt previously-running browsers after verifying
+            // that each saved pid is still the same browser instance
             {
                 let state_rc = std::sync::Arc::clone(&state);
                 tauri::async_runtime::spawn(async move {
-                    match crate::process::sessions_persist::load_sessions().await {
-                        Ok(sessions) => {
-                            for (profile_id, entry) in sessions {
-                                // Probe CDP port to check if browser is still alive
-                                let url = format!("http://127.0.0.1:{}/json/version", entry.cdp_port);
-                                match reqwest::get(&url).await {
-                                    Ok(r) if r.status().is_success() => {
-                                        tracing::info!(
-                                            "Reconnected session: profile={} pid={} cdp_port={}",
-                                            profile_id,
-                                            entry.pid,
-                                            entry.cdp_port
-                                        );
-                                        state_rc.process_manager.register_external(
-                                            &profile_id,
-                                            entry.pid,
-                                            entry.cdp_port,
-                                        );
-                                    }
-                                    _ => {
-                                        tracing::info!(
-                                            "Session dead on restart, removing: profile={}",
-                                            profile_id
-                                        );
-                                        let _ = crate::process::sessions_persist::remove_session(
-                                            &profile_id,
-                                        )
-                                        .await;
-                                    }
-                                }
+                    match crate::process::sessions_persist::restore_sessions(&state_rc.process_manager)
+                        .await
+                    {
+                        Ok(restored) => {
+                            if !restored.is_empty() {
+                                tracing::info!("Reconnected sessions: {:?}", restored);
                             }
                             state_rc.emit("browser-status-changed");
                         }
@@ -101,7 +78,7 @@ pub fn run() {
             // Setup system tray
             tray::setup_tray(app.handle())?;
 
-            // Background task: cleanup dead processes every 30s
+            // Background maintenance every 30s: dead processes, expired trash
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
                 let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(30));
@@ -116,6 +93,14 @@ pub fn run() {
                             Ok(_) => {}
                             Err(e) => tracing::warn!("Dead process cleanup failed: {}", e),
                         }
+                        match commands::trash::core_purge_expired(&state).await {
+                            Ok(purged) if !purged.is_empty() => {
+                                let ids: Vec<&str> = purged.iter().map(|e| e.profile.id.as_str()).collect();
+                                tracing::info!("Purged expired trash entries: {:?}", ids);
+                            }
+                            Ok(_) => {}
+                            Err(e) => tracing::warn!("Trash purge failed: {}", e),
+                        }
                     }
                 }
             });
@@ -144,6 +129,12 @@ pub fn run() {
             commands::add_profile,
             commands::update_profile,
             commands::delete_profile,
+            commands::trash::list_trash,
+            commands::trash::restore_profile,
+            commands::trash::purge_trash,
+            commands::storage::get_storage_report,
+            commands::storage::cleanup_storage,
+            commands::clean::clean_profile,
             commands::update_chrome_path,
             commands::get_browser_source,
             commands::update_browser_source,
@@ -161,10 +152,35 @@ pub fn run() {
             commands::proxy::update_proxy_preset,
             commands::proxy::delete_proxy_preset,
             commands::proxy::test_proxy,
+            commands::geo::check_profile_geo,
+            commands::templates::list_templates,
+            commands::templates::get_template,
+            commands::templates::save_template,
+            commands::templates::delete_template,
+            commands::templates::create_profiles_from_template,
+            commands::templates::clone_profile,
+            commands::groups::list_groups,
+            commands::groups::save_group,
+            commands::groups::delete_group,
+            commands::groups::move_profile_to_group,
+            commands::bundles::export_profile,
+            commands::bundles::import_profile,
+            commands::bulk_import::bulk_import_profiles,
             commands::snapshots::list_snapshots,
             commands::snapshots::create_snapshot,
             commands::snapshots::restore_snapshot,
             commands::snapshots::delete_snapshot,
+            commands::snapshots::export_snapshot,
+            commands::snapshots::import_snapshot,
+            commands::bulk::bulk_launch,
+            commands::bulk::bulk_kill,
+            commands::bulk::bulk_snapshot,
+            commands::bulk::bulk_restore,
+            commands::discovery::discover_browsers,
+            commands::discovery::attach_profile,
+            commands::extensions::list_extensions,
+            commands::extensions::install_extension,
+            commands::extensions::remove_extension,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
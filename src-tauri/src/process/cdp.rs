@@ -0,0 +1,130 @@
+//! Minimal Chrome DevTools Protocol client over the browser websocket.
+//!
+//! Enough for one-off maintenance commands: connect to the browser endpoint,
+//! send commands (optionally on a flattened target session) and collect the
+//! events that arrive meanwhile.
+
+use crate::error::{BrowsionError, Result};
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(15);
+const VERSION_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// The `/json/version` document of the Chrome listening on `cdp_port`.
+pub async fn fetch_version(cdp_port: u16) -> Result<Value> {
+    let url = format!("http://127.0.0.1:{}/json/version", cdp_port);
+    reqwest::Client::builder()
+        .timeout(VERSION_TIMEOUT)
+        .build()
+        .map_err(|e| BrowsionError::CdpConnection(e.to_string()))?
+        .get(&url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| BrowsionError::CdpConnection(e.to_string()))?
+        .json()
+        .await
+        .map_err(|e| BrowsionError::CdpConnection(e.to_string()))
+}
+
+pub struct CdpClient {
+    ws: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    next_id: u64,
+    events: Vec<Value>,
+}
+
+impl CdpClient {
+    /// Connect to the browser-level endpoint of the Chrome listening on `cdp_port`.
+    pub async fn connect(cdp_port: u16) -> Result<Self> {
+        let version = fetch_version(cdp_port).await?;
+        let ws_url = version
+            .get("webSocketDebuggerUrl")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                BrowsionError::CdpConnection(format!("No webSocketDebuggerUrl on CDP port {}", cdp_port))
+            })?;
+        let (ws, _) = tokio_tungstenite::connect_async(ws_url)
+            .await
+            .map_err(|e| BrowsionError::CdpConnection(e.to_string()))?;
+        Ok(Self {
+            ws,
+            next_id: 1,
+            events: Vec::new(),
+        })
+    }
+
+    /// Send `method` and wait for its result. Events received in the meantime
+    /// are kept for `take_events`.
+    pub async fn call(&mut self, method: &str, params: Value, session_id: Option<&str>) -> Result<Value> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let mut message = json!({ "id": id, "method": method, "params": params });
+        if let Some(session_id) = session_id {
+            message["sessionId"] = json!(session_id);
+        }
+        self.ws
+            .send(Message::Text(message.to_string()))
+            .await
+            .map_err(|e| BrowsionError::CdpConnection(e.to_string()))?;
+
+        let response = tokio::time::timeout(COMMAND_TIMEOUT, async {
+            loop {
+                match self.next_message().await? {
+                    msg if msg.get("id").and_then(|v| v.as_u64()) == Some(id) => return Ok::<Value, BrowsionError>(msg),
+                    msg if msg.get("method").is_some() => self.events.push(msg),
+                    _ => {}
+                }
+            }
+        })
+        .await
+        .map_err(|_| BrowsionError::Timeout(format!("CDP {} timed out", method)))??;
+
+        if let Some(error) = response.get("error") {
+            let text = error.get("message").and_then(|m| m.as_str()).unwrap_or("unknown error");
+            return Err(BrowsionError::CdpCommand(format!("{}: {}", method, text)));
+        }
+        Ok(response.get("result").cloned().unwrap_or(Value::Null))
+    }
+
+    /// Keep reading events for `duration`.
+    pub async fn collect_events(&mut self, duration: Duration) -> Result<()> {
+        let deadline = tokio::time::Instant::now() + duration;
+        while let Ok(msg) = tokio::time::timeout_at(deadline, self.next_message()).await {
+            let msg = msg?;
+            if msg.get("method").is_some() {
+                self.events.push(msg);
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove and return the `params` of the buffered events named `method`.
+    pub fn take_events(&mut self, method: &str) -> Vec<Value> {
+        let (taken, kept): (Vec<Value>, Vec<Value>) = std::mem::take(&mut self.events)
+            .into_iter()
+            .partition(|e| e.get("method").and_then(|m| m.as_str()) == Some(method));
+        self.events = kept;
+        taken
+            .into_iter()
+            .map(|mut e| e.get_mut("params").map(Value::take).unwrap_or(Value::Null))
+            .collect()
+    }
+
+    async fn next_message(&mut self) -> Result<Value> {
+        loop {
+            let msg = self
+                .ws
+                .next()
+                .await
+                .ok_or_else(|| BrowsionError::CdpConnection("Connection closed".to_string()))?
+                .map_err(|e| BrowsionError::CdpConnection(e.to_string()))?;
+            if let Message::Text(text) = msg {
+                return serde_json::from_str(&text).map_err(|e| BrowsionError::CdpConnection(e.to_string()));
+            }
+        }
+    }
+}
@@ -0,0 +1,175 @@
+//! Discover Chrome instances that were started outside Browsion.
+//!
+//! With remote debugging enabled, Chrome writes `DevToolsActivePort` into its user
+//! data dir: the first line is the port, the second the browser websocket path.
+//! The owning process is found by matching `--user-data-dir=` on command lines.
+
+use serde::Serialize;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use sysinfo::{Process, System};
+
+/// Contents of a `DevToolsActivePort` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DevToolsActivePort {
+    pub port: u16,
+    /// e.g. `/devtools/browser/1b2c...`
+    pub ws_path: Option<String>,
+}
+
+/// A Chrome browser process with remote debugging enabled.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiscoveredBrowser {
+    pub pid: u32,
+    pub cdp_port: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_data_dir: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ws_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exe: Option<PathBuf>,
+    /// Browsion profile whose `user_data_dir` this browser uses, if any.
+    pub profile_id: Option<String>,
+    /// Whether Browsion already tracks this browser.
+    pub managed: bool,
+}
+
+pub fn parse_devtools_active_port(text: &str) -> Option<DevToolsActivePort> {
+    let mut lines = text.lines();
+    let port: u16 = lines.next()?.trim().parse().ok()?;
+    if port == 0 {
+        return None;
+    }
+    let ws_path = lines
+        .next()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(str::to_string);
+    Some(DevToolsActivePort { port, ws_path })
+}
+
+/// Read `<user_data_dir>/DevToolsActivePort`. Returns None if missing or malformed.
+pub fn read_devtools_active_port(user_data_dir: &Path) -> Option<DevToolsActivePort> {
+    let text = std::fs::read_to_string(user_data_dir.join("DevToolsActivePort")).ok()?;
+    parse_devtools_active_port(&text)
+}
+
+/// Value of a `--switch=value` argument, if present.
+pub fn switch_value(cmd: &[OsString], switch: &str) -> Option<String> {
+    let prefix = format!("{}=", switch);
+    cmd.iter().find_map(|arg| {
+        arg.to_str()
+            .and_then(|a| a.strip_prefix(&prefix))
+            .map(|v| v.trim_matches('"').to_string())
+    })
+}
+
+/// Chrome's main browser process: a chrome/chromium binary without `--type=`
+/// (renderer, GPU and utility children all carry one).
+fn is_browser_process(process: &Process) -> bool {
+    let name = process.name().to_string_lossy().to_lowercase();
+    if !(name.contains("chrome") || name.contains("chromium")) {
+        return false;
+    }
+    if process.status() == sysinfo::ProcessStatus::Zombie {
+        return false;
+    }
+    !process
+        .cmd()
+        .iter()
+        .any(|a| a.to_string_lossy().starts_with("--type="))
+}
+
+/// Compare two directories, resolving symlinks and relative parts when possible.
+pub fn same_dir(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Find the pid of the browser process using `user_data_dir`.
+/// `system` must have been refreshed with command lines.
+pub fn find_browser_pid(system: &System, user_data_dir: &Path) -> Option<u32> {
+    system
+        .processes()
+        .values()
+        .filter(|p| is_browser_process(p))
+        .find(|p| {
+            switch_value(p.cmd(), "--user-data-dir")
+                .map(|dir| same_dir(Path::new(&dir), user_data_dir))
+                .unwrap_or(false)
+        })
+        .map(|p| p.pid().as_u32())
+}
+
+/// List every browser process that exposes a debugging port, either on its
+/// command line or through `DevToolsActivePort` (covers `--remote-debugging-port=0`).
+/// `profile_id` and `managed` are left for the caller to fill in.
+pub fn discover_browsers(system: &System) -> Vec<DiscoveredBrowser> {
+    let mut found: Vec<DiscoveredBrowser> = system
+        .processes()
+        .values()
+        .filter(|p| is_browser_process(p))
+        .filter_map(|p| {
+            let user_data_dir = switch_value(p.cmd(), "--user-data-dir").map(PathBuf::from);
+            let active_port = user_data_dir.as_deref().and_then(read_devtools_active_port);
+            let cmd_port = switch_value(p.cmd(), "--remote-debugging-port")
+                .and_then(|v| v.parse::<u16>().ok())
+                .filter(|port| *port > 0);
+            let cdp_port = cmd_port.or(active_port.as_ref().map(|a| a.port))?;
+            let ws_path = active_port
+                .filter(|a| a.port == cdp_port)
+                .and_then(|a| a.ws_path);
+            Some(DiscoveredBrowser {
+                pid: p.pid().as_u32(),
+                cdp_port,
+                user_data_dir,
+                ws_path,
+                exe: p.exe().map(Path::to_path_buf),
+                profile_id: None,
+                managed: false,
+            })
+        })
+        .collect();
+    found.sort_by_key(|b| b.pid);
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_devtools_active_port() {
+        let parsed = parse_devtools_active_port("9333\n/devtools/browser/abc-123\n").unwrap();
+        assert_eq!(parsed.port, 9333);
+        assert_eq!(parsed.ws_path.as_deref(), Some("/devtools/browser/abc-123"));
+
+        let port_only = parse_devtools_active_port("9444").unwrap();
+        assert_eq!(port_only.port, 9444);
+        assert!(port_only.ws_path.is_none());
+    }
+
+    #[test]
+    fn test_parse_devtools_active_port_invalid() {
+        assert!(parse_devtools_active_port("").is_none());
+        assert!(parse_devtools_active_port("not-a-port\n/devtools").is_none());
+        assert!(parse_devtools_active_port("0\n/devtools/browser/x").is_none());
+    }
+
+    #[test]
+    fn test_switch_value() {
+        let cmd: Vec<OsString> = vec![
+            "/usr/bin/chrome".into(),
+            "--user-data-dir=/home/me/.browsion/profiles/a".into(),
+            "--remote-debugging-port=9222".into(),
+        ];
+        assert_eq!(
+            switch_value(&cmd, "--user-data-dir").as_deref(),
+            Some("/home/me/.browsion/profiles/a")
+        );
+        assert_eq!(switch_value(&cmd, "--remote-debugging-port").as_deref(), Some("9222"));
+        assert!(switch_value(&cmd, "--proxy-server").is_none());
+    }
+}
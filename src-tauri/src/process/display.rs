@@ -0,0 +1,302 @@
+//! Virtual X displays (Xvfb / Xephyr) for headful browsers on machines without a desktop.
+//!
+//! Each display is an X server child process owned by Browsion. Profiles acquire a
+//! display when they launch and release it when killed or found dead; the server is
+//! stopped once no profile uses it. With `pool_size > 0` profiles share up to that
+//! many displays (least-loaded first) instead of getting one each.
+
+use crate::config::schema::{VirtualDisplayServer, VirtualDisplaySettings};
+use crate::error::{BrowsionError, Result};
+use crate::process::manager::ProfileReservation;
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+
+/// How long a freshly spawned server gets to create its socket.
+const READY_TIMEOUT: Duration = Duration::from_secs(5);
+/// How many display numbers above `first_display` to probe for a free one.
+const MAX_PROBE: u32 = 100;
+
+/// X11 socket of display `:n`.
+pub fn x_socket_path(display: u32) -> PathBuf {
+    PathBuf::from(format!("/tmp/.X11-unix/X{}", display))
+}
+
+/// Lock file an X server writes for display `:n`.
+pub fn x_lock_path(display: u32) -> PathBuf {
+    PathBuf::from(format!("/tmp/.X{}-lock", display))
+}
+
+/// Whether some X server (ours or not) already claims display `:n`.
+fn display_taken(display: u32) -> bool {
+    x_lock_path(display).exists() || x_socket_path(display).exists()
+}
+
+/// Program and arguments that start `server` on display `:n`.
+pub fn server_command(server: VirtualDisplayServer, display: u32, screen: &str) -> (&'static str, Vec<String>) {
+    let display_arg = format!(":{}", display);
+    match server {
+        VirtualDisplayServer::Xvfb => (
+            "Xvfb",
+            vec![
+                display_arg,
+                "-screen".to_string(),
+                "0".to_string(),
+                screen.to_string(),
+                "-nolisten".to_string(),
+                "tcp".to_string(),
+            ],
+        ),
+        VirtualDisplayServer::Xephyr => (
+            "Xephyr",
+            vec![
+                display_arg,
+                "-screen".to_string(),
+                screen.to_string(),
+                "-nolisten".to_string(),
+                "tcp".to_string(),
+            ],
+        ),
+    }
+}
+
+/// Check a `WIDTHxHEIGHT[xDEPTH]` geometry string.
+pub fn validate_screen(screen: &str) -> bool {
+    let parts: Vec<&str> = screen.split('x').collect();
+    (parts.len() == 2 || parts.len() == 3)
+        && parts.iter().all(|p| p.parse::<u32>().is_ok_and(|n| n > 0))
+}
+
+/// How long a server gets to exit after SIGTERM before it is killed.
+const STOP_TIMEOUT: Duration = Duration::from_secs(1);
+
+struct ManagedDisplay {
+    /// `None` once `shutdown` has taken the server.
+    child: Option<Child>,
+    users: usize,
+}
+
+impl ManagedDisplay {
+    /// Stop the server: SIGTERM first so it removes its lock and socket, then
+    /// SIGKILL if it is still up after `STOP_TIMEOUT`.
+    async fn shutdown(mut self) {
+        let Some(mut child) = self.child.take() else {
+            return;
+        };
+        #[cfg(unix)]
+        unsafe {
+            libc::kill(child.id() as libc::pid_t, libc::SIGTERM);
+        }
+        let deadline = tokio::time::Instant::now() + STOP_TIMEOUT;
+        while tokio::time::Instant::now() < deadline {
+            if matches!(child.try_wait(), Ok(Some(_))) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        let _ = child.kill();
+        let _ = tokio::task::spawn_blocking(move || child.wait()).await;
+    }
+}
+
+impl Drop for ManagedDisplay {
+    /// A server that was not `shutdown` (e.g. when the pool goes away) is killed
+    /// without waiting for it.
+    fn drop(&mut self) {
+        if let Some(child) = self.child.as_mut() {
+            let _ = child.kill();
+            let _ = child.try_wait();
+        }
+    }
+}
+
+#[derive(Default)]
+struct PoolState {
+    /// display number -> running server
+    displays: BTreeMap<u32, ManagedDisplay>,
+    /// profile_id -> display number
+    assignments: HashMap<String, u32>,
+}
+
+/// Virtual displays owned by this Browsion instance.
+#[derive(Default)]
+pub struct DisplayPool {
+    state: Mutex<PoolState>,
+}
+
+impl DisplayPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get a display for the reserved profile, starting a server if needed, and
+    /// wait until it accepts connections. Returns the display number. Taking the
+    /// reservation keeps a second launch of the profile out while this awaits.
+    pub async fn acquire(
+        &self,
+        reservation: &ProfileReservation,
+        settings: &VirtualDisplaySettings,
+    ) -> Result<u32> {
+        let profile_id = reservation.profile_id();
+        if !cfg!(target_os = "linux") {
+            return Err(BrowsionError::Process(
+                "Virtual displays are only supported on Linux".to_string(),
+            ));
+        }
+        if !validate_screen(&settings.screen) {
+            return Err(BrowsionError::Validation(format!(
+                "Invalid virtual display screen {:?}. Expected WIDTHxHEIGHTxDEPTH",
+                settings.screen
+            )));
+        }
+
+        let display = self.assign(profile_id, settings)?;
+        if let Err(e) = self.wait_ready(display).await {
+            self.release(profile_id).await;
+            return Err(e);
+        }
+        Ok(display)
+    }
+
+    fn assign(&self, profile_id: &str, settings: &VirtualDisplaySettings) -> Result<u32> {
+        let mut state = self.state.lock();
+        if let Some(number) = state.assignments.get(profile_id) {
+            return Ok(*number);
+        }
+
+        let shared = settings.pool_size > 0 && state.displays.len() >= settings.pool_size as usize;
+        let number = if shared {
+            let (number, managed) = state
+                .displays
+                .iter_mut()
+                .min_by_key(|(_, d)| d.users)
+                .expect("pool is non-empty");
+            managed.users += 1;
+            *number
+        } else {
+            let number = (settings.first_display..settings.first_display + MAX_PROBE)
+                .find(|n| !state.displays.contains_key(n) && !display_taken(*n))
+                .ok_or_else(|| {
+                    BrowsionError::Process(format!(
+                        "No free X display between :{} and :{}",
+                        settings.first_display,
+                        settings.first_display + MAX_PROBE - 1
+                    ))
+                })?;
+            let (program, args) = server_command(settings.server, number, &settings.screen);
+            let child = Command::new(program)
+                .args(&args)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .map_err(|e| BrowsionError::Process(format!("Failed to start {}: {}", program, e)))?;
+            tracing::info!("Started {} on :{} (pid {})", program, number, child.id());
+            state.displays.insert(
+                number,
+                ManagedDisplay {
+                    child: Some(child),
+                    users: 1,
+                },
+            );
+            number
+        };
+
+        state.assignments.insert(profile_id.to_string(), number);
+        Ok(number)
+    }
+
+    async fn wait_ready(&self, display: u32) -> Result<()> {
+        let deadline = tokio::time::Instant::now() + READY_TIMEOUT;
+        loop {
+            if x_socket_path(display).exists() {
+                return Ok(());
+            }
+            {
+                let mut state = self.state.lock();
+                let exited = match state.displays.get_mut(&display) {
+                    Some(managed) => !matches!(managed.child.as_mut().map(Child::try_wait), Some(Ok(None))),
+                    None => true,
+                };
+                if exited {
+                    return Err(BrowsionError::Process(format!(
+                        "X server for display :{} exited during startup",
+                        display
+                    )));
+                }
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Err(BrowsionError::Process(format!(
+                    "X server for display :{} did not come up within {}s",
+                    display,
+                    READY_TIMEOUT.as_secs()
+                )));
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+
+    /// Release the display held by `profile_id` (no-op if it holds none). The
+    /// server is stopped when its last user goes away.
+    pub async fn release(&self, profile_id: &str) {
+        let stopped = {
+            let mut state = self.state.lock();
+            let Some(display) = state.assignments.remove(profile_id) else {
+                return;
+            };
+            let Some(managed) = state.displays.get_mut(&display) else {
+                return;
+            };
+            managed.users = managed.users.saturating_sub(1);
+            if managed.users > 0 {
+                return;
+            }
+            state.displays.remove(&display).map(|m| (display, m))
+        };
+        // Stop outside the lock; it waits for the server to exit.
+        if let Some((number, managed)) = stopped {
+            managed.shutdown().await;
+            tracing::info!("Stopped virtual display :{}", number);
+        }
+    }
+
+    /// Display currently assigned to `profile_id`.
+    pub fn display_for(&self, profile_id: &str) -> Option<u32> {
+        self.state.lock().assignments.get(profile_id).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_command() {
+        let (program, args) = server_command(VirtualDisplayServer::Xvfb, 99, "1280x720x24");
+        assert_eq!(program, "Xvfb");
+        assert_eq!(args, vec![":99", "-screen", "0", "1280x720x24", "-nolisten", "tcp"]);
+
+        let (program, args) = server_command(VirtualDisplayServer::Xephyr, 100, "800x600");
+        assert_eq!(program, "Xephyr");
+        assert_eq!(args[0], ":100");
+        assert_eq!(args[2], "800x600");
+    }
+
+    #[test]
+    fn test_validate_screen() {
+        assert!(validate_screen("1920x1080x24"));
+        assert!(validate_screen("800x600"));
+        assert!(!validate_screen("1920x1080x"));
+        assert!(!validate_screen("0x600"));
+        assert!(!validate_screen("big"));
+    }
+
+    #[tokio::test]
+    async fn test_release_unknown_profile_is_noop() {
+        let pool = DisplayPool::new();
+        pool.release("nobody").await;
+        assert!(pool.display_for("nobody").is_none());
+    }
+}
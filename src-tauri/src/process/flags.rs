@@ -0,0 +1,274 @@
+//! Built-in switch presets and merging of the final Chrome argument list.
+//!
+//! Arguments come from three places, in increasing priority: switches Browsion
+//! derives from profile fields, the profile's preset, and `custom_args`. A switch
+//! given more than once keeps its first position and takes its last value, except
+//! for the switches Browsion manages itself, which cannot be overridden. List
+//! switches (`--enable-features`, `--disable-features`, their blink variants and
+//! the extension lists) are combined instead of overridden.
+
+use crate::config::schema::{FlagPreset, LaunchFlags};
+
+/// Switches Browsion needs to control the browser; `custom_args` cannot override them.
+pub const MANAGED_SWITCHES: [&str; 2] = ["user-data-dir", "remote-debugging-port"];
+
+/// Switches whose comma-separated values are merged across all sources.
+pub const LIST_SWITCHES: [&str; 6] = [
+    "enable-features",
+    "disable-features",
+    "enable-blink-features",
+    "disable-blink-features",
+    "load-extension",
+    "disable-extensions-except",
+];
+
+/// Pairs of switches that contradict each other.
+pub const CONFLICTING_SWITCHES: [(&str, &str); 3] = [
+    ("proxy-server", "no-proxy-server"),
+    ("proxy-server", "proxy-pac-url"),
+    ("headless", "kiosk"),
+];
+
+const STEALTH: &[&str] = &[
+    "--no-first-run",
+    "--no-default-browser-check",
+    "--disable-background-networking",
+    "--disable-client-side-phishing-detection",
+    "--disable-default-apps",
+    "--disable-sync",
+    "--metrics-recording-only",
+    "--disable-blink-features=AutomationControlled",
+    "--disable-crash-reporter",
+    "--disable-in-process-stack-traces",
+];
+
+const MINIMAL: &[&str] = &["--no-first-run", "--no-default-browser-check"];
+
+const TESTING: &[&str] = &[
+    "--no-first-run",
+    "--no-default-browser-check",
+    "--disable-background-networking",
+    "--disable-background-timer-throttling",
+    "--disable-backgrounding-occluded-windows",
+    "--disable-renderer-backgrounding",
+    "--disable-component-update",
+    "--disable-default-apps",
+    "--disable-hang-monitor",
+    "--disable-popup-blocking",
+    "--disable-prompt-on-repost",
+    "--disable-sync",
+    "--metrics-recording-only",
+    "--password-store=basic",
+    "--use-mock-keychain",
+];
+
+/// Switches a preset contributes before per-profile adjustments.
+pub fn preset_switches(preset: FlagPreset) -> &'static [&'static str] {
+    match preset {
+        FlagPreset::Stealth => STEALTH,
+        FlagPreset::Minimal => MINIMAL,
+        FlagPreset::Testing => TESTING,
+    }
+}
+
+/// Name of a `--switch[=value]` argument, without dashes. None for positional args.
+pub fn switch_name(arg: &str) -> Option<&str> {
+    let body = arg.strip_prefix("--")?;
+    let name = body.split_once('=').map_or(body, |(name, _)| name);
+    (!name.is_empty()).then_some(name)
+}
+
+fn switch_value(arg: &str) -> Option<&str> {
+    arg.strip_prefix("--")?.split_once('=').map(|(_, v)| v)
+}
+
+/// Preset switches left after removing `flags.disabled`.
+pub fn effective_preset(flags: &LaunchFlags) -> Vec<String> {
+    preset_switches(flags.preset)
+        .iter()
+        .filter(|arg| {
+            switch_name(arg).is_some_and(|name| !flags.disabled.iter().any(|d| d == name))
+        })
+        .map(|arg| arg.to_string())
+        .collect()
+}
+
+/// Values of a list switch (`--disable-features=A,B`) across `args`.
+pub fn list_values<'a>(args: impl IntoIterator<Item = &'a String>, switch: &str) -> Vec<String> {
+    let mut values: Vec<String> = Vec::new();
+    for arg in args {
+        if switch_name(arg) != Some(switch) {
+            continue;
+        }
+        for value in switch_value(arg).unwrap_or("").split(',') {
+            let value = value.trim();
+            if !value.is_empty() && !values.iter().any(|v| v == value) {
+                values.push(value.to_string());
+            }
+        }
+    }
+    values
+}
+
+/// Build the final argument list from Browsion's own switches (`core`), the
+/// profile's flag settings and its custom arguments.
+pub fn merge_args(core: &[String], flags: &LaunchFlags, custom: &[String]) -> Vec<String> {
+    let preset = effective_preset(flags);
+    let sources: Vec<&String> = core.iter().chain(&preset).chain(custom).collect();
+
+    let mut switches: Vec<(String, String)> = Vec::new();
+    let mut positional: Vec<String> = Vec::new();
+    for (index, arg) in sources.iter().enumerate() {
+        let Some(name) = switch_name(arg) else {
+            positional.push(arg.to_string());
+            continue;
+        };
+        if LIST_SWITCHES.contains(&name) {
+            continue;
+        }
+        let from_core = index < core.len();
+        match switches.iter_mut().find(|(n, _)| n == name) {
+            Some(_) if MANAGED_SWITCHES.contains(&name) && !from_core => {}
+            Some(slot) => slot.1 = arg.to_string(),
+            None => switches.push((name.to_string(), arg.to_string())),
+        }
+    }
+
+    let mut out: Vec<String> = switches.into_iter().map(|(_, arg)| arg).collect();
+
+    let mut lists: Vec<(&str, Vec<String>)> = LIST_SWITCHES
+        .iter()
+        .map(|switch| (*switch, list_values(sources.iter().copied(), switch)))
+        .collect();
+    for (switch, extra) in [
+        ("enable-features", &flags.enable_features),
+        ("disable-features", &flags.disable_features),
+    ] {
+        let (_, values) = lists.iter_mut().find(|(s, _)| *s == switch).expect("list switch");
+        for v in extra {
+            if !values.contains(v) {
+                values.push(v.clone());
+            }
+        }
+    }
+    // A feature that is both enabled and disabled stays disabled.
+    let disabled: Vec<String> = lists
+        .iter()
+        .find(|(s, _)| *s == "disable-features")
+        .map(|(_, v)| v.clone())
+        .unwrap_or_default();
+    for (switch, values) in &mut lists {
+        if *switch == "enable-features" {
+            values.retain(|v| !disabled.contains(v));
+        }
+    }
+    // With an allow list, every loaded extension must be on it or Chrome disables it.
+    let loaded: Vec<String> = lists
+        .iter()
+        .find(|(s, _)| *s == "load-extension")
+        .map(|(_, v)| v.clone())
+        .unwrap_or_default();
+    if let Some((_, allowed)) = lists.iter_mut().find(|(s, _)| *s == "disable-extensions-except") {
+        if !allowed.is_empty() {
+            for dir in loaded {
+                if !allowed.contains(&dir) {
+                    allowed.push(dir);
+                }
+            }
+        }
+    }
+    for (switch, values) in lists {
+        if !values.is_empty() {
+            out.push(format!("--{}={}", switch, values.join(",")));
+        }
+    }
+
+    out.extend(positional);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_switch_name() {
+        assert_eq!(switch_name("--proxy-server=http://x"), Some("proxy-server"));
+        assert_eq!(switch_name("--disable-gpu"), Some("disable-gpu"));
+        assert_eq!(switch_name("https://example.com"), None);
+        assert_eq!(switch_name("--"), None);
+    }
+
+    #[test]
+    fn test_merge_last_value_wins_and_managed_kept() {
+        let core = strings(&[
+            "--user-data-dir=/tmp/p",
+            "--remote-debugging-port=9222",
+            "--proxy-server=direct://",
+        ]);
+        let custom = strings(&[
+            "--proxy-server=socks5://127.0.0.1:1080",
+            "--remote-debugging-port=1",
+            "--disable-gpu",
+            "--disable-gpu",
+            "https://example.com",
+        ]);
+        let args = merge_args(&core, &LaunchFlags::default(), &custom);
+
+        assert_eq!(args.iter().filter(|a| a.starts_with("--proxy-server")).count(), 1);
+        assert!(args.contains(&"--proxy-server=socks5://127.0.0.1:1080".to_string()));
+        assert!(args.contains(&"--remote-debugging-port=9222".to_string()));
+        assert_eq!(args.iter().filter(|a| *a == "--disable-gpu").count(), 1);
+        assert_eq!(args.last().map(String::as_str), Some("https://example.com"));
+    }
+
+    #[test]
+    fn test_merge_feature_lists() {
+        let flags = LaunchFlags {
+            preset: FlagPreset::Stealth,
+            disabled: vec!["disable-sync".to_string()],
+            enable_features: vec!["NetworkService".to_string(), "Foo".to_string()],
+            disable_features: vec!["Translate".to_string()],
+        };
+        let custom = strings(&[
+            "--disable-features=Foo,Translate",
+            "--disable-blink-features=Bar",
+        ]);
+        let args = merge_args(&[], &flags, &custom);
+
+        assert!(!args.contains(&"--disable-sync".to_string()));
+        assert!(args.contains(&"--enable-features=NetworkService".to_string()));
+        assert!(args.contains(&"--disable-features=Foo,Translate".to_string()));
+        assert!(args.contains(&"--disable-blink-features=AutomationControlled,Bar".to_string()));
+    }
+
+    #[test]
+    fn test_merge_allows_custom_extensions() {
+        let core = strings(&["--load-extension=/lib/a", "--disable-extensions-except=/lib/a"]);
+        let custom = strings(&["--load-extension=/home/me/dev-ext"]);
+        let args = merge_args(&core, &LaunchFlags::default(), &custom);
+
+        assert!(args.contains(&"--load-extension=/lib/a,/home/me/dev-ext".to_string()));
+        assert!(args.contains(&"--disable-extensions-except=/lib/a,/home/me/dev-ext".to_string()));
+
+        // Without library extensions nothing is restricted
+        let args = merge_args(&[], &LaunchFlags::default(), &custom);
+        assert!(!args.iter().any(|a| a.starts_with("--disable-extensions-except")));
+    }
+
+    #[test]
+    fn test_minimal_preset() {
+        let flags = LaunchFlags {
+            preset: FlagPreset::Minimal,
+            ..Default::default()
+        };
+        assert_eq!(
+            merge_args(&[], &flags, &[]),
+            strings(&["--no-first-run", "--no-default-browser-check"])
+        );
+    }
+}
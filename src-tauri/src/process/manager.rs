@@ -1,20 +1,55 @@
-use crate::config::schema::{AppConfig, ProcessInfo};
+use crate::config::schema::{AppConfig, DisplayMode, LaunchOverrides, ProcessInfo};
 use crate::error::{BrowsionError, Result};
-use crate::process::launcher;
+use crate::process::display::DisplayPool;
+use crate::process::launcher::LaunchContext;
+use crate::process::singleton::{self, LockState};
+use crate::process::{discovery, launcher};
 use parking_lot::Mutex;
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
-use sysinfo::{Pid, ProcessRefreshKind, System};
+use sysinfo::{Pid, ProcessRefreshKind, System, UpdateKind};
+
+/// A browser started by `launch_profile`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Launched {
+    pub pid: u32,
+    pub cdp_port: u16,
+    /// Non-fatal problems with the profile's switches and fingerprint settings.
+    pub warnings: Vec<String>,
+}
+
+/// Exclusive claim on a profile while its browser is being launched, or while
+/// its data is changed with the browser stopped. Released on drop.
+pub struct ProfileReservation {
+    reserved: Arc<Mutex<HashSet<String>>>,
+    profile_id: String,
+}
+
+impl ProfileReservation {
+    pub fn profile_id(&self) -> &str {
+        &self.profile_id
+    }
+}
+
+impl Drop for ProfileReservation {
+    fn drop(&mut self) {
+        self.reserved.lock().remove(&self.profile_id);
+    }
+}
 
 pub struct ProcessManager {
     /// Map of profile_id -> ProcessInfo
     active_processes: Arc<Mutex<HashMap<String, ProcessInfo>>>,
+    /// Profiles held by a `ProfileReservation`
+    reserved: Arc<Mutex<HashSet<String>>>,
     /// System info for process tracking
     system: Arc<Mutex<System>>,
     /// Recently launched profiles (most recent first)
     recent_launches: Arc<Mutex<Vec<String>>>,
+    /// Virtual X displays used by `DisplayMode::Virtual` profiles
+    displays: Arc<DisplayPool>,
 }
 
 impl ProcessManager {
@@ -25,47 +60,124 @@ impl ProcessManager {
     pub fn new_with_recent(recent: Vec<String>) -> Self {
         Self {
             active_processes: Arc::new(Mutex::new(HashMap::new())),
+            reserved: Arc::new(Mutex::new(HashSet::new())),
             system: Arc::new(Mutex::new(System::new_all())),
             recent_launches: Arc::new(Mutex::new(recent)),
+            displays: Arc::new(DisplayPool::new()),
+        }
+    }
+
+    /// Claim `profile_id` for a launch or an offline change to its data. Fails
+    /// with `Conflict` while its browser runs or another reservation holds it.
+    pub fn reserve_profile(&self, profile_id: &str) -> Result<ProfileReservation> {
+        let mut reserved = self.reserved.lock();
+        if reserved.contains(profile_id) {
+            return Err(BrowsionError::Conflict(format!(
+                "Profile {} is busy: it is being launched or its data is being changed",
+                profile_id
+            )));
         }
+        if self.is_running(profile_id) {
+            return Err(BrowsionError::Conflict(format!(
+                "Profile {} is already running",
+                profile_id
+            )));
+        }
+        reserved.insert(profile_id.to_string());
+        Ok(ProfileReservation {
+            reserved: self.reserved.clone(),
+            profile_id: profile_id.to_string(),
+        })
     }
 
     /// Launch a browser profile with the given Chrome executable path.
-    /// Returns `(pid, cdp_port)` so callers can connect via CDP.
+    /// Returns the pid and CDP port so callers can connect via CDP.
     pub async fn launch_profile(
         &self,
         profile_id: &str,
         config: &AppConfig,
         chrome_path: &Path,
-    ) -> Result<(u32, u16)> {
-        let profile = config
+    ) -> Result<Launched> {
+        self.launch_profile_with(profile_id, config, chrome_path, &LaunchOverrides::default())
+            .await
+    }
+
+    /// Like `launch_profile`, with one-off `overrides` applied to a copy of the
+    /// stored profile (after its group settings are resolved).
+    pub async fn launch_profile_with(
+        &self,
+        profile_id: &str,
+        config: &AppConfig,
+        chrome_path: &Path,
+        overrides: &LaunchOverrides,
+    ) -> Result<Launched> {
+        let stored = config
             .profiles
             .iter()
             .find(|p| p.id == profile_id)
             .ok_or_else(|| BrowsionError::ProfileNotFound(profile_id.to_string()))?;
+        let stored = crate::commands::groups::resolve_profile(stored, &config.groups);
+        let overridden;
+        let profile = if overrides.is_empty() {
+            &stored
+        } else {
+            overridden = overrides.apply(&stored);
+            crate::config::validation::validate_profile(&overridden)?;
+            &overridden
+        };
 
-        if self.is_running(profile_id) {
-            return Err(BrowsionError::Process(format!(
-                "Profile {} is already running",
-                profile_id
-            )));
-        }
+        // Held until the browser is in `active_processes`, so a concurrent launch
+        // cannot pass the running check while this one awaits
+        let reservation = self.reserve_profile(profile_id)?;
 
         crate::config::validation::validate_chrome_path(chrome_path)?;
 
+        let aligned;
+        let profile = match crate::commands::geo::pre_launch_check(profile, &config.settings.geo_check)
+            .await?
+        {
+            Some(p) => {
+                aligned = p;
+                &aligned
+            }
+            None => profile,
+        };
+
+        self.recover_profile_lock(profile_id, &profile.user_data_dir)?;
+
         let cdp_port = crate::process::port::allocate_cdp_port();
-        let mut cmd = launcher::build_command(chrome_path, profile, cdp_port);
+        let mut ctx = LaunchContext::new(cdp_port);
+        ctx.fingerprint_chromium = config.browser_source.is_fingerprint_chromium();
+        ctx.extension_dirs =
+            crate::commands::extensions::resolve_extension_dirs(&profile.extensions)
+                .map_err(BrowsionError::Validation)?;
+        if profile.effective_display_mode() == DisplayMode::Virtual {
+            ctx.display = Some(
+                self.displays
+                    .acquire(&reservation, &config.settings.virtual_display)
+                    .await?,
+            );
+        }
+        let warnings = crate::config::validation::profile_warnings(profile, &config.browser_source);
+        for warning in &warnings {
+            tracing::warn!("Profile {}: {}", profile_id, warning);
+        }
+        let mut cmd = launcher::build_command(chrome_path, profile, &ctx);
 
         tracing::info!(
-            "Launching profile {} with CDP port {} — command: {:?}",
+            "Launching profile {} with CDP port {} — command: {}",
             profile_id,
             cdp_port,
-            cmd
+            launcher::describe_command(&cmd)
         );
 
-        let child = cmd
-            .spawn()
-            .map_err(|e| BrowsionError::Process(format!("Failed to launch Chrome: {}", e)))?;
+        let child = match cmd.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                self.displays.release(profile_id).await;
+                return Err(BrowsionError::Process(format!("Failed to launch Chrome: {}", e)));
+            }
+        };
 
         let pid = child.id();
         let now = SystemTime::now()
@@ -78,11 +190,16 @@ impl ProcessManager {
             pid,
             launched_at: now,
             cdp_port: Some(cdp_port),
+            chrome_path: Some(chrome_path.to_path_buf()),
+            user_data_dir: Some(profile.user_data_dir.clone()),
+            browser_ws_id: None,
+            display: ctx.display,
         };
 
         self.active_processes
             .lock()
             .insert(profile_id.to_string(), process_info);
+        drop(reservation);
 
         {
             let mut recent = self.recent_launches.lock();
@@ -100,7 +217,57 @@ impl ProcessManager {
             cdp_port
         );
 
-        Ok((pid, cdp_port))
+        Ok(Launched {
+            pid,
+            cdp_port,
+            warnings,
+        })
+    }
+
+    /// Check Chrome's `SingletonLock` before spawning. Stale locks left by a crash
+    /// are removed; a lock held by a live Chrome we don't track is an error, since
+    /// a new Chrome would just hand off to it and exit.
+    pub(crate) fn recover_profile_lock(&self, profile_id: &str, user_data_dir: &Path) -> Result<()> {
+        match singleton::inspect_lock(user_data_dir, |pid| self.is_chrome_pid_alive(pid)) {
+            LockState::Unlocked => Ok(()),
+            LockState::Stale { pid } => {
+                tracing::warn!(
+                    "Removing stale SingletonLock for profile {} (owner pid {:?} is gone)",
+                    profile_id,
+                    pid
+                );
+                singleton::remove_lock_files(user_data_dir).map_err(|e| {
+                    BrowsionError::Process(format!(
+                        "Failed to remove stale lock in {:?}: {}",
+                        user_data_dir, e
+                    ))
+                })
+            }
+            LockState::Held { pid } => Err(BrowsionError::ProfileLocked(format!(
+                "{:?} is in use by Chrome process {} that Browsion did not launch; \
+                 close it or attach to it instead",
+                user_data_dir, pid
+            ))),
+            LockState::OtherHost { hostname, pid } => Err(BrowsionError::ProfileLocked(format!(
+                "{:?} is locked by process {} on host {}",
+                user_data_dir, pid, hostname
+            ))),
+        }
+    }
+
+    /// Whether `pid` is a live (non-zombie) Chrome/Chromium process.
+    fn is_chrome_pid_alive(&self, pid: u32) -> bool {
+        let pid = Pid::from_u32(pid);
+        let mut system = self.system.lock();
+        system.refresh_processes_specifics(
+            sysinfo::ProcessesToUpdate::Some(&[pid]),
+            ProcessRefreshKind::new(),
+        );
+        system.process(pid).is_some_and(|process| {
+            let name = process.name().to_string_lossy().to_lowercase();
+            (name.contains("chrome") || name.contains("chromium"))
+                && process.status() != sysinfo::ProcessStatus::Zombie
+        })
     }
 
     /// Get the CDP port for a running profile (if available).
@@ -122,25 +289,28 @@ impl ProcessManager {
             tracing::info!("Killing profile {} (PID: {})", profile_id, info.pid);
 
             // Try to kill the process
-            let pid = Pid::from_u32(info.pid);
-            let mut system = self.system.lock();
-            system.refresh_processes_specifics(
-                sysinfo::ProcessesToUpdate::Some(&[pid]),
-                ProcessRefreshKind::new(),
-            );
-
-            if let Some(process) = system.process(pid) {
-                if process.kill() {
-                    tracing::info!("Successfully killed process {}", info.pid);
+            {
+                let pid = Pid::from_u32(info.pid);
+                let mut system = self.system.lock();
+                system.refresh_processes_specifics(
+                    sysinfo::ProcessesToUpdate::Some(&[pid]),
+                    ProcessRefreshKind::new(),
+                );
+
+                if let Some(process) = system.process(pid) {
+                    if process.kill() {
+                        tracing::info!("Successfully killed process {}", info.pid);
+                    } else {
+                        tracing::warn!("Failed to kill process {}", info.pid);
+                    }
                 } else {
-                    tracing::warn!("Failed to kill process {}", info.pid);
+                    tracing::warn!("Process {} not found in system", info.pid);
                 }
-            } else {
-                tracing::warn!("Process {} not found in system", info.pid);
             }
 
             // Remove from active processes
             self.active_processes.lock().remove(profile_id);
+            self.displays.release(profile_id).await;
 
             Ok(())
         } else {
@@ -308,6 +478,9 @@ impl ProcessManager {
                 processes.remove(profile_id);
             }
         }
+        for profile_id in &to_remove {
+            self.displays.release(profile_id).await;
+        }
 
         Ok(to_remove)
     }
@@ -324,19 +497,92 @@ impl ProcessManager {
         recent.clone()
     }
 
-    /// Register an externally-launched browser (e.g., one that survived a Tauri restart).
+    /// Refresh all processes including command lines and executables
+    /// (needed to match `--user-data-dir=`).
+    fn refresh_processes_with_cmd(&self) -> parking_lot::MutexGuard<'_, System> {
+        let mut system = self.system.lock();
+        system.refresh_processes_specifics(
+            sysinfo::ProcessesToUpdate::All,
+            ProcessRefreshKind::new()
+                .with_cmd(UpdateKind::OnlyIfNotSet)
+                .with_exe(UpdateKind::OnlyIfNotSet),
+        );
+        system
+    }
+
+    /// List Chrome browsers on this machine that expose a debugging port.
+    /// `managed` is set for browsers Browsion already tracks; `profile_id` is left
+    /// for the caller to match against the configured profiles.
+    pub fn discover_browsers(&self) -> Vec<discovery::DiscoveredBrowser> {
+        let tracked: Vec<u32> = self
+            .active_processes
+            .lock()
+            .values()
+            .map(|info| info.pid)
+            .collect();
+        let system = self.refresh_processes_with_cmd();
+        let mut browsers = discovery::discover_browsers(&system);
+        for b in &mut browsers {
+            b.managed = tracked.contains(&b.pid);
+        }
+        browsers
+    }
+
+    /// Find a running browser that uses `user_data_dir` and exposes a debugging
+    /// port via `DevToolsActivePort`. Returns `(pid, cdp_port)`.
+    pub fn find_external_browser(&self, user_data_dir: &Path) -> Result<(u32, u16)> {
+        let active = discovery::read_devtools_active_port(user_data_dir).ok_or_else(|| {
+            BrowsionError::BrowserNotRunning(format!(
+                "No DevToolsActivePort in {:?}: browser is not running with remote debugging",
+                user_data_dir
+            ))
+        })?;
+        let system = self.refresh_processes_with_cmd();
+        let pid = discovery::find_browser_pid(&system, user_data_dir).ok_or_else(|| {
+            BrowsionError::BrowserNotRunning(format!(
+                "No running Chrome process uses --user-data-dir={}",
+                user_data_dir.display()
+            ))
+        })?;
+        Ok((pid, active.port))
+    }
+
+    /// Register an externally-launched browser (e.g., one adopted via the API).
     /// Does NOT spawn a new process — just tracks the existing PID + CDP port.
-    pub fn register_external(&self, profile_id: &str, pid: u32, cdp_port: u16) {
+    pub fn register_external(
+        &self,
+        profile_id: &str,
+        pid: u32,
+        cdp_port: u16,
+        user_data_dir: Option<PathBuf>,
+    ) {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap_or_default()
             .as_secs();
 
+        let chrome_path = {
+            let pid = Pid::from_u32(pid);
+            let mut system = self.system.lock();
+            system.refresh_processes_specifics(
+                sysinfo::ProcessesToUpdate::Some(&[pid]),
+                ProcessRefreshKind::new().with_exe(UpdateKind::OnlyIfNotSet),
+            );
+            system
+                .process(pid)
+                .and_then(|p| p.exe())
+                .map(Path::to_path_buf)
+        };
+
         let process_info = ProcessInfo {
             profile_id: profile_id.to_string(),
             pid,
             launched_at: now,
             cdp_port: Some(cdp_port),
+            chrome_path,
+            user_data_dir,
+            browser_ws_id: None,
+            display: None,
         };
 
         self.active_processes
@@ -350,6 +596,71 @@ impl ProcessManager {
             cdp_port
         );
     }
+
+    /// Track a previously persisted process again (session reconnect).
+    /// Callers must run `verify_process` first.
+    pub fn restore_process(&self, info: ProcessInfo) {
+        tracing::info!(
+            "Restored session: profile={} pid={} cdp_port={:?}",
+            info.profile_id,
+            info.pid,
+            info.cdp_port
+        );
+        self.active_processes
+            .lock()
+            .insert(info.profile_id.clone(), info);
+    }
+
+    /// Check that `info.pid` is still the browser described by `info`, not a
+    /// recycled pid: it must have started no later than `launched_at` and carry
+    /// the recorded `--user-data-dir` and `--remote-debugging-port` switches. The
+    /// executable is not compared: wrappers like `/usr/bin/google-chrome` exec a
+    /// different binary.
+    pub fn verify_process(&self, info: &ProcessInfo) -> std::result::Result<(), String> {
+        let pid = Pid::from_u32(info.pid);
+        let mut system = self.system.lock();
+        system.refresh_processes_specifics(
+            sysinfo::ProcessesToUpdate::Some(&[pid]),
+            ProcessRefreshKind::new().with_cmd(UpdateKind::Always),
+        );
+        let process = system
+            .process(pid)
+            .ok_or_else(|| format!("pid {} is not running", info.pid))?;
+
+        if process.status() == sysinfo::ProcessStatus::Zombie {
+            return Err(format!("pid {} is a zombie", info.pid));
+        }
+
+        // Allow a little clock slack between spawn and our timestamp.
+        if info.launched_at > 0 && process.start_time() > info.launched_at + 5 {
+            return Err(format!(
+                "pid {} was recycled: process started at {}, session recorded at {}",
+                info.pid,
+                process.start_time(),
+                info.launched_at
+            ));
+        }
+
+        if let Some(expected) = &info.user_data_dir {
+            let actual = discovery::switch_value(process.cmd(), "--user-data-dir");
+            if !actual.is_some_and(|dir| discovery::same_dir(Path::new(&dir), expected)) {
+                return Err(format!(
+                    "pid {} does not use --user-data-dir={}",
+                    info.pid,
+                    expected.display()
+                ));
+            }
+        }
+
+        if let Some(port) = info.cdp_port {
+            let actual = discovery::switch_value(process.cmd(), "--remote-debugging-port");
+            if actual.is_some_and(|p| p != port.to_string()) {
+                return Err(format!("pid {} does not listen on CDP port {}", info.pid, port));
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl Default for ProcessManager {
@@ -1,7 +1,12 @@
+pub mod cdp;
+pub mod discovery;
+pub mod display;
+pub mod flags;
 pub mod launcher;
 pub mod manager;
 pub mod port;
 pub mod sessions_persist;
+pub mod singleton;
 
 pub use launcher::*;
 pub use manager::*;
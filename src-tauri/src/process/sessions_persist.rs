@@ -1,15 +1,59 @@
 //! Persist running browser sessions across Tauri restarts.
 //! Saved to ~/.browsion/running_sessions.json
+//!
+//! Every read-modify-write holds an exclusive lock on `running_sessions.json.lock`
+//! and replaces the file atomically (temp file + rename), so concurrent
+//! `save_session` / `remove_session` tasks cannot clobber each other.
 
+use crate::config::schema::ProcessInfo;
+use crate::process::ProcessManager;
+use fs2::FileExt;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionEntry {
     pub pid: u32,
     pub cdp_port: u16,
+    /// Unix timestamp (s) when the browser was launched or registered.
+    /// 0 for sessions written by older versions.
+    #[serde(default)]
+    pub launched_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chrome_path: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_data_dir: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub browser_ws_id: Option<String>,
+}
+
+impl SessionEntry {
+    fn from_process_info(info: &ProcessInfo, cdp_port: u16) -> Self {
+        Self {
+            pid: info.pid,
+            cdp_port,
+            launched_at: info.launched_at,
+            chrome_path: info.chrome_path.clone(),
+            user_data_dir: info.user_data_dir.clone(),
+            browser_ws_id: info.browser_ws_id.clone(),
+        }
+    }
+
+    fn to_process_info(&self, profile_id: &str) -> ProcessInfo {
+        ProcessInfo {
+            profile_id: profile_id.to_string(),
+            pid: self.pid,
+            launched_at: self.launched_at,
+            cdp_port: Some(self.cdp_port),
+            chrome_path: self.chrome_path.clone(),
+            user_data_dir: self.user_data_dir.clone(),
+            browser_ws_id: self.browser_ws_id.clone(),
+            // Virtual displays are owned by the Browsion instance that started them
+            display: None,
+        }
+    }
 }
 
 fn sessions_path() -> PathBuf {
@@ -19,10 +63,8 @@ fn sessions_path() -> PathBuf {
         .join("running_sessions.json")
 }
 
-/// Load persisted sessions map (profile_id → SessionEntry).
-pub async fn load_sessions() -> io::Result<HashMap<String, SessionEntry>> {
-    let path = sessions_path();
-    match tokio::fs::read_to_string(&path).await {
+fn read_map(path: &Path) -> io::Result<HashMap<String, SessionEntry>> {
+    match std::fs::read_to_string(path) {
         Ok(text) => serde_json::from_str(&text)
             .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
         Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(HashMap::new()),
@@ -30,26 +72,238 @@ pub async fn load_sessions() -> io::Result<HashMap<String, SessionEntry>> {
     }
 }
 
-/// Upsert a session entry for the given profile.
-pub async fn save_session(profile_id: &str, pid: u32, cdp_port: u16) -> io::Result<()> {
-    let mut map = load_sessions().await.unwrap_or_default();
-    map.insert(profile_id.to_string(), SessionEntry { pid, cdp_port });
-    write_map(&map).await
+/// Locked read-modify-write of the sessions file at `path`.
+fn update_map_at(
+    path: &Path,
+    update: impl FnOnce(&mut HashMap<String, SessionEntry>),
+) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let lock = std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path.with_extension("json.lock"))?;
+    lock.lock_exclusive()?;
+
+    let mut map = read_map(path).unwrap_or_else(|e| {
+        tracing::warn!("Discarding unreadable sessions file {:?}: {}", path, e);
+        HashMap::new()
+    });
+    update(&mut map);
+
+    let text = serde_json::to_string_pretty(&map)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let tmp = path.with_extension(format!("json.tmp.{}", std::process::id()));
+    std::fs::write(&tmp, text)?;
+    let result = std::fs::rename(&tmp, path);
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    let _ = fs2::FileExt::unlock(&lock);
+    result
+}
+
+async fn update_map(
+    update: impl FnOnce(&mut HashMap<String, SessionEntry>) + Send + 'static,
+) -> io::Result<()> {
+    tokio::task::spawn_blocking(move || update_map_at(&sessions_path(), update))
+        .await
+        .map_err(io::Error::other)?
+}
+
+/// Load persisted sessions map (profile_id → SessionEntry).
+pub async fn load_sessions() -> io::Result<HashMap<String, SessionEntry>> {
+    tokio::task::spawn_blocking(|| read_map(&sessions_path()))
+        .await
+        .map_err(io::Error::other)?
+}
+
+/// Ask a browser's `/json/version` for its websocket debugger id.
+pub async fn fetch_browser_ws_id(cdp_port: u16) -> Option<String> {
+    let version = crate::process::cdp::fetch_version(cdp_port).await.ok()?;
+    version
+        .get("webSocketDebuggerUrl")
+        .and_then(|v| v.as_str())
+        .and_then(|url| url.rsplit('/').next())
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+}
+
+/// Record `ws_id` on the session of `profile_id` if it is still the one for `pid`.
+/// A session removed in the meantime (browser killed) stays removed.
+fn set_browser_ws_id(map: &mut HashMap<String, SessionEntry>, profile_id: &str, pid: u32, ws_id: String) {
+    if let Some(entry) = map.get_mut(profile_id).filter(|e| e.pid == pid) {
+        entry.browser_ws_id = Some(ws_id);
+    }
+}
+
+/// Upsert the session for a running browser. If the websocket id is not known
+/// yet, waits (up to ~10s) for the CDP endpoint to come up and then adds it to
+/// the entry, unless the session was removed meanwhile.
+pub async fn save_session(info: &ProcessInfo) -> io::Result<()> {
+    let Some(cdp_port) = info.cdp_port else {
+        return Ok(());
+    };
+    let entry = SessionEntry::from_process_info(info, cdp_port);
+    let known = entry.browser_ws_id.is_some();
+    let profile_id = info.profile_id.clone();
+    update_map(move |map| {
+        map.insert(profile_id, entry);
+    })
+    .await?;
+    if known {
+        return Ok(());
+    }
+
+    for _ in 0..20 {
+        if let Some(ws_id) = fetch_browser_ws_id(cdp_port).await {
+            let (profile_id, pid) = (info.profile_id.clone(), info.pid);
+            return update_map(move |map| set_browser_ws_id(map, &profile_id, pid, ws_id)).await;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    }
+    Ok(())
 }
 
 /// Remove a session entry (called when browser is killed).
 pub async fn remove_session(profile_id: &str) -> io::Result<()> {
-    let mut map = load_sessions().await.unwrap_or_default();
-    map.remove(profile_id);
-    write_map(&map).await
+    let profile_id = profile_id.to_string();
+    update_map(move |map| {
+        map.remove(&profile_id);
+    })
+    .await
 }
 
-async fn write_map(map: &HashMap<String, SessionEntry>) -> io::Result<()> {
-    let path = sessions_path();
-    if let Some(parent) = path.parent() {
-        tokio::fs::create_dir_all(parent).await?;
+/// Check that the CDP port still belongs to the recorded browser instance.
+async fn verify_cdp(entry: &SessionEntry) -> Result<(), String> {
+    let current = fetch_browser_ws_id(entry.cdp_port)
+        .await
+        .ok_or_else(|| format!("CDP port {} does not answer", entry.cdp_port))?;
+    match &entry.browser_ws_id {
+        Some(expected) if *expected != current => Err(format!(
+            "CDP port {} is now owned by another browser instance",
+            entry.cdp_port
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Re-adopt browsers that survived a restart. Each session is verified (pid
+/// identity, command line, browser websocket id) before it is
+/// registered; sessions that fail verification are dropped from the file.
+/// Returns the restored profile ids.
+pub async fn restore_sessions(process_manager: &ProcessManager) -> io::Result<Vec<String>> {
+    let sessions = load_sessions().await?;
+    let mut restored = Vec::new();
+    let mut dead = Vec::new();
+
+    for (profile_id, entry) in sessions {
+        let mut info = entry.to_process_info(&profile_id);
+        let verdict = match process_manager.verify_process(&info) {
+            Ok(()) => verify_cdp(&entry).await,
+            Err(e) => Err(e),
+        };
+        match verdict {
+            Ok(()) => {
+                if info.browser_ws_id.is_none() {
+                    info.browser_ws_id = fetch_browser_ws_id(entry.cdp_port).await;
+                }
+                process_manager.restore_process(info);
+                restored.push(profile_id);
+            }
+            Err(reason) => {
+                tracing::info!(
+                    "Session for profile {} not restored: {}",
+                    profile_id,
+                    reason
+                );
+                dead.push(profile_id);
+            }
+        }
+    }
+
+    if !dead.is_empty() {
+        update_map(move |map| {
+            for id in &dead {
+                map.remove(id);
+            }
+        })
+        .await?;
+    }
+
+    Ok(restored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(pid: u32) -> SessionEntry {
+        SessionEntry {
+            pid,
+            cdp_port: 9222,
+            launched_at: 0,
+            chrome_path: None,
+            user_data_dir: None,
+            browser_ws_id: None,
+        }
+    }
+
+    #[test]
+    fn test_legacy_entry_deserializes() {
+        let map: HashMap<String, SessionEntry> =
+            serde_json::from_str(r#"{"p1": {"pid": 42, "cdp_port": 9300}}"#).unwrap();
+        let e = &map["p1"];
+        assert_eq!(e.pid, 42);
+        assert_eq!(e.launched_at, 0);
+        assert!(e.browser_ws_id.is_none());
+    }
+
+    #[test]
+    fn test_concurrent_updates_do_not_clobber() {
+        let dir = std::env::temp_dir().join(format!("browsion-sessions-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("running_sessions.json");
+
+        let handles: Vec<_> = (0..16u32)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    update_map_at(&path, |map| {
+                        map.insert(format!("p{}", i), entry(i));
+                    })
+                    .unwrap();
+                })
+            })
+            .collect();
+        for h in handles {
+            h.join().unwrap();
+        }
+
+        let map = read_map(&path).unwrap();
+        assert_eq!(map.len(), 16);
+        assert_eq!(map["p7"].pid, 7);
+
+        update_map_at(&path, |map| {
+            map.remove("p7");
+        })
+        .unwrap();
+        assert!(!read_map(&path).unwrap().contains_key("p7"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_late_ws_id_does_not_revive_removed_session() {
+        let mut map = HashMap::from([("p1".to_string(), entry(1)), ("p2".to_string(), entry(2))]);
+        set_browser_ws_id(&mut map, "p1", 1, "ws-1".to_string());
+        assert_eq!(map["p1"].browser_ws_id.as_deref(), Some("ws-1"));
+
+        // Relaunched with a new pid, or killed before the id arrived
+        set_browser_ws_id(&mut map, "p2", 99, "ws-old".to_string());
+        assert!(map["p2"].browser_ws_id.is_none());
+        map.remove("p2");
+        set_browser_ws_id(&mut map, "p2", 2, "ws-2".to_string());
+        assert!(!map.contains_key("p2"));
     }
-    let text = serde_json::to_string_pretty(map)
-        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-    tokio::fs::write(&path, text).await
 }
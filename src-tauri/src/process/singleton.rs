@@ -0,0 +1,130 @@
+//! Inspect and recover Chrome's profile lock in a user data dir.
+//!
+//! On Linux and macOS Chrome claims a user data dir with three symlinks:
+//! `SingletonLock` points at `<hostname>-<pid>`, `SingletonSocket` and
+//! `SingletonCookie` at a socket in the temp dir. They are left behind when Chrome
+//! crashes or Browsion is force-quit, and a new Chrome then either refuses to start
+//! or hands its arguments to the (dead) owner and exits.
+
+use std::io;
+use std::path::Path;
+
+/// Lock artefacts Chrome leaves in the user data dir.
+pub const SINGLETON_FILES: [&str; 3] = ["SingletonLock", "SingletonSocket", "SingletonCookie"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LockState {
+    /// No lock present.
+    Unlocked,
+    /// Lock left by a process that is gone (or whose target is unreadable).
+    Stale { pid: Option<u32> },
+    /// Lock held by a live Chrome process on this machine.
+    Held { pid: u32 },
+    /// Lock written by another machine (shared user data dir); cannot be verified.
+    OtherHost { hostname: String, pid: u32 },
+}
+
+/// Parse a `SingletonLock` target of the form `<hostname>-<pid>`.
+/// The hostname itself may contain dashes, so split on the last one.
+pub fn parse_lock_target(target: &str) -> Option<(String, u32)> {
+    let (hostname, pid) = target.rsplit_once('-')?;
+    let pid: u32 = pid.parse().ok()?;
+    if hostname.is_empty() || pid == 0 {
+        return None;
+    }
+    Some((hostname.to_string(), pid))
+}
+
+#[cfg(unix)]
+fn local_hostname() -> Option<String> {
+    let mut buf = [0u8; 256];
+    let rc = unsafe { libc::gethostname(buf.as_mut_ptr() as *mut libc::c_char, buf.len()) };
+    if rc != 0 {
+        return None;
+    }
+    let end = buf.iter().position(|b| *b == 0).unwrap_or(buf.len());
+    Some(String::from_utf8_lossy(&buf[..end]).into_owned())
+}
+
+/// Inspect the lock in `user_data_dir`. `is_chrome_alive(pid)` decides whether the
+/// owning pid still belongs to a running Chrome (pids get recycled).
+#[cfg(unix)]
+pub fn inspect_lock(user_data_dir: &Path, is_chrome_alive: impl Fn(u32) -> bool) -> LockState {
+    let lock_path = user_data_dir.join("SingletonLock");
+    if std::fs::symlink_metadata(&lock_path).is_err() {
+        return LockState::Unlocked;
+    }
+
+    let target = match std::fs::read_link(&lock_path) {
+        Ok(t) => t.to_string_lossy().into_owned(),
+        Err(_) => return LockState::Stale { pid: None },
+    };
+    let Some((hostname, pid)) = parse_lock_target(&target) else {
+        return LockState::Stale { pid: None };
+    };
+
+    if local_hostname().is_some_and(|local| local != hostname) {
+        return LockState::OtherHost { hostname, pid };
+    }
+
+    if is_chrome_alive(pid) {
+        LockState::Held { pid }
+    } else {
+        LockState::Stale { pid: Some(pid) }
+    }
+}
+
+/// Windows locks the profile with an open `lockfile` handle that the OS releases
+/// when Chrome dies, so there is nothing stale to inspect.
+#[cfg(not(unix))]
+pub fn inspect_lock(_user_data_dir: &Path, _is_chrome_alive: impl Fn(u32) -> bool) -> LockState {
+    LockState::Unlocked
+}
+
+/// Remove all singleton artefacts from `user_data_dir`. Missing entries are ignored.
+pub fn remove_lock_files(user_data_dir: &Path) -> io::Result<()> {
+    for name in SINGLETON_FILES {
+        let path = user_data_dir.join(name);
+        if std::fs::symlink_metadata(&path).is_ok() {
+            std::fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lock_target() {
+        assert_eq!(
+            parse_lock_target("my-host-name-4242"),
+            Some(("my-host-name".to_string(), 4242))
+        );
+        assert_eq!(parse_lock_target("host-0"), None);
+        assert_eq!(parse_lock_target("nopid"), None);
+        assert_eq!(parse_lock_target("-123"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_inspect_and_remove_stale_lock() {
+        let dir = std::env::temp_dir().join(format!("browsion-singleton-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let host = local_hostname().unwrap();
+        std::os::unix::fs::symlink(format!("{}-999999", host), dir.join("SingletonLock")).unwrap();
+        std::os::unix::fs::symlink("/tmp/does-not-exist/socket", dir.join("SingletonSocket")).unwrap();
+
+        assert_eq!(inspect_lock(&dir, |_| true), LockState::Held { pid: 999999 });
+        assert_eq!(
+            inspect_lock(&dir, |_| false),
+            LockState::Stale { pid: Some(999999) }
+        );
+
+        remove_lock_files(&dir).unwrap();
+        assert_eq!(inspect_lock(&dir, |_| true), LockState::Unlocked);
+        assert!(std::fs::symlink_metadata(dir.join("SingletonSocket")).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,3 +1,4 @@
+use crate::config::schema::{AppConfig, ProfileGroup};
 use crate::state::AppState;
 use std::sync::Arc;
 use tauri::{
@@ -28,6 +29,8 @@ pub fn setup_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
                 // Handle profile menu click
                 let profile_id = event_id.strip_prefix("profile_").unwrap();
                 handle_profile_click(app, profile_id);
+            } else if let Some(profile_id) = event_id.strip_prefix("group_profile_") {
+                handle_profile_click(app, profile_id);
             }
         })
         .on_tray_icon_event(|tray, event| {
@@ -108,6 +111,16 @@ fn build_tray_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<Menu<R>> {
                 menu_items.push(Box::new(recent_submenu));
             }
         }
+
+        // One submenu per top-level group, nested like the groups themselves
+        if !config.groups.is_empty() {
+            state.process_manager.refresh_system_processes();
+            menu_items.push(Box::new(PredefinedMenuItem::separator(app)?));
+            for group in config.groups.iter().filter(|g| g.parent.is_none()) {
+                let submenu = build_group_submenu(app, &state, &config, group, 0)?;
+                menu_items.push(Box::new(submenu));
+            }
+        }
     }
 
     // Add separator and quit
@@ -127,6 +140,42 @@ fn build_tray_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<Menu<R>> {
     )
 }
 
+/// Submenu for `group`: its subgroups first, then its profiles.
+fn build_group_submenu<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &AppState,
+    config: &AppConfig,
+    group: &ProfileGroup,
+    depth: usize,
+) -> tauri::Result<Submenu<R>> {
+    let mut items: Vec<Box<dyn tauri::menu::IsMenuItem<R>>> = Vec::new();
+
+    // Depth guard in case a hand-edited config contains a parent cycle
+    if depth < config.groups.len() {
+        for child in config.groups.iter().filter(|g| g.parent.as_deref() == Some(group.id.as_str())) {
+            items.push(Box::new(build_group_submenu(app, state, config, child, depth + 1)?));
+        }
+    }
+    for profile in config.profiles.iter().filter(|p| p.group.as_deref() == Some(group.id.as_str())) {
+        let status = if state.process_manager.is_running_cached(&profile.id) { "●" } else { "○" };
+        let item = MenuItem::with_id(
+            app,
+            format!("group_profile_{}", profile.id),
+            format!("{} {}", status, profile.name),
+            true,
+            None::<&str>,
+        )?;
+        items.push(Box::new(item));
+    }
+
+    Submenu::with_items(
+        app,
+        &group.name,
+        true,
+        &items.iter().map(|item| item.as_ref()).collect::<Vec<_>>(),
+    )
+}
+
 /// Show and focus the main window
 fn show_main_window<R: Runtime>(app: &AppHandle<R>) {
     if let Some(window) = app.get_webview_window("main") {
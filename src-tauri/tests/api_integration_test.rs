@@ -278,6 +278,20 @@ async fn test_browser_cookies_not_running() {
     assert_eq!(res.status(), StatusCode::CONFLICT);
 }
 
+#[tokio::test]
+async fn test_attach_without_browser_is_not_found() {
+    let state = make_state_with_tagged_profiles();
+    for uri in ["/api/profiles/missing/attach", "/api/profiles/bulk-a/attach"] {
+        let req = axum::http::Request::builder()
+            .method("POST")
+            .uri(uri)
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let res = app(state.clone(), None).oneshot(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND, "{}", uri);
+    }
+}
+
 // ---------------------------------------------------------------------------
 // List profiles includes is_running flag
 // ---------------------------------------------------------------------------
@@ -863,6 +877,74 @@ async fn test_profile_snapshots_list_unknown_profile() {
     assert!(infos.is_empty());
 }
 
+#[tokio::test]
+async fn test_snapshot_archive_download_and_upload_errors() {
+    let state = make_state_with_tagged_profiles();
+    let req = axum::http::Request::builder()
+        .uri("/api/profiles/bulk-a/snapshots/no-such-snapshot/download?compression=zstd")
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/profiles/ghost-profile/snapshots/upload")
+        .body(axum::body::Body::from("not a zip"))
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/profiles/bulk-a/snapshots/upload?name=imported")
+        .body(axum::body::Body::from("not a zip"))
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    state.config.write().settings.max_upload_mb = 1;
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/profiles/bulk-a/snapshots/upload?name=imported")
+        .body(axum::body::Body::from(vec![0u8; 2 * 1024 * 1024]))
+        .unwrap();
+    let res = app(state, None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[tokio::test]
+async fn test_snapshot_exclusion_rules_are_validated() {
+    let state = make_state();
+    let profile = serde_json::json!({
+        "id": "rules-1",
+        "name": "Rules",
+        "user_data_dir": "/tmp/browsion-rules-1",
+        "snapshot_exclude": ["Default/History*", "[unclosed"]
+    });
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/profiles")
+        .header("content-type", "application/json")
+        .body(json_body(&profile))
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let mut settings = serde_json::to_value(&state.config.read().settings).unwrap();
+    assert!(settings["snapshot_exclude"].as_array().unwrap().contains(&serde_json::json!("Cache")));
+    settings["snapshot_exclude"] = serde_json::json!(["!"]);
+    let req = axum::http::Request::builder()
+        .method("PUT")
+        .uri("/api/settings")
+        .header("content-type", "application/json")
+        .body(json_body(&serde_json::json!({ "settings": settings })))
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    assert!(state.config.read().settings.snapshot_exclude.contains(&"Cache".to_string()));
+}
+
 // ---------------------------------------------------------------------------
 // Additional profile CRUD edge cases
 // ---------------------------------------------------------------------------
@@ -932,6 +1014,9 @@ async fn test_api_add_profile_with_tags_and_custom_args() {
         .unwrap();
     let res = api_app.clone().oneshot(req).await.unwrap();
     assert_eq!(res.status(), StatusCode::CREATED);
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let saved: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(saved["warnings"], serde_json::json!([]));
 
     // Verify the profile was created with correct tags and custom_args
     // Note: Need to create new app instance since oneshot consumes the router
@@ -1161,3 +1246,834 @@ async fn test_action_log_clear_removes_entries() {
     let entries_after: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
     assert!(entries_after.is_empty());
 }
+
+// ---------------------------------------------------------------------------
+// Bulk operations
+// ---------------------------------------------------------------------------
+
+fn make_state_with_tagged_profiles() -> ApiState {
+    let profiles: Vec<browsion_lib::config::BrowserProfile> = serde_json::from_value(serde_json::json!([
+        { "id": "bulk-a", "name": "A", "user_data_dir": "/tmp/bulk-a", "tags": ["shop-a"] },
+        { "id": "bulk-b", "name": "B", "user_data_dir": "/tmp/bulk-b", "tags": ["shop-b"] },
+        { "id": "bulk-c", "name": "C", "user_data_dir": "/tmp/bulk-c", "tags": ["shop-a"] }
+    ]))
+    .unwrap();
+    Arc::new(AppState::new(AppConfig {
+        profiles,
+        ..AppConfig::default()
+    }))
+}
+
+#[tokio::test]
+async fn test_api_bulk_kill_reports_per_profile_failures() {
+    let api_app = app(make_state_with_tagged_profiles(), None);
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/bulk/kill")
+        .header("content-type", "application/json")
+        .body(json_body(&serde_json::json!({ "tags": ["shop-a"] })))
+        .unwrap();
+    let res = api_app.oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let report: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(report["operation"], "kill");
+    assert_eq!(report["total"], 2);
+    assert_eq!(report["failed"], 2);
+    let results = report["results"].as_array().unwrap();
+    assert_eq!(results[0]["profile_id"], "bulk-a");
+    assert_eq!(results[1]["profile_id"], "bulk-c");
+    assert!(results.iter().all(|r| r["ok"] == false && r["error"].is_string()));
+}
+
+#[tokio::test]
+async fn test_api_bulk_empty_selector_rejected() {
+    let api_app = app(make_state_with_tagged_profiles(), None);
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/bulk/snapshot")
+        .header("content-type", "application/json")
+        .body(json_body(&serde_json::json!({ "tags": [], "name": "snap" })))
+        .unwrap();
+    let res = api_app.oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+// ---------------------------------------------------------------------------
+// Launch overrides
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_api_launch_rejects_malformed_overrides() {
+    let api_app = app(make_state_with_tagged_profiles(), None);
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/launch/bulk-a")
+        .header("content-type", "application/json")
+        .body(axum::body::Body::from(r#"{"window": {"width": "wide"}}"#))
+        .unwrap();
+    let res = api_app.oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+// ---------------------------------------------------------------------------
+// Extension library
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_api_extension_not_found_and_bad_install() {
+    let state = make_state();
+    let req = axum::http::Request::builder()
+        .uri("/api/extensions/no-such-extension-0000")
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/extensions/install")
+        .header("content-type", "application/json")
+        .body(json_body(&serde_json::json!({ "path": "/nonexistent/ext.crx" })))
+        .unwrap();
+    let res = app(state, None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+// ---------------------------------------------------------------------------
+// Proxy geography check
+// ---------------------------------------------------------------------------
+
+/// Local stand-in for an IP-info endpoint.
+async fn spawn_ip_info_mock(response: serde_json::Value) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mock = axum::Router::new().route(
+        "/json",
+        axum::routing::get(move || {
+            let response = response.clone();
+            async move { axum::Json(response) }
+        }),
+    );
+    tokio::spawn(async move {
+        axum::serve(listener, mock).await.unwrap();
+    });
+    format!("http://{}/json", addr)
+}
+
+#[tokio::test]
+async fn test_api_geo_check_reports_mismatches() {
+    let url = spawn_ip_info_mock(serde_json::json!({
+        "ip": "203.0.113.7",
+        "country": "DE",
+        "timezone": "Europe/Berlin"
+    }))
+    .await;
+    let state = make_state_with_tagged_profiles();
+    state.config.write().settings.geo_check.ip_info_url = url;
+
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/profiles/bulk-a/geo-check")
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let report: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(report["exit"]["country"], "DE");
+    assert_eq!(report["aligned"], false);
+    let fields: Vec<&str> = report["mismatches"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["field"].as_str().unwrap())
+        .collect();
+    assert_eq!(fields, vec!["timezone", "lang"]);
+
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/profiles/missing/geo-check")
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let res = app(state, None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_api_geo_align_keeps_grouped_profile_inheriting() {
+    let url = spawn_ip_info_mock(serde_json::json!({
+        "ip": "203.0.113.7",
+        "country": "FR",
+        "timezone": "Europe/Berlin"
+    }))
+    .await;
+    let state = make_state_with_tagged_profiles();
+    {
+        let mut config = state.config.write();
+        config.settings.geo_check.ip_info_url = url;
+        config.groups.push(browsion_lib::config::ProfileGroup {
+            id: "client".to_string(),
+            name: "Client".to_string(),
+            lang: Some("de-DE".to_string()),
+            timezone: Some("Europe/Berlin".to_string()),
+            ..Default::default()
+        });
+        config.profiles[0].group = Some("client".to_string());
+        config.profiles[0].lang = String::new();
+        config.profiles[0].timezone = None;
+    }
+
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/profiles/bulk-a/geo-check?auto_align=true")
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let report: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(report["aligned"], true);
+    let mismatches = report["mismatches"].as_array().unwrap();
+    assert_eq!(mismatches.len(), 1);
+    assert_eq!(mismatches[0]["field"], "lang");
+
+    // The mismatched language is stored; the matching timezone still comes from the group
+    let config = state.config.read();
+    assert_eq!(config.profiles[0].lang, mismatches[0]["expected"].as_str().unwrap());
+    assert_eq!(config.profiles[0].timezone, None);
+}
+
+// ---------------------------------------------------------------------------
+// Templates and cloning
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_api_create_profiles_from_template() {
+    let state = make_state();
+    let template = serde_json::json!({
+        "description": "Shop accounts",
+        "profile": {
+            "id": "tpl-shop-{{n}}",
+            "name": "Shop {{n}} {{city}}",
+            "user_data_dir": "/tmp/browsion-tpl-test/{{id}}",
+            "tags": ["shop"],
+            "custom_args": []
+        }
+    });
+    let req = axum::http::Request::builder()
+        .method("PUT")
+        .uri("/api/templates/shop")
+        .header("content-type", "application/json")
+        .body(json_body(&template))
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/profiles/from-template")
+        .header("content-type", "application/json")
+        .body(json_body(&serde_json::json!({
+            "template": "shop",
+            "count": 2,
+            "vars": { "city": "Berlin" }
+        })))
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let created: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(created.len(), 2);
+    assert_eq!(created[1]["id"], "tpl-shop-2");
+    assert_eq!(created[1]["name"], "Shop 2 Berlin");
+    assert_eq!(created[1]["user_data_dir"], "/tmp/browsion-tpl-test/tpl-shop-2");
+
+    // Same ids again: rejected as a whole
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/profiles/from-template")
+        .header("content-type", "application/json")
+        .body(json_body(&serde_json::json!({ "template": "shop", "vars": { "city": "Rome" } })))
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/profiles/tpl-shop-1/clone")
+        .header("content-type", "application/json")
+        .body(json_body(&serde_json::json!({ "id": "tpl-shop-1-copy" })))
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let clone: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(clone["name"], "Shop 1 Berlin Copy");
+    assert_ne!(clone["user_data_dir"], created[0]["user_data_dir"]);
+    assert_eq!(state.config.read().profiles.len(), 3);
+
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/profiles/missing/clone")
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let res = app(state, None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+// ---------------------------------------------------------------------------
+// Server-side profile ids and conflicts
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_api_add_profile_generates_id_and_rejects_conflicts() {
+    let state = make_state();
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/profiles")
+        .header("content-type", "application/json")
+        .body(json_body(&serde_json::json!({ "name": "No Id" })))
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let created: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let id = created["id"].as_str().unwrap().to_string();
+    assert!(uuid::Uuid::parse_str(&id).is_ok());
+    assert!(created["user_data_dir"]
+        .as_str()
+        .unwrap()
+        .ends_with(&format!(".browsion/profiles/{}", id)));
+
+    // Same id: 409
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/profiles")
+        .header("content-type", "application/json")
+        .body(json_body(&serde_json::json!({
+            "id": id,
+            "name": "Dup",
+            "user_data_dir": "/tmp/browsion-conflict-other"
+        })))
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+
+    // Data dir nested inside an existing profile's: 409
+    let nested = format!("{}/Default", created["user_data_dir"].as_str().unwrap());
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/profiles")
+        .header("content-type", "application/json")
+        .body(json_body(&serde_json::json!({ "name": "Nested", "user_data_dir": nested })))
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+    assert_eq!(state.config.read().profiles.len(), 1);
+}
+
+// ---------------------------------------------------------------------------
+// Partial updates (merge patch + If-Match)
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_api_patch_profile_with_if_match() {
+    let state = make_state_with_tagged_profiles();
+    let req = axum::http::Request::builder()
+        .uri("/api/profiles/bulk-a")
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let etag = res.headers()["etag"].to_str().unwrap().to_string();
+
+    let req = axum::http::Request::builder()
+        .method("PATCH")
+        .uri("/api/profiles/bulk-a")
+        .header("content-type", "application/json")
+        .header("if-match", &etag)
+        .body(json_body(&serde_json::json!({
+            "tags": ["shop-a", "patched"],
+            "proxy_server": "socks5://127.0.0.1:1080"
+        })))
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let new_etag = res.headers()["etag"].to_str().unwrap().to_string();
+    assert_ne!(new_etag, etag);
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let patched: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(patched["tags"], serde_json::json!(["shop-a", "patched"]));
+    assert_eq!(patched["name"], state.config.read().profiles[0].name);
+    assert_eq!(patched["warnings"], serde_json::json!([]));
+
+    // The old ETag is stale now
+    let req = axum::http::Request::builder()
+        .method("PATCH")
+        .uri("/api/profiles/bulk-a")
+        .header("content-type", "application/json")
+        .header("if-match", &etag)
+        .body(json_body(&serde_json::json!({ "proxy_server": null })))
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::PRECONDITION_FAILED);
+    assert!(state.config.read().profiles[0].proxy_server.is_some());
+
+    // Conflicting switches are saved, with warnings in the response
+    let req = axum::http::Request::builder()
+        .method("PATCH")
+        .uri("/api/profiles/bulk-a")
+        .header("content-type", "application/json")
+        .body(json_body(&serde_json::json!({
+            "custom_args": ["--window-size=800,600", "--window-size=1024,768"]
+        })))
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let patched: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let warnings = patched["warnings"].as_array().unwrap();
+    assert!(warnings.iter().any(|w| w.as_str().unwrap().contains("--window-size is given more than once")));
+
+    // The id cannot be patched
+    let req = axum::http::Request::builder()
+        .method("PATCH")
+        .uri("/api/profiles/bulk-a")
+        .header("content-type", "application/json")
+        .body(json_body(&serde_json::json!({ "id": "other" })))
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let req = axum::http::Request::builder()
+        .method("PATCH")
+        .uri("/api/profiles/missing")
+        .header("content-type", "application/json")
+        .body(json_body(&serde_json::json!({ "name": "x" })))
+        .unwrap();
+    let res = app(state, None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+// ---------------------------------------------------------------------------
+// Profile search and pagination
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_api_list_profiles_filter_and_paginate() {
+    let state = make_state_with_tagged_profiles();
+    let req = axum::http::Request::builder()
+        .uri("/api/profiles?tag=shop-a&sort=-id&limit=1")
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["x-total-count"], "2");
+    let cursor = res.headers()["x-next-cursor"].to_str().unwrap().to_string();
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let page: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0]["id"], "bulk-c");
+    assert_eq!(page[0]["is_running"], false);
+
+    let req = axum::http::Request::builder()
+        .uri(format!("/api/profiles?tag=shop-a&sort=-id&limit=1&cursor={}", cursor))
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(res.headers().get("x-next-cursor").is_none());
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let page: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(page[0]["id"], "bulk-a");
+
+    let req = axum::http::Request::builder()
+        .uri("/api/profiles?running=true")
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.headers()["x-total-count"], "0");
+
+    let req = axum::http::Request::builder()
+        .uri("/api/profiles?sort=color")
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let res = app(state, None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+// ---------------------------------------------------------------------------
+// Profile groups
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_api_groups_crud_and_move() {
+    let state = make_state_with_tagged_profiles();
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/groups")
+        .header("content-type", "application/json")
+        .body(json_body(&serde_json::json!({
+            "id": "client",
+            "name": "Client",
+            "proxy_server": "socks5://127.0.0.1:1080",
+            "lang": "de-DE"
+        })))
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/groups")
+        .header("content-type", "application/json")
+        .body(json_body(&serde_json::json!({ "name": "Project", "parent": "client" })))
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let project: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let project_id = project["id"].as_str().unwrap().to_string();
+
+    // A group cannot become its own ancestor
+    let req = axum::http::Request::builder()
+        .method("PUT")
+        .uri("/api/groups/client")
+        .header("content-type", "application/json")
+        .body(json_body(&serde_json::json!({ "name": "Client", "parent": project_id })))
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/profiles/bulk-a/move")
+        .header("content-type", "application/json")
+        .body(json_body(&serde_json::json!({ "group": project_id })))
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    {
+        let config = state.config.read();
+        let resolved = browsion_lib::commands::groups::resolve_profile(&config.profiles[0], &config.groups);
+        assert_eq!(resolved.proxy_server.as_deref(), Some("socks5://127.0.0.1:1080"));
+    }
+
+    // A profile created without a language takes the group's
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/profiles")
+        .header("content-type", "application/json")
+        .body(json_body(&serde_json::json!({
+            "id": "grouped",
+            "name": "Grouped",
+            "user_data_dir": "/tmp/browsion-grouped",
+            "group": project_id
+        })))
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    {
+        let config = state.config.read();
+        let profile = config.profiles.iter().find(|p| p.id == "grouped").unwrap();
+        assert_eq!(profile.lang, "");
+        let resolved = browsion_lib::commands::groups::resolve_profile(profile, &config.groups);
+        assert_eq!(resolved.lang, "de-DE");
+    }
+
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/profiles/bulk-a/move")
+        .header("content-type", "application/json")
+        .body(json_body(&serde_json::json!({ "group": "missing" })))
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    // Deleting the subgroup moves its profile up to the parent
+    let req = axum::http::Request::builder()
+        .method("DELETE")
+        .uri(format!("/api/groups/{}", project_id))
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+    assert_eq!(state.config.read().profiles[0].group.as_deref(), Some("client"));
+    assert_eq!(state.config.read().groups.len(), 1);
+}
+
+// ---------------------------------------------------------------------------
+// Export / import bundles
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_api_export_and_import_bundle() {
+    let state = make_state_with_tagged_profiles();
+    {
+        // Inherited settings travel with the bundle
+        let mut config = state.config.write();
+        config.groups.push(browsion_lib::config::ProfileGroup {
+            id: "client".to_string(),
+            name: "Client".to_string(),
+            lang: Some("de-DE".to_string()),
+            timezone: Some("Europe/Berlin".to_string()),
+            ..Default::default()
+        });
+        config.profiles[0].group = Some("client".to_string());
+    }
+    let req = axum::http::Request::builder()
+        .uri("/api/profiles/bulk-a/export")
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["content-type"], "application/zip");
+    let bundle = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+
+    // Same id already exists: conflict
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/profiles/import")
+        .body(axum::body::Body::from(bundle.clone()))
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/profiles/import?id=bulk-a-imported&name=Imported")
+        .body(axum::body::Body::from(bundle))
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let report: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(report["profile"]["id"], "bulk-a-imported");
+    assert_eq!(report["profile"]["tags"], serde_json::json!(["shop-a"]));
+    assert_eq!(report["original_id"], "bulk-a");
+    assert_eq!(report["profile"]["lang"], "de-DE");
+    assert_eq!(report["profile"]["timezone"], "Europe/Berlin");
+    assert_eq!(report["manifest"]["version"], 1);
+    assert_eq!(state.config.read().profiles.len(), 4);
+
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/profiles/import")
+        .body(axum::body::Body::from("not a zip"))
+        .unwrap();
+    let res = app(state, None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+// ---------------------------------------------------------------------------
+// Bulk import
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_api_bulk_import_dry_run_and_commit() {
+    let state = make_state_with_tagged_profiles();
+    let csv = "Account,Proxy,tags\nshop-1,http://127.0.0.1:8001,shop;eu\nshop-2,not a proxy,shop\n";
+    let import = |extra: serde_json::Value| {
+        let mut body = serde_json::json!({
+            "format": "csv",
+            "data": csv,
+            "mapping": { "Account": "name", "Proxy": "proxy_server" },
+            "defaults": { "lang": "de-DE" },
+        });
+        body.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+        axum::http::Request::builder()
+            .method("POST")
+            .uri("/api/bulk/import")
+            .header("content-type", "application/json")
+            .body(json_body(&body))
+            .unwrap()
+    };
+
+    let res = app(state.clone(), None)
+        .oneshot(import(serde_json::json!({ "dry_run": true })))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let report: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(report["total"], 2);
+    assert_eq!(report["valid"], 1);
+    assert!(report["rows"][1]["error"].is_string());
+    assert_eq!(report["profiles"][0]["tags"], serde_json::json!(["shop", "eu"]));
+    assert_eq!(report["profiles"][0]["lang"], "de-DE");
+    assert_eq!(state.config.read().profiles.len(), 3);
+
+    // One invalid row blocks the whole import
+    let res = app(state.clone(), None).oneshot(import(serde_json::json!({}))).await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(state.config.read().profiles.len(), 3);
+
+    let res = app(state.clone(), None)
+        .oneshot(import(serde_json::json!({ "allow_partial": true })))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let config = state.config.read();
+    assert_eq!(config.profiles.len(), 4);
+    assert_eq!(config.profiles[3].name, "shop-1");
+    assert_eq!(config.profiles[3].proxy_server.as_deref(), Some("http://127.0.0.1:8001"));
+}
+
+// ---------------------------------------------------------------------------
+// Trash
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_api_trash_restore_and_purge() {
+    let state = make_state_with_tagged_profiles();
+    let delete_a = || {
+        axum::http::Request::builder()
+            .method("DELETE")
+            .uri("/api/profiles/bulk-a")
+            .body(axum::body::Body::empty())
+            .unwrap()
+    };
+    // A profile being launched is not moved to the trash
+    let reservation = state.process_manager.reserve_profile("bulk-a").unwrap();
+    let res = app(state.clone(), None).oneshot(delete_a()).await.unwrap();
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+    drop(reservation);
+
+    let res = app(state.clone(), None).oneshot(delete_a()).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+    let entry_id = {
+        let config = state.config.read();
+        assert_eq!(config.profiles.len(), 2);
+        assert_eq!(config.trash.len(), 1);
+        assert_eq!(config.trash[0].profile.id, "bulk-a");
+        assert!(config.trash[0].data_path.is_none());
+        config.trash[0].id.clone()
+    };
+
+    // A new profile took the id: restoring conflicts
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/profiles")
+        .header("content-type", "application/json")
+        .body(json_body(&serde_json::json!({ "id": "bulk-a", "name": "New A", "user_data_dir": "/tmp/bulk-a-new" })))
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let restore = || {
+        axum::http::Request::builder()
+            .method("POST")
+            .uri(format!("/api/trash/{}/restore", entry_id))
+            .body(axum::body::Body::empty())
+            .unwrap()
+    };
+    let res = app(state.clone(), None).oneshot(restore()).await.unwrap();
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+
+    state.config.write().profiles.retain(|p| p.name != "New A");
+    let res = app(state.clone(), None).oneshot(restore()).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(state.config.read().trash.is_empty());
+    assert!(state.config.read().profiles.iter().any(|p| p.id == "bulk-a"));
+
+    // Purge
+    let req = axum::http::Request::builder()
+        .method("DELETE")
+        .uri("/api/profiles/bulk-b")
+        .body(axum::body::Body::empty())
+        .unwrap();
+    app(state.clone(), None).oneshot(req).await.unwrap();
+    let entry_id = state.config.read().trash[0].id.clone();
+    let purge = || {
+        axum::http::Request::builder()
+            .method("DELETE")
+            .uri(format!("/api/trash/{}", entry_id))
+            .body(axum::body::Body::empty())
+            .unwrap()
+    };
+    let res = app(state.clone(), None).oneshot(purge()).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+    assert!(state.config.read().trash.is_empty());
+    let res = app(state, None).oneshot(purge()).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+// ---------------------------------------------------------------------------
+// Storage
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_api_storage_report_and_dry_run_cleanup() {
+    let state = make_state_with_tagged_profiles();
+    let req = axum::http::Request::builder()
+        .uri("/api/storage")
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let res = app(state.clone(), None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let report: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert!(report["artifacts"].is_array());
+    assert!(report["total_bytes"].as_u64().unwrap() >= report["orphaned_bytes"].as_u64().unwrap());
+
+    let req = axum::http::Request::builder()
+        .method("POST")
+        .uri("/api/storage/cleanup")
+        .header("content-type", "application/json")
+        .body(json_body(&serde_json::json!({ "kinds": ["snapshots"] })))
+        .unwrap();
+    let res = app(state, None).oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let cleanup: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(cleanup["dry_run"], true);
+    assert!(cleanup["skipped_external"].is_array());
+    assert!(cleanup["removed"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|a| a["kind"] == "snapshots" && a["orphaned"] == true));
+}
+
+// ---------------------------------------------------------------------------
+// Profile cleaning
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_api_clean_profile_offline() {
+    let state = make_state_with_tagged_profiles();
+    let dir = std::env::temp_dir().join(format!("browsion-clean-api-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(dir.join("Default/Code Cache/js")).unwrap();
+    std::fs::write(dir.join("Default/Preferences"), b"{}").unwrap();
+    std::fs::write(dir.join("Default/Code Cache/js/index"), vec![0u8; 512]).unwrap();
+    std::fs::write(dir.join("Default/Cookies"), b"cookies").unwrap();
+    state.config.write().profiles[0].user_data_dir = dir.clone();
+
+    let clean = |id: &str| {
+        axum::http::Request::builder()
+            .method("POST")
+            .uri(format!("/api/profiles/{}/clean", id))
+            .header("content-type", "application/json")
+            .body(json_body(&serde_json::json!({})))
+            .unwrap()
+    };
+    let res = app(state.clone(), None).oneshot(clean("bulk-a")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let report: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(report["mode"], "offline");
+    assert_eq!(report["bytes_freed"], 512);
+    assert_eq!(report["categories"].as_array().unwrap().len(), 3);
+    assert!(!dir.join("Default/Code Cache").exists());
+    assert!(dir.join("Default/Cookies").exists());
+
+    // A profile being launched is not cleaned under the browser
+    let reservation = state.process_manager.reserve_profile("bulk-a").unwrap();
+    let res = app(state.clone(), None).oneshot(clean("bulk-a")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+    drop(reservation);
+
+    let res = app(state, None).oneshot(clean("missing")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
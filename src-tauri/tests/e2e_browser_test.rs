@@ -940,8 +940,18 @@ async fn test_lifecycle_launch_and_kill() {
         fingerprint: None,
         color: None,
         custom_args: Vec::new(),
+        extensions: vec![],
+        launch_flags: Default::default(),
         tags: Vec::new(),
+        group: None,
         headless: false,
+        window: Default::default(),
+        startup_urls: vec![],
+        display_mode: Default::default(),
+        env: Default::default(),
+        working_dir: None,
+        clear_env: false,
+        snapshot_exclude: vec![],
     };
 
     let state = make_state();
@@ -1031,8 +1041,18 @@ async fn test_profile_crud_via_api() {
         fingerprint: None,
         color: None,
         custom_args: Vec::new(),
+        extensions: vec![],
+        launch_flags: Default::default(),
         tags: Vec::new(),
+        group: None,
         headless: false,
+        window: Default::default(),
+        startup_urls: vec![],
+        display_mode: Default::default(),
+        env: Default::default(),
+        working_dir: None,
+        clear_env: false,
+        snapshot_exclude: vec![],
     };
 
     let create_resp = client
@@ -1572,8 +1592,18 @@ async fn test_snapshot_create_restore() {
         fingerprint: None,
         color: None,
         custom_args: Vec::new(),
+        extensions: vec![],
+        launch_flags: Default::default(),
         tags: Vec::new(),
+        group: None,
         headless: true,
+        window: Default::default(),
+        startup_urls: vec![],
+        display_mode: Default::default(),
+        env: Default::default(),
+        working_dir: None,
+        clear_env: false,
+        snapshot_exclude: vec![],
     };
 
     {
@@ -1774,8 +1804,18 @@ async fn test_action_log_records_api_calls() {
         fingerprint: None,
         color: None,
         custom_args: Vec::new(),
+        extensions: vec![],
+        launch_flags: Default::default(),
         tags: Vec::new(),
+        group: None,
         headless: true,
+        window: Default::default(),
+        startup_urls: vec![],
+        display_mode: Default::default(),
+        env: Default::default(),
+        working_dir: None,
+        clear_env: false,
+        snapshot_exclude: vec![],
     };
 
     let create_resp = http_client
@@ -2,35 +2,87 @@ import { invoke } from '@tauri-apps/api/core';
 import type {
   BrowserProfile,
   BrowserSource,
+  LaunchOverrides,
   CftVersionInfo,
   AppSettings,
   LocalApiConfig,
   RunningStatus,
   ProxyPreset,
   SnapshotInfo,
+  ArchiveCompression,
+  BulkSelector,
+  BulkReport,
+  BulkImportRequest,
+  BulkImportReport,
+  TrashEntry,
+  StorageReport,
+  CleanupRequest,
+  CleanupReport,
+  CleanCategory,
+  CleanReport,
+  DiscoveredBrowser,
+  ExtensionEntry,
+  GeoCheckReport,
+  ProfileTemplate,
+  TemplateSummary,
+  FromTemplateRequest,
+  CloneRequest,
+  ProfileQuery,
+  ProfilePage,
+  SavedProfile,
+  LaunchResult,
+  ProfileGroup,
+  ImportOptions,
+  ImportReport,
 } from '../types/profile';
 
 export const tauriApi = {
   // Profile management
-  async getProfiles(): Promise<BrowserProfile[]> {
-    return invoke('get_profiles');
+  async getProfiles(filter?: ProfileQuery): Promise<ProfilePage> {
+    return invoke('get_profiles', { filter });
   },
 
-  async addProfile(profile: BrowserProfile): Promise<void> {
+  async addProfile(profile: BrowserProfile): Promise<SavedProfile> {
     return invoke('add_profile', { profile });
   },
 
-  async updateProfile(profile: BrowserProfile): Promise<void> {
+  async updateProfile(profile: BrowserProfile): Promise<SavedProfile> {
     return invoke('update_profile', { profile });
   },
 
-  async deleteProfile(profileId: string): Promise<void> {
-    return invoke('delete_profile', { profileId });
+  async deleteProfile(profileId: string, moveData = false): Promise<void> {
+    return invoke('delete_profile', { profileId, moveData });
+  },
+
+  // Trash
+  async listTrash(): Promise<TrashEntry[]> {
+    return invoke('list_trash');
+  },
+
+  async restoreProfile(entryId: string): Promise<BrowserProfile> {
+    return invoke('restore_profile', { entryId });
+  },
+
+  async purgeTrash(entryId?: string): Promise<void> {
+    return invoke('purge_trash', { entryId });
+  },
+
+  async cleanProfile(profileId: string, categories?: CleanCategory[]): Promise<CleanReport> {
+    return invoke('clean_profile', { profileId, categories });
+  },
+
+  // Storage
+  async getStorageReport(): Promise<StorageReport> {
+    return invoke('get_storage_report');
+  },
+
+  async cleanupStorage(request: CleanupRequest): Promise<CleanupReport> {
+    return invoke('cleanup_storage', { request });
   },
 
   // Process management
-  async launchProfile(profileId: string): Promise<number> {
-    return invoke('launch_profile', { profileId });
+  async launchProfile(profileId: string, overrides?: LaunchOverrides): Promise<LaunchResult> {
+    return invoke('launch_profile', { profileId, overrides });
   },
 
   async activateProfile(profileId: string): Promise<void> {
@@ -45,6 +97,14 @@ export const tauriApi = {
     return invoke('get_running_profiles');
   },
 
+  async discoverBrowsers(): Promise<DiscoveredBrowser[]> {
+    return invoke('discover_browsers');
+  },
+
+  async attachProfile(profileId: string): Promise<number> {
+    return invoke('attach_profile', { profileId });
+  },
+
   // Settings
   async getChromePath(): Promise<string> {
     return invoke('get_chrome_path');
@@ -116,6 +176,59 @@ export const tauriApi = {
     return invoke('test_proxy', { url });
   },
 
+  // Templates and cloning
+  async listTemplates(): Promise<TemplateSummary[]> {
+    return invoke('list_templates');
+  },
+
+  async getTemplate(name: string): Promise<ProfileTemplate> {
+    return invoke('get_template', { name });
+  },
+
+  async saveTemplate(template: ProfileTemplate): Promise<void> {
+    return invoke('save_template', { template });
+  },
+
+  async deleteTemplate(name: string): Promise<void> {
+    return invoke('delete_template', { name });
+  },
+
+  async createProfilesFromTemplate(request: FromTemplateRequest): Promise<BrowserProfile[]> {
+    return invoke('create_profiles_from_template', { request });
+  },
+
+  async cloneProfile(profileId: string, request?: CloneRequest): Promise<BrowserProfile> {
+    return invoke('clone_profile', { profileId, request });
+  },
+
+  async listGroups(): Promise<ProfileGroup[]> {
+    return invoke('list_groups');
+  },
+
+  async saveGroup(group: ProfileGroup): Promise<ProfileGroup> {
+    return invoke('save_group', { group });
+  },
+
+  async deleteGroup(groupId: string): Promise<void> {
+    return invoke('delete_group', { groupId });
+  },
+
+  async moveProfileToGroup(profileId: string, group: string | null): Promise<BrowserProfile> {
+    return invoke('move_profile_to_group', { profileId, group });
+  },
+
+  async exportProfile(profileId: string, path: string, includeData = false): Promise<void> {
+    return invoke('export_profile', { profileId, path, includeData });
+  },
+
+  async importProfile(path: string, options?: ImportOptions): Promise<ImportReport> {
+    return invoke('import_profile', { path, options });
+  },
+
+  async checkProfileGeo(profileId: string, autoAlign = false): Promise<GeoCheckReport> {
+    return invoke('check_profile_geo', { profileId, autoAlign });
+  },
+
   // Snapshots
   async listSnapshots(profileId: string): Promise<SnapshotInfo[]> {
     return invoke('list_snapshots', { profileId });
@@ -125,11 +238,59 @@ export const tauriApi = {
     return invoke('create_snapshot', { profileId, name });
   },
 
-  async restoreSnapshot(profileId: string, name: string): Promise<void> {
+  /** Returns warnings, e.g. when the snapshot's Chrome version differs from the configured one. */
+  async restoreSnapshot(profileId: string, name: string): Promise<string[]> {
     return invoke('restore_snapshot', { profileId, name });
   },
 
   async deleteSnapshot(profileId: string, name: string): Promise<void> {
     return invoke('delete_snapshot', { profileId, name });
   },
+
+  async exportSnapshot(
+    profileId: string,
+    name: string,
+    path: string,
+    compression?: ArchiveCompression
+  ): Promise<void> {
+    return invoke('export_snapshot', { profileId, name, path, compression });
+  },
+
+  async importSnapshot(profileId: string, path: string, name?: string): Promise<SnapshotInfo> {
+    return invoke('import_snapshot', { profileId, path, name });
+  },
+
+  // Bulk operations
+  async bulkLaunch(selector: BulkSelector): Promise<BulkReport> {
+    return invoke('bulk_launch', { selector });
+  },
+
+  async bulkKill(selector: BulkSelector): Promise<BulkReport> {
+    return invoke('bulk_kill', { selector });
+  },
+
+  async bulkSnapshot(selector: BulkSelector, name: string): Promise<BulkReport> {
+    return invoke('bulk_snapshot', { selector, name });
+  },
+
+  async bulkRestore(selector: BulkSelector, name: string): Promise<BulkReport> {
+    return invoke('bulk_restore', { selector, name });
+  },
+
+  async bulkImportProfiles(request: BulkImportRequest): Promise<BulkImportReport> {
+    return invoke('bulk_import_profiles', { request });
+  },
+
+  // Extension library
+  async listExtensions(): Promise<ExtensionEntry[]> {
+    return invoke('list_extensions');
+  },
+
+  async installExtension(path: string, id?: string): Promise<ExtensionEntry> {
+    return invoke('install_extension', { path, id });
+  },
+
+  async removeExtension(id: string): Promise<void> {
+    return invoke('remove_extension', { id });
+  },
 };
@@ -1,5 +1,6 @@
 import React, { useState, useEffect } from 'react';
 import { tauriApi } from '../api/tauri';
+import { useToast } from './Toast';
 import type { BrowserProfile, BrowserSource, ProxyPreset } from '../types/profile';
 import { v4 as uuidv4 } from 'uuid';
 import ISO6391 from 'iso-639-1';
@@ -92,19 +93,21 @@ export const ProfileForm: React.FC<ProfileFormProps> = ({
   onSave,
   onCancel,
 }) => {
+  const { showToast } = useToast();
   const [formData, setFormData] = useState<BrowserProfile>({
     id: '',
     name: '',
     description: '',
     user_data_dir: '',
     proxy_server: '',
-    lang: 'en-US',
+    lang: '',
     color: '#3498DB',
     custom_args: [],
     tags: [],
   });
   const [tagsText, setTagsText] = useState('');
   const [customArgsText, setCustomArgsText] = useState('');
+  const [snapshotExcludeText, setSnapshotExcludeText] = useState('');
   const [browserSource, setBrowserSource] = useState<BrowserSource | null>(null);
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
@@ -150,9 +153,11 @@ export const ProfileForm: React.FC<ProfileFormProps> = ({
         (arg) => !presetSet.has(arg)
       );
       setCustomArgsText(additional.join('\n'));
+      setSnapshotExcludeText((profile.snapshot_exclude || []).join('\n'));
     } else {
       setFormData((prev) => ({ ...prev, id: uuidv4() }));
       setCustomArgsText('');
+      setSnapshotExcludeText('');
     }
   }, [profile]);
 
@@ -172,23 +177,31 @@ export const ProfileForm: React.FC<ProfileFormProps> = ({
         .split('\n')
         .map((line) => line.trim())
         .filter((line) => line.length > 0);
+      const snapshotExclude = snapshotExcludeText
+        .split('\n')
+        .map((line) => line.trim())
+        .filter((line) => line.length > 0);
       const profileData: BrowserProfile = {
         ...formData,
         proxy_server: formData.proxy_server || undefined,
         color: formData.color || undefined,
         tags,
         custom_args: [...getPresetArgs(), ...additionalArgs],
+        snapshot_exclude: snapshotExclude.length > 0 ? snapshotExclude : undefined,
         timezone: formData.timezone || undefined,
-        fingerprint: formData.fingerprint || undefined,
+        fingerprint:
+          formData.fingerprint && Object.values(formData.fingerprint).some((v) => v !== undefined)
+            ? formData.fingerprint
+            : undefined,
       };
 
       // If original profile has no ID or is a clone, treat as new
       const isClone = profile && !profile.id;
-      if (profile && profile.id && !isClone) {
-        await tauriApi.updateProfile(profileData);
-      } else {
-        await tauriApi.addProfile(profileData);
-      }
+      const saved =
+        profile && profile.id && !isClone
+          ? await tauriApi.updateProfile(profileData)
+          : await tauriApi.addProfile(profileData);
+      saved.warnings.forEach((warning) => showToast(warning, 'warning'));
 
       onSave();
     } catch (err) {
@@ -232,15 +245,14 @@ export const ProfileForm: React.FC<ProfileFormProps> = ({
                 </div>
 
                 <div className="form-group">
-                  <label htmlFor="user_data_dir">User Data Directory *</label>
+                  <label htmlFor="user_data_dir">User Data Directory</label>
                   <input
                     type="text"
                     id="user_data_dir"
                     name="user_data_dir"
                     value={formData.user_data_dir}
                     onChange={handleChange}
-                    required
-                    placeholder="/home/percy/google_profile/10000"
+                    placeholder="~/.browsion/profiles/<id> when empty"
                   />
                 </div>
 
@@ -269,6 +281,7 @@ export const ProfileForm: React.FC<ProfileFormProps> = ({
                           setFormData((prev) => ({ ...prev, lang: e.target.value }))
                         }
                       >
+                        <option value="">Inherit (group or en-US)</option>
                         {LANGUAGES.map((lang) => (
                           <option key={lang.code} value={lang.code}>
                             {lang.name}
@@ -284,7 +297,7 @@ export const ProfileForm: React.FC<ProfileFormProps> = ({
                           value={formData.lang}
                           onChange={handleChange}
                           list="lang-list"
-                          placeholder="en-US"
+                          placeholder="Inherit (group or en-US)"
                         />
                         <datalist id="lang-list">
                           {LANGUAGES.map((lang) => (
@@ -312,18 +325,22 @@ export const ProfileForm: React.FC<ProfileFormProps> = ({
                 {isFingerprintChromium && (
                   <div className="form-row">
                     <div className="form-group">
-                      <label htmlFor="fingerprint">Fingerprint (--fingerprint)</label>
+                      <label htmlFor="fingerprint">Fingerprint seed (--fingerprint)</label>
                       <input
-                        type="text"
+                        type="number"
                         id="fingerprint"
                         name="fingerprint"
-                        value={formData.fingerprint ?? ''}
-                        onChange={(e) =>
+                        min={0}
+                        max={4294967295}
+                        value={formData.fingerprint?.seed ?? ''}
+                        onChange={(e) => {
+                          const raw = e.target.value.trim();
+                          const seed = raw === '' ? undefined : Number(raw);
                           setFormData((prev) => ({
                             ...prev,
-                            fingerprint: e.target.value.trim() || undefined,
-                          }))
-                        }
+                            fingerprint: { ...prev.fingerprint, seed },
+                          }));
+                        }}
                         placeholder="e.g. 1000 (32-bit integer seed)"
                       />
                     </div>
@@ -413,6 +430,18 @@ export const ProfileForm: React.FC<ProfileFormProps> = ({
                     className="custom-args-textarea"
                   />
                 </div>
+
+                <div className="form-group">
+                  <label htmlFor="snapshot_exclude">Snapshot Exclusions (one glob per line)</label>
+                  <textarea
+                    id="snapshot_exclude"
+                    value={snapshotExcludeText}
+                    onChange={(e) => setSnapshotExcludeText(e.target.value)}
+                    placeholder="!Default/History*&#10;Default/Service Worker"
+                    rows={3}
+                    className="custom-args-textarea"
+                  />
+                </div>
               </div>
             </div>
           </form>
@@ -48,7 +48,7 @@ export const ProfileList: React.FC<ProfileListProps> = React.memo(({ onEditProfi
         tauriApi.getProfiles(),
         tauriApi.getRunningProfiles(),
       ]);
-      setProfiles((prev) => mergeProfilesById(prev, profilesData));
+      setProfiles((prev) => mergeProfilesById(prev, profilesData.profiles));
       setRunningStatus((prev) =>
         areRunningStatusesEqual(prev, statusData) ? prev : statusData
       );
@@ -67,7 +67,7 @@ export const ProfileList: React.FC<ProfileListProps> = React.memo(({ onEditProfi
         tauriApi.getProfiles(),
         tauriApi.getRunningProfiles(),
       ]);
-      setProfiles((prev) => mergeProfilesById(prev, profilesData));
+      setProfiles((prev) => mergeProfilesById(prev, profilesData.profiles));
       setRunningStatus((prev) =>
         areRunningStatusesEqual(prev, statusData) ? prev : statusData
       );
@@ -122,12 +122,13 @@ export const ProfileList: React.FC<ProfileListProps> = React.memo(({ onEditProfi
   const handleLaunch = useCallback(async (id: string) => {
     setLaunchingId(id);
     try {
-      await tauriApi.launchProfile(id);
+      const launched = await tauriApi.launchProfile(id);
       const status = await tauriApi.getRunningProfiles();
       setRunningStatus((prev) =>
         areRunningStatusesEqual(prev, status) ? prev : status
       );
       showToast('Browser launched', 'success');
+      launched.warnings.forEach((warning) => showToast(warning, 'warning'));
     } catch (err) {
       showToast(`Failed to launch: ${err}`, 'error');
     } finally {
@@ -166,7 +167,7 @@ export const ProfileList: React.FC<ProfileListProps> = React.memo(({ onEditProfi
 
   const handleDelete = useCallback((id: string) => {
     setConfirmState({
-      message: 'Delete this profile? This cannot be undone.',
+      message: 'Move this profile to the trash? It can be restored until the trash is purged.',
       confirmLabel: 'Delete',
       confirmClassName: 'btn btn-danger',
       onConfirm: async () => {
@@ -174,7 +175,7 @@ export const ProfileList: React.FC<ProfileListProps> = React.memo(({ onEditProfi
         try {
           await tauriApi.deleteProfile(id);
           await refreshProfilesSilent();
-          showToast('Profile deleted', 'success');
+          showToast('Profile moved to trash', 'success');
         } catch (err) {
           showToast(`Failed to delete: ${err}`, 'error');
         }
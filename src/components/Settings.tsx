@@ -287,7 +287,7 @@ export const Settings: React.FC = React.memo(() => {
 
   const handleSettingsChange = async (
     field: keyof AppSettings,
-    value: boolean
+    value: boolean | number | string[]
   ) => {
     const newSettings = { ...settings, [field]: value };
     setSettings(newSettings);
@@ -540,6 +540,51 @@ export const Settings: React.FC = React.memo(() => {
               <span>Minimize to tray when closing window</span>
             </label>
           </div>
+
+          <div className="form-group">
+            <label>Purge deleted profiles after (days, 0 = never)</label>
+            <input
+              type="number"
+              min={0}
+              value={settings.trash_retention_days ?? 30}
+              onChange={(e) =>
+                handleSettingsChange('trash_retention_days', Math.max(0, Number(e.target.value) || 0))
+              }
+            />
+          </div>
+
+          <div className="form-group">
+            <label>Largest API upload (MiB, 0 = no limit)</label>
+            <input
+              type="number"
+              min={0}
+              value={settings.max_upload_mb ?? 4096}
+              onChange={(e) =>
+                handleSettingsChange('max_upload_mb', Math.max(0, Number(e.target.value) || 0))
+              }
+            />
+          </div>
+
+          <div className="form-group">
+            <label>Snapshot exclusion rules (one glob per line)</label>
+            <textarea
+              rows={6}
+              key={(settings.snapshot_exclude ?? []).join('\n')}
+              defaultValue={(settings.snapshot_exclude ?? []).join('\n')}
+              onBlur={(e) =>
+                handleSettingsChange(
+                  'snapshot_exclude',
+                  e.target.value.split('\n').map((line) => line.trim()).filter(Boolean)
+                )
+              }
+              placeholder={'*\n!Default\n!Default/Cookies*'}
+            />
+            <p className="settings-hint">
+              A name matches at any depth, a path (e.g. <code>Default/History*</code>) from the profile root;
+              <code>!rule</code> keeps what earlier rules exclude. The defaults exclude everything (<code>*</code>) and keep
+              session data. Profiles can add their own rules.
+            </p>
+          </div>
         </div>
 
         <div className="settings-section">
@@ -64,10 +64,14 @@ export const SnapshotModal: React.FC<SnapshotModalProps> = ({
     try {
       setCreating(true);
       setError(null);
-      await tauriApi.createSnapshot(profileId, name);
+      const info = await tauriApi.createSnapshot(profileId, name);
       setNewName('');
-      setSuccess(`Snapshot "${name}" created`);
-      setTimeout(() => setSuccess(null), UI_CONSTANTS.SUCCESS_MESSAGE_DURATION_MS);
+      if (info.warnings?.length) {
+        setSuccess(`Snapshot "${name}" created. ${info.warnings.join(' ')}`);
+      } else {
+        setSuccess(`Snapshot "${name}" created`);
+        setTimeout(() => setSuccess(null), UI_CONSTANTS.SUCCESS_MESSAGE_DURATION_MS);
+      }
       await loadSnapshots();
     } catch (err) {
       setError(err instanceof Error ? err.message : String(err));
@@ -84,9 +88,14 @@ export const SnapshotModal: React.FC<SnapshotModalProps> = ({
         try {
           setRestoring(name);
           setError(null);
-          await tauriApi.restoreSnapshot(profileId, name);
-          setSuccess(`Restored snapshot "${name}"`);
-          setTimeout(() => setSuccess(null), UI_CONSTANTS.SUCCESS_MESSAGE_DURATION_MS);
+          const warnings = await tauriApi.restoreSnapshot(profileId, name);
+          if (warnings.length > 0) {
+            // Keep version warnings on screen until the next action
+            setSuccess(`Restored snapshot "${name}". ${warnings.join(' ')}`);
+          } else {
+            setSuccess(`Restored snapshot "${name}"`);
+            setTimeout(() => setSuccess(null), UI_CONSTANTS.SUCCESS_MESSAGE_DURATION_MS);
+          }
         } catch (err) {
           setError(err instanceof Error ? err.message : String(err));
         } finally {
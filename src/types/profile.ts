@@ -6,13 +6,87 @@ export interface BrowserProfile {
   proxy_server?: string;
   lang: string;
   timezone?: string;
-  fingerprint?: string;
+  fingerprint?: FingerprintConfig;
   color?: string;
   custom_args: string[];
+  extensions?: string[];
+  launch_flags?: LaunchFlags;
+  window?: WindowSettings;
+  startup_urls?: string[];
   tags: string[];
+  /** ProfileGroup id; unset proxy/timezone/color and an empty lang are inherited. */
+  group?: string;
   headless?: boolean;
+  display_mode?: DisplayMode;
+  env?: Record<string, string>;
+  working_dir?: string;
+  clear_env?: boolean;
+  /** Snapshot exclusion globs, applied after the global `snapshot_exclude` rules. */
+  snapshot_exclude?: string[];
 }
 
+/** A saved profile with its launch flag and fingerprint warnings. */
+export interface SavedProfile extends BrowserProfile {
+  warnings: string[];
+}
+
+export interface LaunchResult {
+  pid: number;
+  cdp_port: number;
+  /** Launch flag and fingerprint warnings. */
+  warnings: string[];
+}
+
+export type FingerprintPlatform = 'windows' | 'linux' | 'macos';
+
+export type WebRtcPolicy =
+  | 'default'
+  | 'default_public_and_private_interfaces'
+  | 'default_public_interface_only'
+  | 'disable_non_proxied_udp';
+
+/** fingerprint-chromium settings; unset fields are derived from the seed. */
+export interface FingerprintConfig {
+  seed?: number;
+  platform?: FingerprintPlatform;
+  platform_version?: string;
+  brand?: string;
+  brand_version?: string;
+  hardware_concurrency?: number;
+  gpu_vendor?: string;
+  gpu_renderer?: string;
+  screen?: { width: number; height: number };
+  webrtc_policy?: WebRtcPolicy;
+}
+
+export type WindowMode = 'normal' | 'maximized' | 'kiosk' | 'app';
+
+export interface WindowSettings {
+  width?: number;
+  height?: number;
+  x?: number;
+  y?: number;
+  mode: WindowMode;
+  device_scale_factor?: number;
+}
+
+/** One-off launch changes; the stored profile is not modified. */
+export interface LaunchOverrides {
+  window?: WindowSettings;
+  startup_urls?: string[];
+}
+
+export type FlagPreset = 'stealth' | 'minimal' | 'testing';
+
+export interface LaunchFlags {
+  preset: FlagPreset;
+  disabled?: string[];
+  enable_features?: string[];
+  disable_features?: string[];
+}
+
+export type DisplayMode = 'window' | 'headless' | 'virtual';
+
 export type CftChannel = 'Stable' | 'Beta' | 'Dev' | 'Canary';
 
 export type BrowserSource =
@@ -40,6 +114,27 @@ export interface AppConfig {
 export interface AppSettings {
   auto_start: boolean;
   minimize_to_tray: boolean;
+  virtual_display?: VirtualDisplaySettings;
+  geo_check?: GeoCheckSettings;
+  trash_retention_days?: number;
+  /** Globs for what snapshots leave out; `!rule` keeps what earlier rules exclude. */
+  snapshot_exclude?: string[];
+  /** Largest snapshot archive or bundle the HTTP API accepts, in MiB (0 = no limit). */
+  max_upload_mb?: number;
+}
+
+export type GeoCheckMode = 'off' | 'warn' | 'block' | 'align';
+
+export interface GeoCheckSettings {
+  ip_info_url: string;
+  before_launch: GeoCheckMode;
+}
+
+export interface VirtualDisplaySettings {
+  server: 'xvfb' | 'xephyr';
+  screen: string;
+  pool_size: number;
+  first_display: number;
 }
 
 export type RunningStatus = Record<string, boolean>;
@@ -53,11 +148,262 @@ export interface ProxyPreset {
 export interface SnapshotInfo {
   name: string;
   created_at_ts: number;
+  /** Logical size of the snapshotted files. */
   size_bytes: number;
+  /** Bytes only this snapshot uses; freed when it is deleted. */
+  unique_bytes: number;
+  storage: 'copy' | 'chunked';
+  /** Chrome version that last used the profile when the snapshot was taken. */
+  chrome_version?: string;
+  /** What was left out while taking the snapshot, e.g. links pointing outside the profile. */
+  warnings?: string[];
 }
 
+export type ArchiveCompression = 'deflate' | 'zstd' | 'stored';
+
 export interface LocalApiConfig {
   enabled: boolean;
   api_port: number;
   api_key?: string;
 }
+
+export interface DiscoveredBrowser {
+  pid: number;
+  cdp_port: number;
+  user_data_dir?: string;
+  ws_path?: string;
+  exe?: string;
+  profile_id: string | null;
+  managed: boolean;
+}
+
+export interface BulkSelector {
+  tags?: string[];
+  profile_ids?: string[];
+}
+
+export type BulkOperation = 'launch' | 'kill' | 'snapshot' | 'restore';
+
+export interface BulkItemResult {
+  profile_id: string;
+  ok: boolean;
+  error?: string;
+  pid?: number;
+  cdp_port?: number;
+  warnings?: string[];
+}
+
+export interface BulkReport {
+  operation: BulkOperation;
+  total: number;
+  succeeded: number;
+  failed: number;
+  results: BulkItemResult[];
+}
+
+export interface TrashEntry {
+  id: string;
+  profile: BrowserProfile;
+  deleted_at_ts: number;
+  data_path?: string;
+}
+
+export type CleanCategory =
+  | 'http_cache'
+  | 'code_cache'
+  | 'service_workers'
+  | 'history'
+  | 'downloads'
+  | 'crash_dumps';
+
+export interface CleanCategoryResult {
+  category: CleanCategory;
+  bytes_freed: number;
+  skipped?: string;
+}
+
+export interface CleanReport {
+  profile_id: string;
+  mode: 'offline' | 'cdp';
+  bytes_freed: number;
+  categories: CleanCategoryResult[];
+}
+
+export type ArtifactKind = 'profile_data' | 'snapshots' | 'snapshot_blobs' | 'cft_version' | 'trash';
+
+export interface StorageArtifact {
+  kind: ArtifactKind;
+  path: string;
+  size_bytes: number;
+  owner?: string;
+  orphaned: boolean;
+  external: boolean;
+}
+
+export interface StorageReport {
+  artifacts: StorageArtifact[];
+  total_bytes: number;
+  orphaned_bytes: number;
+}
+
+export interface CleanupRequest {
+  kinds?: ArtifactKind[];
+  /** Defaults to true. */
+  dry_run?: boolean;
+  include_external?: boolean;
+}
+
+export interface CleanupReport {
+  dry_run: boolean;
+  removed: StorageArtifact[];
+  freed_bytes: number;
+  skipped_external: StorageArtifact[];
+  errors: string[];
+}
+
+export interface BulkImportRequest {
+  format?: 'csv' | 'jsonl';
+  data: string;
+  mapping?: Record<string, string>;
+  defaults?: Partial<BrowserProfile>;
+  dry_run?: boolean;
+  allow_partial?: boolean;
+}
+
+export interface ImportRowResult {
+  row: number;
+  id?: string;
+  error?: string;
+}
+
+export interface BulkImportReport {
+  dry_run: boolean;
+  committed: boolean;
+  total: number;
+  valid: number;
+  rows: ImportRowResult[];
+  profiles: BrowserProfile[];
+}
+
+export interface ExtensionEntry {
+  id: string;
+  name: string;
+  version: string;
+  manifest_version: number;
+  source: string;
+  installed_at_ts: number;
+  used_by: string[];
+}
+
+export interface GeoInfo {
+  ip?: string;
+  country?: string;
+  timezone?: string;
+  languages?: string[];
+}
+
+export interface GeoMismatch {
+  field: 'timezone' | 'lang';
+  current?: string;
+  expected: string;
+}
+
+export interface GeoCheckReport {
+  profile_id: string;
+  proxy_server?: string;
+  exit: GeoInfo;
+  mismatches: GeoMismatch[];
+  aligned: boolean;
+}
+
+/** Profile blueprint; string fields may use {{n}}, {{id}} and custom {{vars}}. */
+export interface ProfileTemplate {
+  name: string;
+  description?: string;
+  profile: BrowserProfile;
+}
+
+export interface TemplateSummary {
+  name: string;
+  description: string;
+  /** Placeholders used by the template, besides `n` and `id`. */
+  variables: string[];
+}
+
+export interface FromTemplateRequest {
+  template: string;
+  count?: number;
+  start?: number;
+  vars?: Record<string, string>;
+  instances?: Record<string, string>[];
+}
+
+export interface CloneRequest {
+  id?: string;
+  name?: string;
+  user_data_dir?: string;
+  copy_data?: boolean;
+}
+
+export type ProfileSort = 'config' | 'name' | '-name' | 'id' | '-id';
+
+/** Profile list filters; every given filter must match. */
+export interface ProfileQuery {
+  /** Comma-separated; a profile must have all of these tags. */
+  tag?: string;
+  name?: string;
+  running?: boolean;
+  proxy_preset?: string;
+  color?: string;
+  sort?: ProfileSort;
+  /** `next_cursor` of the previous page. */
+  cursor?: string;
+  limit?: number;
+}
+
+export interface ProfilePage {
+  profiles: BrowserProfile[];
+  /** Ids of the running profiles on this page. */
+  running: string[];
+  /** Matches across all pages. */
+  total: number;
+  /** Pass as `cursor` for the next page; null on the last page. */
+  next_cursor: string | null;
+}
+
+/** Nested profile folder; members inherit its proxy, lang, timezone and color. */
+export interface ProfileGroup {
+  id: string;
+  name: string;
+  parent?: string;
+  color?: string;
+  proxy_server?: string;
+  lang?: string;
+  timezone?: string;
+}
+
+export interface ImportOptions {
+  id?: string;
+  name?: string;
+  user_data_dir?: string;
+  /** Import under a generated id when the bundled one is taken. */
+  rename_on_conflict?: boolean;
+}
+
+export interface BundleManifest {
+  format: string;
+  version: number;
+  app_version: string;
+  exported_at_ts: number;
+  profile_id: string;
+  includes_data: boolean;
+}
+
+export interface ImportReport {
+  profile: BrowserProfile;
+  manifest: BundleManifest;
+  original_id?: string;
+  proxy_presets_added: string[];
+  data_restored: boolean;
+  warnings: string[];
+}
@@ -54,9 +54,18 @@ export function areProfilesEqual(a: BrowserProfile, b: BrowserProfile): boolean
     a.proxy_server !== b.proxy_server ||
     a.lang !== b.lang ||
     a.timezone !== b.timezone ||
-    a.fingerprint !== b.fingerprint ||
+    JSON.stringify(a.fingerprint ?? null) !== JSON.stringify(b.fingerprint ?? null) ||
     a.color !== b.color ||
-    a.headless !== b.headless
+    a.group !== b.group ||
+    a.headless !== b.headless ||
+    (a.display_mode ?? 'window') !== (b.display_mode ?? 'window') ||
+    a.working_dir !== b.working_dir ||
+    Boolean(a.clear_env) !== Boolean(b.clear_env) ||
+    JSON.stringify(a.env ?? {}) !== JSON.stringify(b.env ?? {}) ||
+    JSON.stringify(a.launch_flags ?? null) !== JSON.stringify(b.launch_flags ?? null) ||
+    JSON.stringify(a.extensions ?? []) !== JSON.stringify(b.extensions ?? []) ||
+    JSON.stringify(a.window ?? null) !== JSON.stringify(b.window ?? null) ||
+    JSON.stringify(a.startup_urls ?? []) !== JSON.stringify(b.startup_urls ?? [])
   ) {
     return false;
   }